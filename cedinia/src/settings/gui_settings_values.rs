@@ -135,6 +135,9 @@ impl StringComboBoxItems {
             ("blake3", "Blake3", HashType::Blake3),
             ("crc32", "CRC32", HashType::Crc32),
             ("xxh3", "XXH3", HashType::Xxh3),
+            ("sha256", "SHA-256", HashType::Sha256),
+            ("sha1", "SHA-1", HashType::Sha1),
+            ("md5", "MD5", HashType::Md5),
         ]);
 
         let hash_size = Self::convert(&[("8", "8", 8u8), ("16", "16", 16), ("32", "32", 32), ("64", "64", 64)]);
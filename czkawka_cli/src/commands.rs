@@ -1,11 +1,16 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
+use clap::Parser;
 #[cfg(not(feature = "no_colors"))]
 use clap::builder::Styles;
 #[cfg(not(feature = "no_colors"))]
 use clap::builder::styling::AnsiColor;
 use czkawka_core::CZKAWKA_VERSION;
+use czkawka_core::common::cache::CacheBackend;
+use czkawka_core::common::date_filter::parse_date_limit;
 use czkawka_core::common::model::{CheckingMethod, HashType};
+use czkawka_core::common::select_rules::SelectionRule;
 use czkawka_core::common::tool_data::DeleteMethod;
 use czkawka_core::re_exported::{FilterType, HashAlg};
 use czkawka_core::tools::broken_files::CheckedTypes;
@@ -18,14 +23,17 @@ use czkawka_core::tools::similar_videos::{
 };
 use czkawka_core::tools::video_optimizer::{NoiseReductionMethod, VideoCodec};
 use log::error;
+use serde_json::Value;
 
 use crate::parsers::{
-    parse_audio_length_ratio, parse_audio_maximum_difference, parse_audio_similarity_percent, parse_broken_files, parse_checking_method_duplicate,
+    parse_audio_length_ratio, parse_audio_maximum_difference, parse_audio_similarity_percent, parse_broken_files, parse_cache_backend, parse_checking_method_duplicate,
     parse_checking_method_same_music, parse_crop_mechanism, parse_delete_method, parse_duration_tolerance_pct, parse_geometric_invariance, parse_hash_type, parse_image_hash_size,
-    parse_match_fraction, parse_max_samples, parse_maximal_file_size, parse_maximum_difference, parse_min_crop_size, parse_minimal_file_size, parse_minimum_segment_duration,
-    parse_music_duplicate_type, parse_noise_reduction, parse_scan_duration, parse_similar_hash_algorithm, parse_similar_image_filter, parse_skip_forward_amount, parse_tolerance,
-    parse_video_codec, parse_window_count,
+    parse_loopback_address, parse_match_fraction, parse_max_samples, parse_maximal_file_size, parse_maximum_difference, parse_min_crop_size, parse_minimal_file_size,
+    parse_minimum_segment_duration, parse_music_duplicate_type, parse_noise_reduction, parse_progress_format, parse_scan_duration, parse_similar_hash_algorithm,
+    parse_similar_image_filter, parse_skip_forward_amount, parse_tolerance, parse_video_codec, parse_watch_event_format, parse_window_count,
 };
+use crate::progress::ProgressFormat;
+use crate::watch::WatchEventFormat;
 
 #[cfg(not(feature = "no_colors"))]
 pub const CLAP_STYLING: Styles = Styles::styled()
@@ -56,6 +64,23 @@ const LONG_VERSION: &str = concat!(
 pub struct Args {
     #[command(subcommand)]
     pub command: Commands,
+    #[clap(
+        long,
+        global = true,
+        value_parser = parse_progress_format,
+        default_value = "BARS",
+        help = "Progress format (BARS, JSON)",
+        long_help = "Format of progress updates. BARS shows progress bars in terminal. JSON writes one JSON object per line for every update, with stage, stage index, checked entries and bytes and estimated remaining time in seconds, so wrapper scripts can parse it. JSON updates are written to stderr or to file descriptor set with --progress-fd."
+    )]
+    pub progress_format: ProgressFormat,
+    #[clap(
+        long,
+        global = true,
+        value_name = "fd",
+        help = "File descriptor for JSON progress",
+        long_help = "Already opened file descriptor to which JSON progress updates are written instead of stderr, e.g. `--progress-fd 3 3>progress.jsonl`. Only available on Unix systems."
+    )]
+    pub progress_fd: Option<u32>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -66,6 +91,12 @@ pub enum Commands {
         after_help = "EXAMPLE:\n    czkawka dup -d /home/rafal -e /home/rafal/Obrazy  -m 25 -x 7z rar IMAGE -s hash -f results.txt -D aeo"
     )]
     Duplicates(DuplicatesArgs),
+    #[clap(
+        name = "dup-folders",
+        about = "Finds folders with identical content",
+        after_help = "EXAMPLE:\n    czkawka dup-folders -d /home/rafal/Photos /mnt/backup -m 0 -f results.txt -D aeo"
+    )]
+    DuplicateFolders(DuplicateFoldersArgs),
     #[clap(
         name = "empty-folders",
         about = "Finds empty folders",
@@ -136,8 +167,47 @@ pub enum Commands {
         after_help = "EXAMPLE:\n    czkawka exif-remover -d /home/rafal -f results.txt"
     )]
     ExifRemover(ExifRemoverArgs),
+    #[clap(
+        name = "undo",
+        about = "Reverts operations recorded in journal",
+        after_help = "EXAMPLE:\n    czkawka undo /home/rafal/czkawka_journal.jsonl -Q"
+    )]
+    Undo(UndoArgs),
+    #[clap(
+        name = "restore",
+        about = "Restores items from quarantine folder",
+        after_help = "EXAMPLE:\n    czkawka restore /mnt/backup/czkawka_quarantine -Q"
+    )]
+    Restore(RestoreArgs),
+    #[clap(
+        name = "cache",
+        about = "Inspects, cleans, prunes, exports and imports cache files",
+        after_help = "EXAMPLE:\n    czkawka cache list\n    czkawka cache prune --older-than 90d\n    czkawka cache export --json /home/rafal/cache.json"
+    )]
+    Cache(CacheArgs),
+    #[clap(
+        name = "watch",
+        about = "Watches directories and reports new duplicates or similar images as files change",
+        after_help = "EXAMPLE:\n    czkawka watch --events json dup -d /home/rafal/Downloads -m 1024\n    czkawka watch --hook /home/rafal/notify.sh image -d /home/rafal/Pictures"
+    )]
+    Watch(WatchArgs),
+    #[clap(
+        name = "serve",
+        about = "Starts local JSON-RPC server, which allows to run tools from other programs",
+        after_help = "EXAMPLE:\n    czkawka serve --address 127.0.0.1:7878\n    czkawka serve --socket /run/user/1000/czkawka.sock"
+    )]
+    Serve(ServeArgs),
+    #[clap(
+        name = "run",
+        about = "Runs tools declared in profile file",
+        after_help = "EXAMPLE:\n    czkawka run /home/rafal/nightly.toml\n    czkawka run --check /home/rafal/nightly.toml"
+    )]
+    Run(RunArgs),
 }
 
+// Commands which are not tools, so they cannot be started by server or from profile
+pub const NON_TOOL_COMMANDS: &[&str] = &["undo", "restore", "cache", "watch", "serve", "run"];
+
 #[derive(Debug, clap::Args)]
 pub struct DuplicatesArgs {
     #[clap(flatten)]
@@ -203,14 +273,100 @@ pub struct DuplicatesArgs {
         long,
         default_value = "BLAKE3",
         value_parser = parse_hash_type,
-        help = "Hash type (BLAKE3, CRC32, XXH3)",
-        long_help = "Hash algorithm used to calculate file hashes. BLAKE3 is recommended for most cases (fast and secure), CRC32 is faster but less reliable, XXH3 is very fast but not cryptographically secure."
+        help = "Hash type (BLAKE3, CRC32, XXH3, SHA256, SHA1, MD5)",
+        long_help = "Hash algorithm used to calculate file hashes. BLAKE3 is recommended for most cases (fast and secure), CRC32 is faster but less reliable, XXH3 is very fast but not cryptographically secure. SHA256, SHA1 and MD5 are slower, but produce the same hashes as sha256sum, sha1sum and md5sum, so results can be compared with external checksum files."
     )]
     pub hash_type: HashType,
     #[clap(flatten)]
     pub case_sensitive_name_comparison: CaseSensitiveNameComparison,
     #[clap(flatten)]
     pub allow_hard_links: AllowHardLinks,
+    #[clap(
+        long,
+        conflicts_with = "checksum_manifest",
+        help = "Compare files byte by byte before deleting or linking them",
+        long_help = "Before deleting, hardlinking or otherwise replacing files found by HASH search method, compares each of them byte by byte with file which is kept. Files with different content (e.g. because of hash collision or outdated cache) are reported as errors and left untouched. Cannot be used with --checksum-manifest, because files from manifests exist only as hashes."
+    )]
+    pub verify_before_delete: bool,
+    #[clap(
+        long,
+        value_name = "manifest-file",
+        help = "Checksum manifest(s) to compare files with",
+        long_help = "Checksum file(s) created by sha256sum, sha1sum, md5sum or b3sum (also in BSD style with --tag), or hash indexes saved with --export-hash-index. Instead of looking for duplicates between scanned files, every scanned file is compared with hashes from manifests and files already listed in them are reported. Hash type must match algorithm used to create manifests."
+    )]
+    pub checksum_manifest: Vec<PathBuf>,
+    #[clap(
+        long,
+        requires = "checksum_manifest",
+        help = "Report files missing from checksum manifests",
+        long_help = "Used with --checksum-manifest, reports scanned files which content is not listed in any manifest, instead of files which are already listed."
+    )]
+    pub missing_from_manifest: bool,
+    #[clap(
+        long,
+        value_name = "index-file",
+        help = "Save hashes of all scanned files to index",
+        long_help = "Saves path, size, modification date and hash of every scanned file to JSON index. Index may be copied to other machine and used there with --checksum-manifest, to find files already present on this machine without connecting both disks at the same time. Every file is hashed, so search takes longer than usual."
+    )]
+    pub export_hash_index: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Look for duplicates also inside archives",
+        long_help = "Works only with HASH search method. Files inside zip, 7z and tar (also .tar.gz, .tar.zst, .tar.bz2 and .tar.xz) archives are compared with normal files and with each other. They are shown as \"archive.zip!/dir/file.jpg\" and are never deleted or linked. Every found archive is fully decompressed, so search may take much longer. Xz archives are decompressed to temporary file, so they are skipped when bigger than 16 GiB after decompression."
+    )]
+    pub check_archives: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DuplicateFoldersArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(
+        short,
+        long,
+        value_parser = parse_minimal_file_size,
+        default_value = "1",
+        help = "Minimum size in bytes",
+        long_help = "Minimum size of checked files in bytes. Smaller files are ignored, as if they were not inside compared folders"
+    )]
+    pub minimal_file_size: u64,
+    #[clap(
+        short = 'i',
+        long,
+        value_parser = parse_maximal_file_size,
+        default_value = "18446744073709551615",
+        help = "Maximum size in bytes",
+        long_help = "Maximum size of checked files in bytes. Bigger files are ignored, as if they were not inside compared folders"
+    )]
+    pub maximal_file_size: u64,
+    #[clap(
+        short = 'c',
+        long,
+        value_parser = parse_minimal_file_size,
+        default_value = "257144",
+        help = "Minimum size of files stored in the hash cache (bytes)",
+        long_help = "Minimum file size (in bytes) to be included in the hash cache. Cache is shared with duplicate finder using the same hash type."
+    )]
+    pub minimal_cached_file_size: u64,
+    #[clap(flatten)]
+    pub delete_method: DMethod,
+    #[clap(
+        short = 't',
+        long,
+        default_value = "BLAKE3",
+        value_parser = parse_hash_type,
+        help = "Hash type (BLAKE3, CRC32, XXH3, SHA256, SHA1, MD5)",
+        long_help = "Hash algorithm used to calculate hashes of files inside folders. Folders are compared by names and hashes of all files and subfolders inside them. Only files matching filters are compared, so folders may differ by other files, e.g. empty files ignored with default minimal size. Such folders are not removed after deleting their compared files."
+    )]
+    pub hash_type: HashType,
+    #[clap(
+        long,
+        value_name = "percent",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+        help = "Report similar folders sharing at least given percent of files",
+        long_help = "Instead of identical folders, reports pairs of folders in which at least given percent of all files have the same content in both folders, e.g. partially synced or diverged backup copies. Files which are only in one of folders are listed for each side. Files are compared only by content, so renamed files are also treated as common. Similar folders cannot be deleted or linked."
+    )]
+    pub minimal_overlap: Option<u8>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -944,9 +1100,15 @@ pub struct CommonCliItems {
         short = 'E',
         long,
         help = "Excluded item(s)",
-        long_help = "List of excluded items using wildcards (e.g., */temp*, *.tmp). May be slower than -e, so use -e for directories when possible. Helpful macros are available: DEFAULT (the same default excluded items the GUI uses) and $TRASH (the OS trash / recycle bin, matching the GUI defaults - */Trash/*,*/.Trash-*/* on Linux/macOS, *:\\$RECYCLE.BIN\\* on Windows)."
+        long_help = "List of excluded items using wildcards (e.g., */temp*, *.tmp). May be slower than -e, so use -e for directories when possible. Helpful macros are available: DEFAULT (the same default excluded items the GUI uses) and $TRASH (the OS trash / recycle bin, matching the GUI defaults - */Trash/*,*/.Trash-*/* on Linux/macOS, *:\\$RECYCLE.BIN\\* on Windows).\nItems containing ** are globs, where ** matches any number of folders and * only part of a single name (e.g., **/target/**). Items starting with regex: are regular expressions matched against the whole path (e.g., regex:.*/cache_[0-9]+/.*). Items starting with ! are negations - the last matching item decides, so e.g. -E '*/build/*' '!*/app/build/*' excludes all build folders except one.\nRules from .czkawkaignore files found in scanned folders are always used."
     )]
     pub excluded_items: Vec<String>,
+    #[clap(
+        long,
+        help = "Respect .gitignore files",
+        long_help = "Skips files ignored by .gitignore files found in scanned folders, in the same way as rules from .czkawkaignore files."
+    )]
+    pub respect_gitignore: bool,
     #[clap(
         short = 'x',
         long,
@@ -963,6 +1125,54 @@ pub struct CommonCliItems {
         long_help = "List of file extensions to exclude from search."
     )]
     pub excluded_extensions: Vec<String>,
+    #[clap(
+        long,
+        value_name = "date",
+        value_parser = parse_date_limit,
+        help = "Only files modified after date",
+        long_help = "Only files modified at or after given date are checked. Date can be an absolute UTC date (e.g. 2024-05-31 or '2024-05-31 14:30') or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 30d."
+    )]
+    pub modified_after: Option<u64>,
+    #[clap(
+        long,
+        value_name = "date",
+        value_parser = parse_date_limit,
+        help = "Only files modified before date",
+        long_help = "Only files modified at or before given date are checked. Date can be an absolute UTC date (e.g. 2024-05-31 or '2024-05-31 14:30') or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 30d."
+    )]
+    pub modified_before: Option<u64>,
+    #[clap(
+        long,
+        value_name = "date",
+        value_parser = parse_date_limit,
+        help = "Only files created after date",
+        long_help = "Only files created at or after given date are checked. Date can be an absolute UTC date (e.g. 2024-05-31 or '2024-05-31 14:30') or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 30d. Creation time is not available on every platform and filesystem - there files are not filtered by it."
+    )]
+    pub created_after: Option<u64>,
+    #[clap(
+        long,
+        value_name = "date",
+        value_parser = parse_date_limit,
+        help = "Only files created before date",
+        long_help = "Only files created at or before given date are checked. Date can be an absolute UTC date (e.g. 2024-05-31 or '2024-05-31 14:30') or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 30d. Creation time is not available on every platform and filesystem - there files are not filtered by it."
+    )]
+    pub created_before: Option<u64>,
+    #[clap(
+        long,
+        value_name = "date",
+        value_parser = parse_date_limit,
+        help = "Only files accessed after date",
+        long_help = "Only files accessed at or after given date are checked. Date can be an absolute UTC date (e.g. 2024-05-31 or '2024-05-31 14:30') or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 30d. Access time may be not updated by filesystems mounted with noatime - there files are not filtered by it."
+    )]
+    pub accessed_after: Option<u64>,
+    #[clap(
+        long,
+        value_name = "date",
+        value_parser = parse_date_limit,
+        help = "Only files accessed before date",
+        long_help = "Only files accessed at or before given date are checked. Date can be an absolute UTC date (e.g. 2024-05-31 or '2024-05-31 14:30') or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 30d. Access time may be not updated by filesystems mounted with noatime - there files are not filtered by it."
+    )]
+    pub accessed_before: Option<u64>,
     #[clap(flatten)]
     pub file_to_save: FileToSave,
     #[clap(flatten)]
@@ -1000,6 +1210,257 @@ pub struct CommonCliItems {
         long_help = "Disables the cache system. This will make scanning slower but ensures fresh results without cached data."
     )]
     pub disable_cache: bool,
+    #[clap(
+        long,
+        help = "Use directory snapshot",
+        long_help = "Saves list of entries of every scanned folder in cache and takes unchanged folders from it in the next scans, so only content of changed folders is listed again. Metadata of every folder and file is still read in every scan, so files modified in place are also noticed - the gain is saved listing of folders, which is noticeable mostly on network and FUSE filesystems with big, rarely changing trees."
+    )]
+    pub dir_snapshot: bool,
+    #[clap(
+        long,
+        default_value = "BINCODE",
+        value_parser = parse_cache_backend,
+        help = "Cache backend (BINCODE, DATABASE)",
+        long_help = "Storage used for cache. BINCODE - separate file for every tool and its settings, always loaded and saved as a whole. DATABASE - single embedded database shared by all tools, where only entries of currently checked files are read and written, not corrupted when scan is killed. Database is shared by all scans of one process, other processes wait until it is closed after short read or write and scan without cache only when it stays used for too long. Both backends have separate data, so switching backend starts with empty cache."
+    )]
+    pub cache_backend: CacheBackend,
+    #[clap(
+        long,
+        value_name = "journal-file",
+        help = "Record destructive operations to journal file",
+        long_help = "Appends every delete, hardlink, reflink, symlink, move and rename to the given journal file (one JSON entry per line), before it starts and again with its result. Operations recorded there can be reverted later with `czkawka undo`. Nothing is recorded in dry run. Files modified in place by exif-remover and video-optimizer are not recorded, because their previous content is not kept."
+    )]
+    pub journal: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct UndoArgs {
+    #[clap(
+        help = "Journal file to revert",
+        long_help = "Journal file created with --journal. Entries are reverted from newest to oldest. Hardlinks and symlinks are replaced with independent copies, reflinked files are already independent, so they are left as they are."
+    )]
+    pub journal: PathBuf,
+    #[clap(
+        short = 'Q',
+        long,
+        help = "Dry run - preview operations",
+        long_help = "Shows which entries would be reverted without touching any files."
+    )]
+    pub dry_run: bool,
+    #[clap(flatten)]
+    pub do_not_print: DoNotPrint,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct RestoreArgs {
+    #[clap(
+        help = "Quarantine folder to restore",
+        long_help = "Quarantine folder used with --quarantine. Items are moved back to their original locations, successfully restored items are removed from the manifest."
+    )]
+    pub quarantine: PathBuf,
+    #[clap(
+        short = 'Q',
+        long,
+        help = "Dry run - preview operations",
+        long_help = "Shows which items would be restored without touching any files."
+    )]
+    pub dry_run: bool,
+    #[clap(flatten)]
+    pub do_not_print: DoNotPrint,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommands,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum CacheCommands {
+    #[clap(name = "list", about = "Lists cache files with their tool, version, number of entries, size and time of last save")]
+    List,
+    #[clap(name = "clean", about = "Removes entries of non-existent or modified files from all cache files")]
+    Clean,
+    #[clap(
+        name = "prune",
+        about = "Removes entries of files modified before given date from all cache files and cache database",
+        after_help = "EXAMPLE:\n    czkawka cache prune --older-than 180d -Q"
+    )]
+    Prune(CachePruneArgs),
+    #[clap(
+        name = "export",
+        about = "Exports entries of all cache files and cache database to json file",
+        after_help = "EXAMPLE:\n    czkawka cache export --json /home/rafal/cache.json"
+    )]
+    Export(CacheExportArgs),
+    #[clap(
+        name = "import",
+        about = "Merges entries exported with cache export into local cache files or cache database",
+        after_help = "EXAMPLE:\n    czkawka cache import /home/rafal/cache.json --cache-backend DATABASE"
+    )]
+    Import(CacheImportArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CachePruneArgs {
+    #[clap(
+        long,
+        value_name = "date",
+        value_parser = parse_date_limit,
+        help = "Remove entries of files modified before date",
+        long_help = "Removes entries of files, which were last modified before given date, from all cache files and tables of cache database. Cache files without any entries left are removed together with their json copies. Date can be an absolute UTC date (e.g. 2024-05-31) or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 90d. Use cache clean to remove entries of non-existent or modified files."
+    )]
+    pub older_than: u64,
+    #[clap(
+        short = 'Q',
+        long,
+        help = "Dry run - preview operations",
+        long_help = "Shows how many entries would be removed from every cache file without removing them."
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CacheExportArgs {
+    #[clap(
+        long,
+        value_name = "file-path",
+        help = "Json file to export cache to",
+        long_help = "Json file to which entries of all cache files and tables of cache database with current version are exported."
+    )]
+    pub json: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CacheImportArgs {
+    #[clap(
+        help = "Json file created with cache export",
+        long_help = "Json file created with cache export, possibly on other machine. Entries exported from both cache backends are added to local cache of chosen backend, entries already present locally are kept."
+    )]
+    pub json: PathBuf,
+    #[clap(
+        long,
+        default_value = "BINCODE",
+        value_parser = parse_cache_backend,
+        help = "Cache backend to import into (BINCODE, DATABASE)",
+        long_help = "Cache backend to which entries are imported. BINCODE - local cache files, DATABASE - cache database. It should be the same backend as used by scans."
+    )]
+    pub cache_backend: CacheBackend,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct WatchArgs {
+    #[command(subcommand)]
+    pub tool: WatchTool,
+    #[clap(
+        long,
+        value_parser = parse_watch_event_format,
+        default_value = "LOG",
+        help = "Format of events (LOG, JSON)",
+        long_help = "Format of reported events. LOG writes them to log file in cache folder (and to terminal with ENABLE_TERMINAL_LOGS_IN_CLI=1), JSON prints one JSON object per line to stdout, with event type, new files and all files of group."
+    )]
+    pub events: WatchEventFormat,
+    #[clap(
+        long,
+        value_name = "program",
+        help = "Program run for every event",
+        long_help = "Program run for every group with new files. New files are passed as arguments and whole event as JSON in CZKAWKA_EVENT environment variable."
+    )]
+    pub hook: Option<PathBuf>,
+    #[clap(
+        long,
+        value_name = "seconds",
+        default_value = "2",
+        help = "Seconds without changes before rescan",
+        long_help = "Directories are scanned again after no file changed in them for this number of seconds, so copying many files triggers only one rescan."
+    )]
+    pub debounce: u64,
+    #[clap(
+        long,
+        value_name = "seconds",
+        default_value = "60",
+        help = "Seconds between rescans without inotify",
+        long_help = "Interval between rescans, used on systems without inotify or when directories cannot be watched, e.g. after reaching limit of inotify watches."
+    )]
+    pub poll_interval: u64,
+    #[clap(
+        long,
+        help = "Report groups found during first scan",
+        long_help = "Reports groups found during first scan. By default only groups with files which were not part of any group during previous scan are reported."
+    )]
+    pub report_existing: bool,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum WatchTool {
+    #[clap(
+        name = "dup",
+        about = "Watches for duplicate files, accepts same arguments as dup command, except delete arguments",
+        after_help = "EXAMPLE:\n    czkawka watch dup -d /home/rafal/Downloads -m 1024"
+    )]
+    Duplicates(DuplicatesArgs),
+    #[clap(
+        name = "image",
+        about = "Watches for similar images, accepts same arguments as image command, except delete arguments",
+        after_help = "EXAMPLE:\n    czkawka watch image -d /home/rafal/Pictures"
+    )]
+    SimilarImages(SimilarImagesArgs),
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(group = clap::ArgGroup::new("listen").required(true))]
+pub struct ServeArgs {
+    #[clap(
+        long,
+        group = "listen",
+        value_parser = parse_loopback_address,
+        value_name = "address:port",
+        help = "Loopback address to listen on",
+        long_help = "TCP address on which server listens, e.g. 127.0.0.1:7878 or localhost:7878. Only loopback addresses are allowed. Any local program (also web page opened in browser) may connect to it, so every client must authenticate with token."
+    )]
+    pub address: Option<SocketAddr>,
+    #[clap(
+        long,
+        group = "listen",
+        value_name = "path",
+        help = "Unix socket to listen on",
+        long_help = "Path of Unix socket on which server listens, access to it can be limited with file permissions. Only available on Unix systems."
+    )]
+    pub socket: Option<PathBuf>,
+    #[clap(
+        long,
+        value_name = "path",
+        help = "Save access token to file",
+        long_help = "Token is generated at every start of server and must be sent in `auth` request, as first request of every connection. By default it is printed to standard output, with this option it is instead saved to the given file, readable only by its owner."
+    )]
+    pub token_file: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct RunArgs {
+    #[clap(
+        value_name = "profile",
+        help = "Profile file with tools to run",
+        long_help = "TOML file with tools to run. Optional [common] table contains arguments shared by all tools, e.g. directories or excluded-items, and every [[tools]] table contains name of tool in `tool` key and its own arguments, e.g. delete-method or file-to-save. Arguments use names of long options, values are strings, numbers, booleans for flags or arrays for repeated options. Tools with subcommands use also `subcommand` key and `subcommand-args` table.\nAll tools are validated before running first one and are run in order of declaration. Number of threads cannot be changed between tools, so thread-number should be set only in [common] table."
+    )]
+    pub profile: PathBuf,
+    #[clap(long, help = "Only validate profile", long_help = "Validates profile and exits without running any tool.")]
+    pub check: bool,
+}
+
+// Options of delete request sent to server, parsed from the same arguments as in tool commands
+#[derive(Debug, clap::Parser)]
+#[clap(name = "delete")]
+pub struct ServeAdvancedDeleteArgs {
+    #[clap(flatten)]
+    pub delete_method: DMethod,
+}
+
+#[derive(Debug, clap::Parser)]
+#[clap(name = "delete")]
+pub struct ServeSimpleDeleteArgs {
+    #[clap(flatten)]
+    pub delete_method: SDMethod,
 }
 
 #[derive(Debug, clap::Args, Clone, Copy)]
@@ -1020,17 +1481,38 @@ pub struct DoNotPrint {
     pub do_not_print_messages: bool,
 }
 
-#[derive(Debug, clap::Args, Clone, Copy)]
+#[derive(Debug, clap::Args, Clone)]
 pub struct DMethod {
     #[clap(
         short = 'D',
         long,
         default_value = "NONE",
         value_parser = parse_delete_method,
-        help = "Delete method (AEN, AEO, ON, OO, AEB, AES, OB, OS, HARD)",
-        long_help = "Method for selecting which files to delete from duplicate groups:\nAEN - All files Except Newest (keeps only newest)\nAEO - All files Except Oldest (keeps only oldest)\nON - Only the Newest deleted (keeps all but newest)\nOO - Only the Oldest deleted (keeps all but oldest)\nAEB - All files Except Biggest (keeps only biggest)\nAES - All files Except Smallest (keeps only smallest)\nOB - Only the Biggest deleted (keeps all but biggest)\nOS - Only the Smallest deleted (keeps all but smallest)\nHARD - create hard links to save space\nNONE - do not delete files (default)"
+        help = "Delete method (AEN, AEO, ON, OO, AEB, AES, OB, OS, HARD, SYMLINK, REFLINK)",
+        long_help = "Method for selecting which files to delete from duplicate groups:\nAEN - All files Except Newest (keeps only newest)\nAEO - All files Except Oldest (keeps only oldest)\nON - Only the Newest deleted (keeps all but newest)\nOO - Only the Oldest deleted (keeps all but oldest)\nAEB - All files Except Biggest (keeps only biggest)\nAES - All files Except Smallest (keeps only smallest)\nOB - Only the Biggest deleted (keeps all but biggest)\nOS - Only the Smallest deleted (keeps all but smallest)\nHARD - create hard links to save space, only with HASH search method\nSYMLINK - replace duplicates with symbolic links to kept file, works also across filesystems, only with HASH search method\nREFLINK - replace duplicates with copy-on-write clones (btrfs, XFS), files keep their own metadata and stay independent, only with HASH search method\nNONE - do not delete files (default)"
     )]
     pub delete_method: DeleteMethod,
+    #[clap(
+        long,
+        value_name = "rule",
+        conflicts_with = "delete_method",
+        help = "Select files to delete with rule",
+        long_help = "Chooses files to delete in each group with a rule instead of a delete method. Rule has form `keep where <condition>` or `delete where <condition>`, e.g. --select-rule 'keep where path ~ \"/raw/\" or (width * height max)'.\nConditions: ==, !=, <, <=, >, >= compare values, `~` checks if text contains pattern (case-insensitive, * works as wildcard), `max`/`min` match items with the biggest/smallest value in group, combined with and, or, not and parentheses.\nFields: path, name, folder, extension, size, modified; similar images add width, height; similar music adds title, artist, year, genre, bitrate, length; similar videos add codec, bitrate, duration.\nWhole group is never deleted, unless reference folders are used. The same rule can be used in Krokiet."
+    )]
+    pub select_rule: Option<SelectionRule>,
+    #[clap(
+        long,
+        value_name = "path-or-pattern",
+        help = "Preferred path(s) of kept files",
+        long_help = "Ordered list of paths, which should be kept when choosing file to delete or link in a group, e.g. --preferred-paths /photos/originals --preferred-paths /backup. Earlier entries have higher priority. Entries with * are treated as wildcards (like excluded items), others as path prefixes. Ties are resolved by the selected delete method."
+    )]
+    pub preferred_paths: Vec<String>,
+    #[clap(
+        long,
+        help = "Create relative symlinks",
+        long_help = "Used with SYMLINK delete method - symlinks point to the kept file with a path relative to the symlink location instead of an absolute path, so they stay valid after moving the whole folder tree."
+    )]
+    pub relative_symlinks: bool,
     #[clap(
         short = 'Q',
         long,
@@ -1045,10 +1527,24 @@ pub struct DMethod {
         long_help = "Instead of permanently deleting files, move them to the system trash/recycle bin where they can be recovered."
     )]
     pub move_to_trash: bool,
+    #[clap(
+        long,
+        value_name = "quarantine-folder",
+        conflicts_with = "move_to_trash",
+        help = "Move items to quarantine folder",
+        long_help = "Instead of deleting files, move them into the given folder, mirroring their original directory structure. A manifest is written there, so everything can be put back with `czkawka restore`. Works also on headless servers and removable drives where the system trash is unavailable."
+    )]
+    pub quarantine: Option<PathBuf>,
+}
+
+impl DMethod {
+    pub fn deletes(&self) -> bool {
+        self.delete_method != DeleteMethod::None || self.select_rule.is_some()
+    }
 }
 
 // Simple delete method - delete files or not
-#[derive(Debug, clap::Args, Clone, Copy)]
+#[derive(Debug, clap::Args, Clone)]
 pub struct SDMethod {
     #[clap(short = 'D', long, help = "Delete found items", long_help = "Automatically delete all found items matching the criteria.")]
     pub delete_files: bool,
@@ -1066,6 +1562,14 @@ pub struct SDMethod {
         long_help = "Instead of permanently deleting files, move them to the system trash/recycle bin where they can be recovered."
     )]
     pub move_to_trash: bool,
+    #[clap(
+        long,
+        value_name = "quarantine-folder",
+        conflicts_with = "move_to_trash",
+        help = "Move items to quarantine folder",
+        long_help = "Instead of deleting files, move them into the given folder, mirroring their original directory structure. A manifest is written there, so everything can be put back with `czkawka restore`. Works also on headless servers and removable drives where the system trash is unavailable."
+    )]
+    pub quarantine: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -1193,6 +1697,90 @@ pub fn validate_file_sizes(minimal: u64, maximal: u64) {
     }
 }
 
+/// Converts JSON object into long command line arguments, e.g. `{"directories": ["/a", "/b"], "dry-run": true}` into `--directories=/a --directories=/b --dry-run`.
+pub fn cli_args_from_json(args: Option<&Value>) -> Result<Vec<String>, String> {
+    let args = match args {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Object(args)) => args,
+        Some(_) => return Err("Arguments must be an object".to_string()),
+    };
+    let mut cli_args = Vec::new();
+    for (name, value) in args {
+        let name = name.replace('_', "-");
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                Value::Bool(true) => cli_args.push(format!("--{name}")),
+                Value::Bool(false) | Value::Null => {}
+                Value::String(value) => cli_args.push(format!("--{name}={value}")),
+                Value::Number(value) => cli_args.push(format!("--{name}={value}")),
+                Value::Array(_) | Value::Object(_) => return Err(format!("Invalid value of argument \"{name}\"")),
+            }
+        }
+    }
+    Ok(cli_args)
+}
+
+/// Checks if tool deletes, links or moves found files just after search.
+pub fn deletes_after_search(command: &Commands) -> bool {
+    match command {
+        Commands::Duplicates(DuplicatesArgs { delete_method, .. })
+        | Commands::DuplicateFolders(DuplicateFoldersArgs { delete_method, .. })
+        | Commands::SimilarImages(SimilarImagesArgs { delete_method, .. })
+        | Commands::SameMusic(SameMusicArgs { delete_method, .. })
+        | Commands::SimilarVideos(SimilarVideosArgs { delete_method, .. }) => delete_method.deletes(),
+        Commands::EmptyFolders(EmptyFoldersArgs { delete_method, .. })
+        | Commands::BiggestFiles(BiggestFilesArgs { delete_method, .. })
+        | Commands::EmptyFiles(EmptyFilesArgs { delete_method, .. })
+        | Commands::Temporary(TemporaryArgs { delete_method, .. })
+        | Commands::InvalidSymlinks(InvalidSymlinksArgs { delete_method, .. })
+        | Commands::BrokenFiles(BrokenFilesArgs { delete_method, .. })
+        | Commands::BadNames(BadNamesArgs { delete_method, .. }) => delete_method.delete_files,
+        _ => false,
+    }
+}
+
+/// Returns settings shared by all tools, or None for commands which are not tools.
+pub fn tool_common_cli_items(command: &Commands) -> Option<&CommonCliItems> {
+    match command {
+        Commands::Duplicates(DuplicatesArgs { common_cli_items, .. })
+        | Commands::DuplicateFolders(DuplicateFoldersArgs { common_cli_items, .. })
+        | Commands::EmptyFolders(EmptyFoldersArgs { common_cli_items, .. })
+        | Commands::BiggestFiles(BiggestFilesArgs { common_cli_items, .. })
+        | Commands::EmptyFiles(EmptyFilesArgs { common_cli_items, .. })
+        | Commands::Temporary(TemporaryArgs { common_cli_items, .. })
+        | Commands::SimilarImages(SimilarImagesArgs { common_cli_items, .. })
+        | Commands::SameMusic(SameMusicArgs { common_cli_items, .. })
+        | Commands::InvalidSymlinks(InvalidSymlinksArgs { common_cli_items, .. })
+        | Commands::BrokenFiles(BrokenFilesArgs { common_cli_items, .. })
+        | Commands::SimilarVideos(SimilarVideosArgs { common_cli_items, .. })
+        | Commands::BadExtensions(BadExtensionsArgs { common_cli_items, .. })
+        | Commands::BadNames(BadNamesArgs { common_cli_items, .. })
+        | Commands::VideoOptimizer(VideoOptimizerArgs { common_cli_items, .. })
+        | Commands::ExifRemover(ExifRemoverArgs { common_cli_items, .. }) => Some(common_cli_items),
+        Commands::Undo(_) | Commands::Restore(_) | Commands::Cache(_) | Commands::Watch(_) | Commands::Serve(_) | Commands::Run(_) => None,
+    }
+}
+
+/// Parses tool with arguments given as JSON objects, in the same way as they would be given in command line.
+pub fn parse_tool_command(tool: &str, args: Option<&Value>, subcommand: Option<&str>, subcommand_args: Option<&Value>) -> Result<Commands, String> {
+    if NON_TOOL_COMMANDS.contains(&tool) {
+        return Err(format!("\"{tool}\" is not a tool"));
+    }
+    let mut cli_args = vec!["czkawka".to_string(), tool.to_string()];
+    cli_args.extend(cli_args_from_json(args)?);
+    // Some tools have subcommands with own arguments, e.g. "transcode" in "video-optimizer"
+    if let Some(subcommand) = subcommand {
+        cli_args.push(subcommand.to_string());
+        cli_args.extend(cli_args_from_json(subcommand_args)?);
+    }
+
+    Args::try_parse_from(cli_args).map(|args| args.command).map_err(|e| e.to_string())
+}
+
 const HELP_TEMPLATE: &str = r#"
 {bin} {version}
 
@@ -1209,6 +1797,7 @@ COMMANDS:
 
 EXAMPLES:
     {bin} dup -d /home/rafal -e /home/rafal/Obrazy  -m 25 -x 7z rar IMAGE -s hash -f results.txt -D aeo
+    {bin} image -d /home/rafal/Photos --select-rule 'keep where width * height max' -Q
     {bin} empty-folders -d /home/rafal/rr /home/gateway -f results.txt
     {bin} big -d /home/rafal/ /home/piszczal -e /home/rafal/Roman -n 25 -x VIDEO -f results.txt
     {bin} empty-files -d /home/rafal /home/szczekacz -e /home/rafal/Pulpit -R -f results.txt
@@ -1221,4 +1810,30 @@ EXAMPLES:
     {bin} bad-names -d /home/rafal -u -j -w -n -f results.txt
     {bin} video-optimizer -d /home/rafal transcode -c h264 -f results.txt
     {bin} video-optimizer -d /home/rafal crop -m blackbars -f results.txt
-    {bin} exif-remover -d /home/rafal -x IMAGE -f results.txt"#;
+    {bin} exif-remover -d /home/rafal -x IMAGE -f results.txt
+    {bin} undo /home/rafal/czkawka_journal.jsonl -Q
+    {bin} restore /mnt/backup/czkawka_quarantine -Q"#;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_cli_args_from_json() {
+        let args = json!({"directories": ["/a", "/-b"], "search_method": "HASH", "minimal-file-size": 10, "dry-run": true, "move-to-trash": false});
+        assert_eq!(
+            cli_args_from_json(Some(&args)).ok(),
+            Some(vec![
+                "--directories=/a".to_string(),
+                "--directories=/-b".to_string(),
+                "--dry-run".to_string(),
+                "--minimal-file-size=10".to_string(),
+                "--search-method=HASH".to_string(),
+            ])
+        );
+        cli_args_from_json(Some(&json!({"directories": [["/a"]]}))).unwrap_err();
+        cli_args_from_json(Some(&json!(["/a"]))).unwrap_err();
+    }
+}
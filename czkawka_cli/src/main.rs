@@ -6,19 +6,27 @@ use std::thread;
 use clap::Parser;
 use commands::Commands;
 use crossbeam_channel::{Receiver, Sender, unbounded};
+use czkawka_core::common::cache::{CacheFileInfo, clean_all_cache_files, export_cache_files, import_cache_files, list_cache_files, prune_cache_files};
 use czkawka_core::common::config_cache_path::{print_infos_and_warnings, set_config_cache_path};
 use czkawka_core::common::consts::DEFAULT_THREAD_SIZE;
+use czkawka_core::common::date_filter::{DateFilter, DateRange};
 use czkawka_core::common::image::register_image_decoding_hooks;
+use czkawka_core::common::journal::undo_journal;
 use czkawka_core::common::logger::{filtering_messages, print_version_mode, setup_logger};
+use czkawka_core::common::multi_scan::MultiScanTool;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::quarantine::restore_quarantine;
 use czkawka_core::common::set_number_of_threads;
 use czkawka_core::common::tool_data::{CommonData, DeleteMethod};
 use czkawka_core::common::traits::{AllTraits, FixingItems, PrintResults, Search};
+use czkawka_core::helpers::messages::MessageLimit;
 use czkawka_core::tools::bad_extensions::{BadExtensions, BadExtensionsFixParams, BadExtensionsParameters};
 use czkawka_core::tools::bad_names::{BadNames, BadNamesParameters, NameFixerParams, NameIssues};
 use czkawka_core::tools::big_file::{BigFile, BigFileParameters, SearchMode};
 use czkawka_core::tools::broken_files::{BrokenFiles, BrokenFilesParameters, CheckedTypes};
+use czkawka_core::tools::duplicate::manifest::ManifestMode;
 use czkawka_core::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
+use czkawka_core::tools::duplicate_folders::{DuplicateFolders, DuplicateFoldersParameters};
 use czkawka_core::tools::empty_files::{EmptyFiles, EmptyFilesParameters};
 use czkawka_core::tools::empty_folder::EmptyFolder;
 use czkawka_core::tools::exif_remover::{ExifRemover, ExifRemoverParameters, ExifTagsFixerParams};
@@ -31,17 +39,25 @@ use czkawka_core::tools::video_optimizer::{
     HardwareEncoder, VideoCropFixParams, VideoCropParams, VideoCroppingMechanism, VideoOptimizer, VideoOptimizerFixParams, VideoOptimizerParameters, VideoTranscodeFixParams,
     VideoTranscodeParams,
 };
+use humansize::{BINARY, format_size};
 use log::{debug, error, info};
 
 use crate::commands::{
-    Args, BadExtensionsArgs, BadNamesArgs, BiggestFilesArgs, BrokenFilesArgs, CommonCliItems, DMethod, DuplicatesArgs, EmptyFilesArgs, EmptyFoldersArgs, ExifRemoverArgs,
-    InvalidSymlinksArgs, SDMethod, SameMusicArgs, SimilarImagesArgs, SimilarVideosArgs, TemporaryArgs, VideoOptimizerArgs, validate_file_sizes,
+    Args, BadExtensionsArgs, BadNamesArgs, BiggestFilesArgs, BrokenFilesArgs, CacheArgs, CacheCommands, CommonCliItems, DMethod, DuplicateFoldersArgs, DuplicatesArgs,
+    EmptyFilesArgs, EmptyFoldersArgs, ExifRemoverArgs, InvalidSymlinksArgs, RestoreArgs, SDMethod, SameMusicArgs, SimilarImagesArgs, SimilarVideosArgs, TemporaryArgs, UndoArgs,
+    VideoOptimizerArgs, validate_file_sizes,
 };
-use crate::progress::connect_progress;
+use crate::profile::run_profile;
+use crate::progress::{ProgressFormat, connect_progress, connect_progress_json};
+use crate::serve::serve;
+use crate::watch::watch;
 
 mod commands;
 mod parsers;
+mod profile;
 mod progress;
+mod serve;
+mod watch;
 
 #[derive(Debug)]
 pub struct CliOutput {
@@ -56,7 +72,11 @@ fn main() {
         use clap::CommandFactory;
         Args::command().debug_assert();
     }
-    let command = Args::parse().command;
+    let Args {
+        command,
+        progress_format,
+        progress_fd,
+    } = Args::parse();
 
     let config_cache_path_set_result = set_config_cache_path("Czkawka", "Czkawka");
     setup_logger(true, "czkawka_cli", filtering_messages);
@@ -74,20 +94,13 @@ fn main() {
     let calculate_thread = thread::Builder::new()
         .stack_size(DEFAULT_THREAD_SIZE)
         .spawn(move || match command {
-            Commands::Duplicates(duplicates_args) => duplicates(duplicates_args, &stop_flag, &progress_sender),
-            Commands::EmptyFolders(empty_folders_args) => empty_folders(empty_folders_args, &stop_flag, &progress_sender),
-            Commands::BiggestFiles(biggest_files_args) => biggest_files(biggest_files_args, &stop_flag, &progress_sender),
-            Commands::EmptyFiles(empty_files_args) => empty_files(empty_files_args, &stop_flag, &progress_sender),
-            Commands::Temporary(temporary_args) => temporary(temporary_args, &stop_flag, &progress_sender),
-            Commands::SimilarImages(similar_images_args) => similar_images(similar_images_args, &stop_flag, &progress_sender),
-            Commands::SameMusic(same_music_args) => same_music(same_music_args, &stop_flag, &progress_sender),
-            Commands::InvalidSymlinks(invalid_symlinks_args) => invalid_symlinks(invalid_symlinks_args, &stop_flag, &progress_sender),
-            Commands::BrokenFiles(broken_files_args) => broken_files(broken_files_args, &stop_flag, &progress_sender),
-            Commands::SimilarVideos(similar_videos_args) => similar_videos(similar_videos_args, &stop_flag, &progress_sender),
-            Commands::BadExtensions(bad_extensions_args) => bad_extensions(bad_extensions_args, &stop_flag, &progress_sender),
-            Commands::BadNames(bad_names_args) => bad_names(bad_names_args, &stop_flag, &progress_sender),
-            Commands::VideoOptimizer(video_optimizer_args) => video_optimizer(video_optimizer_args, &stop_flag, &progress_sender),
-            Commands::ExifRemover(exif_remover_args) => exif_remover(exif_remover_args, &stop_flag, &progress_sender),
+            Commands::Undo(undo_args) => undo(&undo_args),
+            Commands::Restore(restore_args) => restore(&restore_args),
+            Commands::Cache(cache_args) => cache(cache_args, &stop_flag),
+            Commands::Watch(watch_args) => watch(watch_args, &stop_flag, &progress_sender),
+            Commands::Serve(serve_args) => serve(&serve_args, &stop_flag),
+            Commands::Run(run_args) => run_profile(&run_args, &stop_flag, &progress_sender),
+            command => run_tool(&command, &stop_flag, &progress_sender),
         })
         .expect("Failed to spawn calculation thread");
 
@@ -100,7 +113,10 @@ fn main() {
     })
     .expect("Error setting Ctrl-C handler");
 
-    connect_progress(&progress_receiver);
+    match progress_format {
+        ProgressFormat::Bars => connect_progress(&progress_receiver),
+        ProgressFormat::Json => connect_progress_json(&progress_receiver, progress_fd),
+    }
 
     let cli_output = calculate_thread.join().expect("Failed to join calculation thread");
 
@@ -116,7 +132,65 @@ fn main() {
     }
 }
 
-fn duplicates(duplicates: DuplicatesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn run_tool(command: &Commands, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    match command {
+        Commands::Duplicates(duplicates_args) => duplicates(duplicates_args, stop_flag, progress_sender),
+        Commands::DuplicateFolders(duplicate_folders_args) => duplicate_folders(duplicate_folders_args, stop_flag, progress_sender),
+        Commands::EmptyFolders(empty_folders_args) => empty_folders(empty_folders_args, stop_flag, progress_sender),
+        Commands::BiggestFiles(biggest_files_args) => biggest_files(biggest_files_args, stop_flag, progress_sender),
+        Commands::EmptyFiles(empty_files_args) => empty_files(empty_files_args, stop_flag, progress_sender),
+        Commands::Temporary(temporary_args) => temporary(temporary_args, stop_flag, progress_sender),
+        Commands::SimilarImages(similar_images_args) => similar_images(similar_images_args, stop_flag, progress_sender),
+        Commands::SameMusic(same_music_args) => same_music(same_music_args, stop_flag, progress_sender),
+        Commands::InvalidSymlinks(invalid_symlinks_args) => invalid_symlinks(invalid_symlinks_args, stop_flag, progress_sender),
+        Commands::BrokenFiles(broken_files_args) => broken_files(broken_files_args, stop_flag, progress_sender),
+        Commands::SimilarVideos(similar_videos_args) => similar_videos(similar_videos_args, stop_flag, progress_sender),
+        Commands::BadExtensions(bad_extensions_args) => bad_extensions(bad_extensions_args, stop_flag, progress_sender),
+        Commands::BadNames(bad_names_args) => bad_names(bad_names_args, stop_flag, progress_sender),
+        Commands::VideoOptimizer(video_optimizer_args) => video_optimizer(video_optimizer_args, stop_flag, progress_sender),
+        Commands::ExifRemover(exif_remover_args) => exif_remover(exif_remover_args, stop_flag, progress_sender),
+        Commands::Undo(_) | Commands::Restore(_) | Commands::Cache(_) | Commands::Watch(_) | Commands::Serve(_) | Commands::Run(_) => unreachable!("Not a tool command"),
+    }
+}
+
+fn supports_multi_scan(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Duplicates(_) | Commands::EmptyFiles(_) | Commands::BiggestFiles(_) | Commands::BrokenFiles(_) | Commands::Temporary(_)
+    )
+}
+
+// Tools are prepared the same way as when running alone, so only directory traversal may be shared
+fn prepare_multi_scan_tool(command: &Commands) -> Option<MultiScanTool> {
+    match command {
+        Commands::Duplicates(duplicates_args) => Some(MultiScanTool::Duplicates(Box::new(prepare_duplicate_finder(duplicates_args)))),
+        Commands::EmptyFiles(empty_files_args) => Some(MultiScanTool::EmptyFiles(prepare_empty_files(empty_files_args))),
+        Commands::BiggestFiles(biggest_files_args) => Some(MultiScanTool::BigFile(prepare_biggest_files(biggest_files_args))),
+        Commands::BrokenFiles(broken_files_args) => Some(MultiScanTool::BrokenFiles(prepare_broken_files(broken_files_args))),
+        Commands::Temporary(temporary_args) => Some(MultiScanTool::Temporary(prepare_temporary(temporary_args))),
+        _ => None,
+    }
+}
+
+fn write_multi_scan_tool_results(tool: &MultiScanTool, common_cli_items: &CommonCliItems) -> CliOutput {
+    match tool {
+        MultiScanTool::Duplicates(tool) => save_and_write_results_to_writer(tool.as_ref(), common_cli_items),
+        MultiScanTool::EmptyFiles(tool) => save_and_write_results_to_writer(tool, common_cli_items),
+        MultiScanTool::BigFile(tool) => save_and_write_results_to_writer(tool, common_cli_items),
+        MultiScanTool::BrokenFiles(tool) => save_and_write_results_to_writer(tool, common_cli_items),
+        MultiScanTool::Temporary(tool) => save_and_write_results_to_writer(tool, common_cli_items),
+    }
+}
+
+fn duplicates(duplicates: &DuplicatesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_duplicate_finder(duplicates);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    save_and_write_results_to_writer(&tool, &duplicates.common_cli_items)
+}
+
+fn prepare_duplicate_finder(duplicates: &DuplicatesArgs) -> DuplicateFinder {
     let DuplicatesArgs {
         common_cli_items,
         reference_directories,
@@ -130,45 +204,100 @@ fn duplicates(duplicates: DuplicatesArgs, stop_flag: &Arc<AtomicBool>, progress_
         case_sensitive_name_comparison,
         minimal_prehash_cache_file_size,
         use_prehash_cache,
+        verify_before_delete,
+        checksum_manifest,
+        missing_from_manifest,
+        export_hash_index,
+        check_archives,
     } = duplicates;
 
-    validate_file_sizes(minimal_file_size, maximal_file_size);
+    validate_file_sizes(*minimal_file_size, *maximal_file_size);
 
     let params = DuplicateFinderParameters::new(
-        search_method,
-        hash_type,
-        use_prehash_cache,
-        minimal_cached_file_size,
-        minimal_prehash_cache_file_size,
+        *search_method,
+        *hash_type,
+        *use_prehash_cache,
+        *minimal_cached_file_size,
+        *minimal_prehash_cache_file_size,
         case_sensitive_name_comparison.case_sensitive_name_comparison,
     );
     let mut tool = DuplicateFinder::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
-    tool.set_minimal_file_size(minimal_file_size);
-    tool.set_maximal_file_size(maximal_file_size);
+    set_common_settings(&mut tool, common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    tool.set_minimal_file_size(*minimal_file_size);
+    tool.set_maximal_file_size(*maximal_file_size);
     tool.set_hide_hard_links(!allow_hard_links.allow_hard_links);
-    set_advanced_delete(&mut tool, delete_method);
+    tool.set_verify_before_delete(*verify_before_delete);
+    let manifest_mode = if *missing_from_manifest { ManifestMode::Missing } else { ManifestMode::AlreadyPresent };
+    tool.set_checksum_manifests(checksum_manifest.clone(), manifest_mode);
+    tool.set_hash_index_export_path(export_hash_index.clone());
+    tool.set_check_archives(*check_archives);
+    set_advanced_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn duplicate_folders(duplicate_folders: &DuplicateFoldersArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_duplicate_folders(duplicate_folders);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    save_and_write_results_to_writer(&tool, &duplicate_folders.common_cli_items)
+}
+
+fn prepare_duplicate_folders(duplicate_folders: &DuplicateFoldersArgs) -> DuplicateFolders {
+    let DuplicateFoldersArgs {
+        common_cli_items,
+        minimal_file_size,
+        maximal_file_size,
+        minimal_cached_file_size,
+        delete_method,
+        hash_type,
+        minimal_overlap,
+    } = duplicate_folders;
+
+    validate_file_sizes(*minimal_file_size, *maximal_file_size);
+
+    let params = DuplicateFoldersParameters::new(*hash_type, *minimal_cached_file_size);
+    let mut tool = DuplicateFolders::new(params);
+
+    set_common_settings(&mut tool, common_cli_items, None);
+    tool.set_minimal_file_size(*minimal_file_size);
+    tool.set_maximal_file_size(*maximal_file_size);
+    tool.set_minimal_overlap(*minimal_overlap);
+    set_advanced_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn empty_folders(empty_folders: &EmptyFoldersArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_empty_folders(empty_folders);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &empty_folders.common_cli_items)
 }
 
-fn empty_folders(empty_folders: EmptyFoldersArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_empty_folders(empty_folders: &EmptyFoldersArgs) -> EmptyFolder {
     let EmptyFoldersArgs { common_cli_items, delete_method } = empty_folders;
 
     let mut tool = EmptyFolder::new();
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn biggest_files(biggest_files: &BiggestFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_biggest_files(biggest_files);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &biggest_files.common_cli_items)
 }
 
-fn biggest_files(biggest_files: BiggestFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_biggest_files(biggest_files: &BiggestFilesArgs) -> BigFile {
     let BiggestFilesArgs {
         common_cli_items,
         number_of_files,
@@ -176,19 +305,25 @@ fn biggest_files(biggest_files: BiggestFilesArgs, stop_flag: &Arc<AtomicBool>, p
         smallest_mode,
     } = biggest_files;
 
-    let big_files_mode = if smallest_mode { SearchMode::SmallestFiles } else { SearchMode::BiggestFiles };
-    let params = BigFileParameters::new(number_of_files, big_files_mode);
+    let big_files_mode = if *smallest_mode { SearchMode::SmallestFiles } else { SearchMode::BiggestFiles };
+    let params = BigFileParameters::new(*number_of_files, big_files_mode);
     let mut tool = BigFile::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn empty_files(empty_files: &EmptyFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_empty_files(empty_files);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &empty_files.common_cli_items)
 }
 
-fn empty_files(empty_files: EmptyFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_empty_files(empty_files: &EmptyFilesArgs) -> EmptyFiles {
     let EmptyFilesArgs {
         common_cli_items,
         delete_method,
@@ -197,20 +332,26 @@ fn empty_files(empty_files: EmptyFilesArgs, stop_flag: &Arc<AtomicBool>, progres
     } = empty_files;
 
     let params = EmptyFilesParameters {
-        search_zero_byte_content_files: zero_byte_content || non_printable_content,
-        search_non_printable_content_files: non_printable_content,
+        search_zero_byte_content_files: *zero_byte_content || *non_printable_content,
+        search_non_printable_content_files: *non_printable_content,
     };
     let mut tool = EmptyFiles::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn temporary(temporary: &TemporaryArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_temporary(temporary);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &temporary.common_cli_items)
 }
 
-fn temporary(temporary: TemporaryArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_temporary(temporary: &TemporaryArgs) -> Temporary {
     let TemporaryArgs {
         common_cli_items,
         delete_method,
@@ -220,19 +361,25 @@ fn temporary(temporary: TemporaryArgs, stop_flag: &Arc<AtomicBool>, progress_sen
     let params = if extensions.is_empty() {
         TemporaryParameters::default()
     } else {
-        TemporaryParameters { extensions }
+        TemporaryParameters { extensions: extensions.clone() }
     };
     let mut tool = Temporary::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn similar_images(similar_images: &SimilarImagesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_similar_images(similar_images);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &similar_images.common_cli_items)
 }
 
-fn similar_images(similar_images: SimilarImagesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_similar_images(similar_images: &SimilarImagesArgs) -> SimilarImages {
     let SimilarImagesArgs {
         common_cli_items,
         reference_directories,
@@ -249,31 +396,37 @@ fn similar_images(similar_images: SimilarImagesArgs, stop_flag: &Arc<AtomicBool>
         ignore_same_resolution,
     } = similar_images;
 
-    validate_file_sizes(minimal_file_size, maximal_file_size);
+    validate_file_sizes(*minimal_file_size, *maximal_file_size);
 
     let params = SimilarImagesParameters::new(
-        max_difference,
-        hash_size,
-        hash_alg,
-        image_filter,
+        *max_difference,
+        *hash_size,
+        *hash_alg,
+        *image_filter,
         ignore_same_size.ignore_same_size,
         ignore_same_resolution.ignore_same_resolution,
-        geometric_invariance,
+        *geometric_invariance,
     );
     let mut tool = SimilarImages::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
-    tool.set_minimal_file_size(minimal_file_size);
-    tool.set_maximal_file_size(maximal_file_size);
+    set_common_settings(&mut tool, common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    tool.set_minimal_file_size(*minimal_file_size);
+    tool.set_maximal_file_size(*maximal_file_size);
     tool.set_hide_hard_links(!allow_hard_links.allow_hard_links);
-    set_advanced_delete(&mut tool, delete_method);
+    set_advanced_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn same_music(same_music: &SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_same_music(same_music);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &same_music.common_cli_items)
 }
 
-fn same_music(same_music: SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_same_music(same_music: &SameMusicArgs) -> SameMusic {
     let SameMusicArgs {
         common_cli_items,
         reference_directories,
@@ -288,42 +441,54 @@ fn same_music(same_music: SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_s
         compare_fingerprints_only_with_similar_titles,
     } = same_music;
 
-    validate_file_sizes(minimal_file_size, maximal_file_size);
+    validate_file_sizes(*minimal_file_size, *maximal_file_size);
 
     let params = SameMusicParameters::new(
-        music_similarity,
-        approximate_comparison,
-        search_method,
-        minimum_segment_duration,
-        maximum_difference,
-        compare_fingerprints_only_with_similar_titles,
+        *music_similarity,
+        *approximate_comparison,
+        *search_method,
+        *minimum_segment_duration,
+        *maximum_difference,
+        *compare_fingerprints_only_with_similar_titles,
     );
     let mut tool = SameMusic::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
-    tool.set_minimal_file_size(minimal_file_size);
-    tool.set_maximal_file_size(maximal_file_size);
-    set_advanced_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    tool.set_minimal_file_size(*minimal_file_size);
+    tool.set_maximal_file_size(*maximal_file_size);
+    set_advanced_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn invalid_symlinks(invalid_symlinks: &InvalidSymlinksArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_invalid_symlinks(invalid_symlinks);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &invalid_symlinks.common_cli_items)
 }
 
-fn invalid_symlinks(invalid_symlinks: InvalidSymlinksArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_invalid_symlinks(invalid_symlinks: &InvalidSymlinksArgs) -> InvalidSymlinks {
     let InvalidSymlinksArgs { common_cli_items, delete_method } = invalid_symlinks;
 
     let mut tool = InvalidSymlinks::new();
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn broken_files(broken_files: &BrokenFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_broken_files(broken_files);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &broken_files.common_cli_items)
 }
 
-fn broken_files(broken_files: BrokenFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_broken_files(broken_files: &BrokenFilesArgs) -> BrokenFiles {
     let BrokenFilesArgs {
         common_cli_items,
         delete_method,
@@ -332,20 +497,26 @@ fn broken_files(broken_files: BrokenFilesArgs, stop_flag: &Arc<AtomicBool>, prog
 
     let mut checked_type = CheckedTypes::NONE;
     for check_type in checked_types {
-        checked_type |= check_type;
+        checked_type |= *check_type;
     }
     let params = BrokenFilesParameters::new(checked_type);
     let mut tool = BrokenFiles::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn similar_videos(similar_videos: &SimilarVideosArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let mut tool = prepare_similar_videos(similar_videos);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &similar_videos.common_cli_items)
 }
 
-fn similar_videos(similar_videos: SimilarVideosArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_similar_videos(similar_videos: &SimilarVideosArgs) -> SimilarVideos {
     let SimilarVideosArgs {
         reference_directories,
         common_cli_items,
@@ -374,61 +545,78 @@ fn similar_videos(similar_videos: SimilarVideosArgs, stop_flag: &Arc<AtomicBool>
         audio_min_duration_seconds,
     } = similar_videos;
 
-    validate_file_sizes(minimal_file_size, maximal_file_size);
+    validate_file_sizes(*minimal_file_size, *maximal_file_size);
 
     let params = SimilarVideosParameters::new(
-        tolerance,
+        *tolerance,
         ignore_same_size.ignore_same_size,
         ignore_same_resolution.ignore_same_resolution,
-        skip_forward_amount,
-        scan_duration,
-        crop_detect,
-        window_count,
-        duration_tolerance_pct,
-        min_matching_windows,
-        subclip_min_match,
-        generate_thumbnails,
-        thumbnail_video_percentage_from_start,
-        generate_thumbnail_grid,
-        thumbnail_grid_tiles_per_side,
-        check_audio_content,
-        audio_similarity_percent,
-        audio_maximum_difference,
-        audio_length_ratio,
-        audio_min_duration_seconds,
+        *skip_forward_amount,
+        *scan_duration,
+        *crop_detect,
+        *window_count,
+        *duration_tolerance_pct,
+        *min_matching_windows,
+        *subclip_min_match,
+        *generate_thumbnails,
+        *thumbnail_video_percentage_from_start,
+        *generate_thumbnail_grid,
+        *thumbnail_grid_tiles_per_side,
+        *check_audio_content,
+        *audio_similarity_percent,
+        *audio_maximum_difference,
+        *audio_length_ratio,
+        *audio_min_duration_seconds,
     );
     let mut tool = SimilarVideos::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
-    tool.set_minimal_file_size(minimal_file_size);
-    tool.set_maximal_file_size(maximal_file_size);
+    set_common_settings(&mut tool, common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    tool.set_minimal_file_size(*minimal_file_size);
+    tool.set_maximal_file_size(*maximal_file_size);
     tool.set_hide_hard_links(!allow_hard_links.allow_hard_links);
-    set_advanced_delete(&mut tool, delete_method);
+    set_advanced_delete(&mut tool, delete_method.clone());
+
+    tool
+}
+
+fn bad_extensions(bad_extensions: &BadExtensionsArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let (mut tool, fix_params) = prepare_bad_extensions(bad_extensions);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    if let Some(fix_params) = fix_params {
+        tool.fix_items(stop_flag, Some(progress_sender), fix_params);
+    }
+
+    save_and_write_results_to_writer(&tool, &bad_extensions.common_cli_items)
 }
 
-fn bad_extensions(bad_extensions: BadExtensionsArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_bad_extensions(bad_extensions: &BadExtensionsArgs) -> (BadExtensions, Option<BadExtensionsFixParams>) {
     let BadExtensionsArgs { common_cli_items, fix_extensions } = bad_extensions;
 
     let params = BadExtensionsParameters::new();
     let mut tool = BadExtensions::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
+    set_common_settings(&mut tool, common_cli_items, None);
+
+    let fix_params = fix_extensions.then_some(BadExtensionsFixParams {});
+
+    (tool, fix_params)
+}
+
+fn bad_names(bad_names: &BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let (mut tool, fix_params) = prepare_bad_names(bad_names);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    if fix_extensions {
-        let fix_params = BadExtensionsFixParams {};
+    if let Some(fix_params) = fix_params {
         tool.fix_items(stop_flag, Some(progress_sender), fix_params);
     }
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &bad_names.common_cli_items)
 }
 
-fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_bad_names(bad_names: &BadNamesArgs) -> (BadNames, Option<NameFixerParams>) {
     let BadNamesArgs {
         common_cli_items,
         delete_method,
@@ -441,7 +629,7 @@ fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_send
         fix_names,
     } = bad_names;
 
-    let restricted_charset_allowed = restricted_charset.and_then(|s| {
+    let restricted_charset_allowed = restricted_charset.as_ref().and_then(|s| {
         let mut items: Vec<_> = s.chars().collect();
         items.sort_unstable();
         items.dedup();
@@ -449,31 +637,38 @@ fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_send
     });
 
     let name_issues = NameIssues {
-        uppercase_extension,
-        emoji_used,
-        space_at_start_or_end,
-        non_ascii_graphical,
+        uppercase_extension: *uppercase_extension,
+        emoji_used: *emoji_used,
+        space_at_start_or_end: *space_at_start_or_end,
+        non_ascii_graphical: *non_ascii_graphical,
         restricted_charset_allowed,
-        remove_duplicated_non_alphanumeric,
+        remove_duplicated_non_alphanumeric: *remove_duplicated_non_alphanumeric,
     };
 
     let params = BadNamesParameters::new(name_issues);
     let mut tool = BadNames::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method.clone());
+
+    let fix_params = fix_names.then(NameFixerParams::default);
+
+    (tool, fix_params)
+}
+
+fn video_optimizer(video_optimizer: &VideoOptimizerArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let (mut tool, fix_params) = prepare_video_optimizer(video_optimizer);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    if fix_names {
-        let fix_params = NameFixerParams::default();
+    if let Some(fix_params) = fix_params {
         tool.fix_items(stop_flag, Some(progress_sender), fix_params);
     }
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(&tool, &video_optimizer.common_cli_items)
 }
 
-fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn prepare_video_optimizer(video_optimizer: &VideoOptimizerArgs) -> (VideoOptimizer, Option<VideoOptimizerFixParams>) {
     use crate::commands::{CropArgs, TranscodeArgs, VideoOptimizerMode as CliVideoOptimizerMode};
 
     let VideoOptimizerArgs { common_cli_items, mode } = video_optimizer;
@@ -499,41 +694,39 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                 custom_ffmpeg_command,
             } = transcode_args;
 
-            let excluded_codecs_vec = excluded_codecs.map_or_else(
+            let excluded_codecs_vec = excluded_codecs.as_ref().map_or_else(
                 || vec!["hevc".to_string(), "h265".to_string(), "av1".to_string(), "vp9".to_string()],
                 |s| s.split(',').map(|c| c.trim().to_string()).collect(),
             );
 
             let params = VideoOptimizerParameters::VideoTranscode(VideoTranscodeParams::new(
                 excluded_codecs_vec,
-                generate_thumbnails,
-                thumbnail_percentage,
-                thumbnail_grid,
-                thumbnail_grid_tiles_per_side,
+                *generate_thumbnails,
+                *thumbnail_percentage,
+                *thumbnail_grid,
+                *thumbnail_grid_tiles_per_side,
             ));
 
             let mut tool = VideoOptimizer::new(params);
-            set_common_settings(&mut tool, &common_cli_items, None);
-            tool.search(stop_flag, Some(progress_sender));
-
-            if fix_videos {
-                let fix_params = VideoOptimizerFixParams::VideoTranscode(VideoTranscodeFixParams {
-                    codec: target_codec,
-                    quality,
-                    fail_if_not_smaller,
-                    overwrite_original,
-                    limit_video_size,
-                    max_width,
-                    max_height,
-                    noise_reduction,
-                    noise_reduction_strength,
-                    custom_ffmpeg_command,
+            set_common_settings(&mut tool, common_cli_items, None);
+
+            let fix_params = fix_videos.then(|| {
+                VideoOptimizerFixParams::VideoTranscode(VideoTranscodeFixParams {
+                    codec: *target_codec,
+                    quality: *quality,
+                    fail_if_not_smaller: *fail_if_not_smaller,
+                    overwrite_original: *overwrite_original,
+                    limit_video_size: *limit_video_size,
+                    max_width: *max_width,
+                    max_height: *max_height,
+                    noise_reduction: noise_reduction.clone(),
+                    noise_reduction_strength: *noise_reduction_strength,
+                    custom_ffmpeg_command: custom_ffmpeg_command.clone(),
                     hardware_encoder: HardwareEncoder::None, // TODO  - missing hardware encoder
-                });
-                tool.fix_items(stop_flag, Some(progress_sender), fix_params);
-            }
+                })
+            });
 
-            save_and_write_results_to_writer(&tool, &common_cli_items)
+            (tool, fix_params)
         }
         CliVideoOptimizerMode::Crop(crop_args) => {
             let CropArgs {
@@ -561,36 +754,46 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
 
             let params = VideoOptimizerParameters::VideoCrop(VideoCropParams::with_custom_params(
                 crop_mech,
-                black_pixel_threshold,
-                black_bar_percentage,
-                max_samples,
-                min_crop_size,
-                generate_thumbnails,
-                thumbnail_percentage,
-                thumbnail_grid,
-                thumbnail_grid_tiles_per_side,
+                *black_pixel_threshold,
+                *black_bar_percentage,
+                *max_samples,
+                *min_crop_size,
+                *generate_thumbnails,
+                *thumbnail_percentage,
+                *thumbnail_grid,
+                *thumbnail_grid_tiles_per_side,
             ));
 
             let mut tool = VideoOptimizer::new(params);
-            set_common_settings(&mut tool, &common_cli_items, None);
-            tool.search(stop_flag, Some(progress_sender));
-
-            if fix_videos {
-                let fix_params = VideoOptimizerFixParams::VideoCrop(VideoCropFixParams {
-                    overwrite_original,
-                    target_codec,
-                    quality,
+            set_common_settings(&mut tool, common_cli_items, None);
+
+            let fix_params = fix_videos.then(|| {
+                VideoOptimizerFixParams::VideoCrop(VideoCropFixParams {
+                    overwrite_original: *overwrite_original,
+                    target_codec: *target_codec,
+                    quality: *quality,
                     crop_mechanism: crop_mech,
-                });
-                tool.fix_items(stop_flag, Some(progress_sender), fix_params);
-            }
+                })
+            });
 
-            save_and_write_results_to_writer(&tool, &common_cli_items)
+            (tool, fix_params)
         }
     }
 }
 
-fn exif_remover(exif_remover: ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+fn exif_remover(exif_remover: &ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let (mut tool, fix_params) = prepare_exif_remover(exif_remover);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    if let Some(fix_params) = fix_params {
+        tool.fix_items(stop_flag, Some(progress_sender), fix_params);
+    }
+
+    save_and_write_results_to_writer(&tool, &exif_remover.common_cli_items)
+}
+
+fn prepare_exif_remover(exif_remover: &ExifRemoverArgs) -> (ExifRemover, Option<ExifTagsFixerParams>) {
     let ExifRemoverArgs {
         common_cli_items,
         ignored_tags,
@@ -598,21 +801,144 @@ fn exif_remover(exif_remover: ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, prog
         override_file,
     } = exif_remover;
 
-    let ignored_tags_vec = ignored_tags.map(|s| s.split(',').map(|tag| tag.trim().to_string()).collect()).unwrap_or_default();
+    let ignored_tags_vec = ignored_tags.as_ref().map(|s| s.split(',').map(|tag| tag.trim().to_string()).collect()).unwrap_or_default();
 
     let params = ExifRemoverParameters::new(ignored_tags_vec);
     let mut tool = ExifRemover::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
+    set_common_settings(&mut tool, common_cli_items, None);
 
-    tool.search(stop_flag, Some(progress_sender));
+    let fix_params = fix_exif.then_some(ExifTagsFixerParams { override_file: *override_file });
 
-    if fix_exif {
-        let fix_params = ExifTagsFixerParams { override_file };
-        tool.fix_items(stop_flag, Some(progress_sender), fix_params);
+    (tool, fix_params)
+}
+
+fn undo(undo: &UndoArgs) -> CliOutput {
+    let undo_result = undo_journal(&undo.journal, undo.dry_run);
+
+    let mut output = if undo.dry_run {
+        format!("Would revert {} entries", undo_result.reverted_entries)
+    } else {
+        format!("Reverted {} entries, {} failed", undo_result.reverted_entries, undo_result.failed_entries)
+    };
+    output += &format!(
+        ", {} cannot be reverted, {} don't need to be reverted\n",
+        undo_result.not_revertible_entries, undo_result.not_needed_entries
+    );
+
+    if !undo.do_not_print.do_not_print_messages {
+        output += &undo_result.messages.create_messages_text(MessageLimit::NoLimit);
     }
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    CliOutput {
+        found_any_files: undo_result.failed_entries > 0 || undo_result.messages.critical.is_some(),
+        ignored_error_code_on_found: false,
+        output,
+    }
+}
+
+fn restore(restore: &RestoreArgs) -> CliOutput {
+    let restore_result = restore_quarantine(&restore.quarantine, restore.dry_run);
+
+    let mut output = if restore.dry_run {
+        format!("Would restore {} items\n", restore_result.restored_entries)
+    } else {
+        format!("Restored {} items, {} failed\n", restore_result.restored_entries, restore_result.failed_entries)
+    };
+
+    if !restore.do_not_print.do_not_print_messages {
+        output += &restore_result.messages.create_messages_text(MessageLimit::NoLimit);
+    }
+
+    CliOutput {
+        found_any_files: restore_result.failed_entries > 0 || restore_result.messages.critical.is_some(),
+        ignored_error_code_on_found: false,
+        output,
+    }
+}
+
+fn cache(cache: CacheArgs, stop_flag: &Arc<AtomicBool>) -> CliOutput {
+    let result = match cache.command {
+        CacheCommands::List => list_cache_files().map(|cache_files| (format_cache_files(&cache_files), Vec::new())),
+        CacheCommands::Clean => clean_all_cache_files(stop_flag, None).map(|stats| {
+            let output = format!(
+                "Cleaned {} of {} cache files, removed {} of {} entries, size reduced from {} to {}\n",
+                stats.successfully_cleaned,
+                stats.total_files_found,
+                stats.total_entries_removed,
+                stats.total_entries_before,
+                format_size(stats.total_size_before, BINARY),
+                format_size(stats.total_size_after, BINARY)
+            );
+            (output, stats.errors)
+        }),
+        CacheCommands::Prune(prune_args) => prune_cache_files(prune_args.older_than, prune_args.dry_run).map(|stats| {
+            let mut output = if prune_args.dry_run {
+                format!("Would remove {} entries and {} empty cache files\n", stats.removed_entries, stats.removed_files.len())
+            } else {
+                format!("Removed {} entries and {} empty cache files\n", stats.removed_entries, stats.removed_files.len())
+            };
+            for (file_name, removed_entries) in &stats.pruned_files {
+                let removed_file = if stats.removed_files.contains(file_name) { ", file removed" } else { "" };
+                output += &format!("{file_name} - {removed_entries} entries{removed_file}\n");
+            }
+            (output, stats.errors)
+        }),
+        CacheCommands::Export(export_args) => export_cache_files(&export_args.json).map(|stats| {
+            let output = format!(
+                "Exported {} entries from {} cache files to \"{}\"\n",
+                stats.exported_entries,
+                stats.exported_files,
+                export_args.json.to_string_lossy()
+            );
+            (output, stats.errors)
+        }),
+        CacheCommands::Import(import_args) => import_cache_files(&import_args.json, import_args.cache_backend).map(|stats| {
+            let output = format!(
+                "Imported {} cache files, added {} entries, skipped {} entries already present in cache\n",
+                stats.imported_files, stats.added_entries, stats.skipped_entries
+            );
+            (output, stats.errors)
+        }),
+    };
+
+    match result {
+        Ok((mut output, errors)) => {
+            for error in &errors {
+                output += &format!("Error: {error}\n");
+            }
+            CliOutput {
+                found_any_files: !errors.is_empty(),
+                ignored_error_code_on_found: false,
+                output,
+            }
+        }
+        Err(e) => CliOutput {
+            found_any_files: true,
+            ignored_error_code_on_found: false,
+            output: format!("Error: {e}"),
+        },
+    }
+}
+
+fn format_cache_files(cache_files: &[CacheFileInfo]) -> String {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let mut output = format!("Found {} cache files\n", cache_files.len());
+    for cache_file in cache_files {
+        let cache_type = cache_file.cache_type.unwrap_or("outdated version");
+        let version = cache_file.version.map_or_else(|| "-".to_string(), |version| version.to_string());
+        let entries = cache_file.entries.map_or_else(|| "-".to_string(), |entries| entries.to_string());
+        let size = cache_file.size.map_or_else(|| "-".to_string(), |size| format_size(size, BINARY));
+        let saved = cache_file.modified_date.map_or_else(
+            || "-".to_string(),
+            |modified_date| format!("{} days ago", now.saturating_sub(modified_date) / (24 * 60 * 60)),
+        );
+        output += &format!(
+            "{} - type: {cache_type}, version: {version}, entries: {entries}, size: {size}, saved: {saved}\n",
+            cache_file.file_name
+        );
+    }
+    output
 }
 
 fn save_and_write_results_to_writer<T: CommonData + PrintResults>(component: &T, common_cli_items: &CommonCliItems) -> CliOutput {
@@ -669,15 +995,24 @@ where
     }
     component.set_dry_run(s_delete.dry_run);
     component.set_move_to_trash(s_delete.move_to_trash);
+    component.set_quarantine_path(s_delete.quarantine);
 }
 
 fn set_advanced_delete<T>(component: &mut T, a_delete: DMethod)
 where
     T: AllTraits,
 {
-    component.set_delete_method(a_delete.delete_method);
+    if let Some(select_rule) = a_delete.select_rule {
+        component.set_delete_method(DeleteMethod::Rule);
+        component.set_selection_rule(Some(select_rule));
+    } else {
+        component.set_delete_method(a_delete.delete_method);
+    }
+    component.set_preferred_paths(a_delete.preferred_paths);
+    component.set_relative_symlinks(a_delete.relative_symlinks);
     component.set_dry_run(a_delete.dry_run);
     component.set_move_to_trash(a_delete.move_to_trash);
+    component.set_quarantine_path(a_delete.quarantine);
 }
 
 fn set_common_settings<T>(component: &mut T, common_cli_items: &CommonCliItems, reference_directories: Option<&Vec<PathBuf>>)
@@ -695,10 +1030,19 @@ where
     component.set_included_paths(included_directories);
     component.set_excluded_paths(common_cli_items.excluded_directories.clone());
     component.set_excluded_items(common_cli_items.excluded_items.clone());
+    component.set_date_filter(DateFilter {
+        modified: DateRange::new(common_cli_items.modified_after, common_cli_items.modified_before),
+        created: DateRange::new(common_cli_items.created_after, common_cli_items.created_before),
+        accessed: DateRange::new(common_cli_items.accessed_after, common_cli_items.accessed_before),
+    });
+    component.set_respect_gitignore(common_cli_items.respect_gitignore);
     component.set_recursive_search(!common_cli_items.not_recursive);
     #[cfg(target_family = "unix")]
     component.set_exclude_other_filesystems(common_cli_items.exclude_other_filesystems);
     component.set_allowed_extensions(common_cli_items.allowed_extensions.clone());
     component.set_excluded_extensions(common_cli_items.excluded_extensions.clone());
     component.set_use_cache(!common_cli_items.disable_cache);
+    component.set_use_dir_snapshot(common_cli_items.dir_snapshot);
+    component.set_cache_backend(common_cli_items.cache_backend);
+    component.set_journal_path(common_cli_items.journal.clone());
 }
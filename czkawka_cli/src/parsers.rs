@@ -1,3 +1,6 @@
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use czkawka_core::common::cache::CacheBackend;
 use czkawka_core::common::model::{CheckingMethod, HashType};
 use czkawka_core::common::tool_data::DeleteMethod;
 use czkawka_core::re_exported::{FilterType, HashAlg};
@@ -10,6 +13,9 @@ use czkawka_core::tools::similar_videos::{
 };
 use czkawka_core::tools::video_optimizer::{NoiseReductionMethod, VideoCodec};
 
+use crate::progress::ProgressFormat;
+use crate::watch::WatchEventFormat;
+
 /// Values above this threshold are practically meaningless for audio segment matching
 const MAX_SAME_MUSIC_DIFFERENCE: f64 = 10.0;
 
@@ -152,7 +158,46 @@ pub(crate) fn parse_hash_type(src: &str) -> Result<HashType, &'static str> {
         "blake3" => Ok(HashType::Blake3),
         "crc32" => Ok(HashType::Crc32),
         "xxh3" => Ok(HashType::Xxh3),
-        _ => Err("Couldn't parse the hash type (allowed: BLAKE3, CRC32, XXH3)"),
+        "sha256" => Ok(HashType::Sha256),
+        "sha1" => Ok(HashType::Sha1),
+        "md5" => Ok(HashType::Md5),
+        _ => Err("Couldn't parse the hash type (allowed: BLAKE3, CRC32, XXH3, SHA256, SHA1, MD5)"),
+    }
+}
+
+pub(crate) fn parse_cache_backend(src: &str) -> Result<CacheBackend, &'static str> {
+    match src.to_ascii_lowercase().as_str() {
+        "bincode" => Ok(CacheBackend::Bincode),
+        "database" => Ok(CacheBackend::Database),
+        _ => Err("Couldn't parse the cache backend (allowed: BINCODE, DATABASE)"),
+    }
+}
+
+pub(crate) fn parse_progress_format(src: &str) -> Result<ProgressFormat, &'static str> {
+    match src.to_ascii_lowercase().as_str() {
+        "bars" => Ok(ProgressFormat::Bars),
+        "json" => Ok(ProgressFormat::Json),
+        _ => Err("Couldn't parse the progress format (allowed: BARS, JSON)"),
+    }
+}
+
+pub(crate) fn parse_watch_event_format(src: &str) -> Result<WatchEventFormat, &'static str> {
+    match src.to_ascii_lowercase().as_str() {
+        "log" => Ok(WatchEventFormat::Log),
+        "json" => Ok(WatchEventFormat::Json),
+        _ => Err("Couldn't parse the watch event format (allowed: LOG, JSON)"),
+    }
+}
+
+pub(crate) fn parse_loopback_address(src: &str) -> Result<SocketAddr, String> {
+    let addresses: Vec<SocketAddr> = src.to_socket_addrs().map_err(|e| format!("Couldn't parse address \"{src}\" - {e}"))?.collect();
+    // Token is sent in plain text, so limiting server to loopback is additional protection next to authentication
+    match addresses.first() {
+        Some(address) if addresses.iter().all(|address| address.ip().is_loopback()) => Ok(*address),
+        Some(_) => Err(format!(
+            "Address \"{src}\" is not a loopback address, only e.g. 127.0.0.1:<port> or localhost:<port> are allowed"
+        )),
+        None => Err(format!("Couldn't resolve address \"{src}\"")),
     }
 }
 
@@ -233,13 +278,15 @@ pub(crate) fn parse_delete_method(src: &str) -> Result<DeleteMethod, &'static st
         "aen" => Ok(DeleteMethod::AllExceptNewest),
         "aeo" => Ok(DeleteMethod::AllExceptOldest),
         "hard" => Ok(DeleteMethod::HardLink),
+        "symlink" => Ok(DeleteMethod::Symlink),
+        "reflink" => Ok(DeleteMethod::Reflink),
         "on" => Ok(DeleteMethod::OneNewest),
         "oo" => Ok(DeleteMethod::OneOldest),
         "aeb" => Ok(DeleteMethod::AllExceptBiggest),
         "aes" => Ok(DeleteMethod::AllExceptSmallest),
         "ob" => Ok(DeleteMethod::OneBiggest),
         "os" => Ok(DeleteMethod::OneSmallest),
-        _ => Err("Couldn't parse the delete method (allowed: AEN, AEO, ON, OO, HARD, AEB, AES, OB, OS)"),
+        _ => Err("Couldn't parse the delete method (allowed: AEN, AEO, ON, OO, HARD, SYMLINK, REFLINK, AEB, AES, OB, OS)"),
     }
 }
 
@@ -371,4 +418,13 @@ mod tests {
         assert_eq!(parse_geometric_invariance("mirror-flip"), Ok(GeometricInvariance::MirrorFlip));
         assert_eq!(parse_geometric_invariance("mirror-flip-rotate90"), Ok(GeometricInvariance::MirrorFlipRotate90));
     }
+
+    #[test]
+    fn test_parse_loopback_address() {
+        assert_eq!(parse_loopback_address("127.0.0.1:7878"), Ok(SocketAddr::from(([127, 0, 0, 1], 7878))));
+        assert!(parse_loopback_address("[::1]:7878").is_ok_and(|address| address.ip().is_loopback()));
+        parse_loopback_address("0.0.0.0:7878").unwrap_err();
+        parse_loopback_address("192.168.1.1:7878").unwrap_err();
+        parse_loopback_address("127.0.0.1").unwrap_err();
+    }
 }
@@ -0,0 +1,264 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crossbeam_channel::Sender;
+use czkawka_core::common::multi_scan::MultiScan;
+use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::traits::Search;
+use log::info;
+use serde_json::{Map, Value};
+
+use crate::commands::{Commands, RunArgs, parse_tool_command, tool_common_cli_items};
+use crate::{CliOutput, prepare_multi_scan_tool, run_tool, supports_multi_scan, write_multi_scan_tool_results};
+
+const COMMON_TABLE: &str = "common";
+const TOOLS_TABLE: &str = "tools";
+
+/// Runs all tools from profile one after another, but only when whole profile is valid.
+/// Consecutive tools which can share directory traversal, walk through directories only once.
+pub(crate) fn run_profile(run: &RunArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let profile_path = run.profile.to_string_lossy();
+    let tools = match load_profile(&run.profile) {
+        Ok(tools) => tools,
+        Err(errors) => {
+            return CliOutput {
+                found_any_files: true,
+                ignored_error_code_on_found: false,
+                output: format!("Profile \"{profile_path}\" is invalid:\n{}", errors.join("\n")),
+            };
+        }
+    };
+
+    if run.check {
+        return CliOutput {
+            found_any_files: false,
+            ignored_error_code_on_found: false,
+            output: format!("Profile \"{profile_path}\" is valid, it contains {} tools", tools.len()),
+        };
+    }
+
+    let mut output = String::new();
+    let mut found_any_files = false;
+    let mut idx = 0;
+    for group in group_tools(&tools) {
+        if stop_flag.load(Ordering::Relaxed) {
+            info!("Stopped, skipping remaining {} tools", tools.len() - idx);
+            break;
+        }
+
+        let cli_outputs = if let [(name, command)] = group {
+            info!("Running {name} ({}/{})", idx + 1, tools.len());
+            vec![run_tool(command, stop_flag, progress_sender)]
+        } else {
+            info!(
+                "Running {} tools ({}-{}/{}) with shared directory traversal",
+                group.len(),
+                idx + 1,
+                idx + group.len(),
+                tools.len()
+            );
+            let mut multi_scan = MultiScan::new(group.iter().filter_map(|(_, command)| prepare_multi_scan_tool(command)).collect());
+            multi_scan.search(stop_flag, Some(progress_sender));
+            multi_scan
+                .get_tools()
+                .iter()
+                .zip(group)
+                .filter_map(|(tool, (_, command))| tool_common_cli_items(command).map(|common_cli_items| write_multi_scan_tool_results(tool, common_cli_items)))
+                .collect()
+        };
+
+        for ((name, _), cli_output) in group.iter().zip(cli_outputs) {
+            found_any_files |= cli_output.found_any_files && !cli_output.ignored_error_code_on_found;
+            output += &format!("=== {name} ({}/{}) ===\n{}\n", idx + 1, tools.len(), cli_output.output);
+            idx += 1;
+        }
+    }
+
+    CliOutput {
+        found_any_files,
+        ignored_error_code_on_found: false,
+        output,
+    }
+}
+
+/// Groups consecutive tools which can be run with `MultiScan`, so they walk through directories only once.
+/// Other tools are in their own groups.
+fn group_tools(tools: &[(String, Commands)]) -> Vec<&[(String, Commands)]> {
+    tools
+        .chunk_by(|(_, first), (_, second)| supports_multi_scan(first) && supports_multi_scan(second))
+        .collect()
+}
+
+fn load_profile(path: &Path) -> Result<Vec<(String, Commands)>, Vec<String>> {
+    let content = fs::read_to_string(path).map_err(|e| vec![format!("Cannot read profile - {e}")])?;
+    parse_profile(&content)
+}
+
+/// Parses every tool of profile, returns tools with their names or all found errors.
+fn parse_profile(content: &str) -> Result<Vec<(String, Commands)>, Vec<String>> {
+    let mut profile: Map<String, Value> = toml::from_str(content).map_err(|e| vec![format!("Cannot parse profile - {e}")])?;
+
+    let common = match profile.remove(COMMON_TABLE) {
+        None => Map::new(),
+        Some(Value::Object(common)) => common,
+        Some(_) => return Err(vec![format!("\"{COMMON_TABLE}\" must be a table")]),
+    };
+    let tools = match profile.remove(TOOLS_TABLE) {
+        Some(Value::Array(tools)) if !tools.is_empty() => tools,
+        _ => return Err(vec![format!("Profile must contain at least one [[{TOOLS_TABLE}]] table")]),
+    };
+    if !profile.is_empty() {
+        return Err(profile.keys().map(|key| format!("Unknown key \"{key}\"")).collect());
+    }
+
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, tool) in tools.into_iter().enumerate() {
+        match parse_profile_tool(&common, tool) {
+            Ok(command) => commands.push(command),
+            Err(e) => errors.push(format!("Tool {} - {e}", idx + 1)),
+        }
+    }
+
+    // Global thread pool can be built only once, so it is not possible to change number of threads between tools
+    let mut thread_numbers: Vec<usize> = commands
+        .iter()
+        .filter_map(|(_, command)| tool_common_cli_items(command))
+        .map(|items| items.thread_number)
+        .collect();
+    thread_numbers.dedup();
+    if thread_numbers.len() > 1 {
+        errors.push("All tools must use the same \"thread-number\", set it in [common] table".to_string());
+    }
+
+    if errors.is_empty() { Ok(commands) } else { Err(errors) }
+}
+
+fn parse_profile_tool(common: &Map<String, Value>, tool: Value) -> Result<(String, Commands), String> {
+    let Value::Object(tool) = tool else {
+        return Err("must be a table".to_string());
+    };
+
+    let mut name = None;
+    let mut subcommand = None;
+    let mut subcommand_args = None;
+    // Arguments of tool replace common arguments with the same name
+    let mut args: Map<String, Value> = common.iter().map(|(key, value)| (key.replace('_', "-"), value.clone())).collect();
+    for (key, value) in tool {
+        match (key.replace('_', "-").as_str(), value) {
+            ("tool", Value::String(value)) => name = Some(value),
+            ("subcommand", Value::String(value)) => subcommand = Some(value),
+            ("subcommand-args", value) => subcommand_args = Some(value),
+            ("tool" | "subcommand", _) => return Err(format!("\"{key}\" must be a string")),
+            (key, value) => {
+                args.insert(key.to_string(), value);
+            }
+        }
+    }
+    let Some(name) = name else {
+        return Err("missing \"tool\" key".to_string());
+    };
+    let label = subcommand.as_ref().map_or_else(|| name.clone(), |subcommand| format!("{name} {subcommand}"));
+
+    parse_tool_command(&name, Some(&Value::Object(args)), subcommand.as_deref(), subcommand_args.as_ref())
+        .map(|command| (label.clone(), command))
+        .map_err(|e| format!("\"{label}\" - {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() {
+        let profile = r#"
+[common]
+directories = ["/a", "/b"]
+excluded_items = ["*/.git/*"]
+
+[[tools]]
+tool = "dup"
+search-method = "SIZE"
+delete-method = "AEN"
+file-to-save = "dup.txt"
+
+[[tools]]
+tool = "empty-files"
+directories = ["/c"]
+
+[[tools]]
+tool = "video-optimizer"
+subcommand = "transcode"
+subcommand-args = { target-codec = "h265" }
+"#;
+        let tools = parse_profile(profile).expect("Profile should be valid");
+        assert_eq!(
+            tools.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+            vec!["dup", "empty-files", "video-optimizer transcode"]
+        );
+        assert!(matches!(&tools[0].1, Commands::Duplicates(args) if args.common_cli_items.directories.len() == 2 && args.common_cli_items.excluded_items.len() == 1));
+        assert!(matches!(&tools[1].1, Commands::EmptyFiles(args) if args.common_cli_items.directories.len() == 1));
+    }
+
+    #[test]
+    fn test_parse_invalid_profile() {
+        let profile = r#"
+[common]
+directories = ["/a"]
+
+[[tools]]
+tool = "dup"
+unknown-argument = true
+
+[[tools]]
+tool = "empty-files"
+
+[[tools]]
+tool = "undo"
+"#;
+        let errors = parse_profile(profile).expect_err("Profile should be invalid");
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("Tool 1 - \"dup\""));
+        assert!(errors[1].starts_with("Tool 3 - \"undo\""));
+
+        let errors = parse_profile("[common]\ndirectories = [\"/a\"]\n[[tools]]\ntool = \"dup\"\nthread-number = 2\n[[tools]]\ntool = \"empty-files\"")
+            .expect_err("Profile with different number of threads should be invalid");
+        assert_eq!(errors.len(), 1);
+        parse_profile("[common]\ndirectories = [\"/a\"]\nthread-number = 2\n[[tools]]\ntool = \"dup\"\n[[tools]]\ntool = \"empty-files\"")
+            .expect("Profile with common number of threads should be valid");
+
+        parse_profile("[common]\ndirectories = [\"/a\"]").expect_err("Profile without tools should be invalid");
+        parse_profile("[[tools]]\ntool = \"dup\"\ndirectories = [\"/a\"]\n[other]").expect_err("Profile with unknown table should be invalid");
+    }
+
+    #[test]
+    fn test_group_tools() {
+        let profile = r#"
+[common]
+directories = ["/a"]
+
+[[tools]]
+tool = "dup"
+
+[[tools]]
+tool = "empty-files"
+
+[[tools]]
+tool = "temp"
+
+[[tools]]
+tool = "image"
+
+[[tools]]
+tool = "big"
+
+[[tools]]
+tool = "broken"
+"#;
+        let tools = parse_profile(profile).expect("Profile should be valid");
+        let groups: Vec<Vec<&str>> = group_tools(&tools).iter().map(|group| group.iter().map(|(name, _)| name.as_str()).collect()).collect();
+        assert_eq!(groups, vec![vec!["dup", "empty-files", "temp"], vec!["image"], vec!["big", "broken"]]);
+    }
+}
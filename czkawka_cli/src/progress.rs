@@ -1,8 +1,17 @@
-use std::time::Duration;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Receiver;
 use czkawka_core::common::progress_data::{ProgressData, ToolStage};
 use indicatif::{ProgressBar, ProgressStyle};
+use log::error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    Bars,
+    Json,
+}
 
 pub(crate) fn connect_progress(progress_receiver: &Receiver<ProgressData>) {
     let mut pb = ProgressBar::new(1);
@@ -33,6 +42,76 @@ pub(crate) fn connect_progress(progress_receiver: &Receiver<ProgressData>) {
     pb.finish_and_clear();
 }
 
+pub(crate) fn connect_progress_json(progress_receiver: &Receiver<ProgressData>, progress_fd: Option<u32>) {
+    let mut writer: Box<dyn Write> = match progress_fd {
+        Some(fd) => match OpenOptions::new().append(true).open(format!("/dev/fd/{fd}")) {
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                error!("Failed to open file descriptor {fd} for progress, using stderr instead - {e}");
+                Box::new(std::io::stderr())
+            }
+        },
+        None => Box::new(std::io::stderr()),
+    };
+
+    let mut eta_estimator = EtaEstimator::default();
+    while let Ok(progress_data) = progress_receiver.recv() {
+        let eta_seconds = eta_estimator.update(&progress_data, Instant::now());
+        if let Err(e) = writeln!(writer, "{}", progress_to_json(&progress_data, eta_seconds)).and_then(|()| writer.flush()) {
+            error!("Failed to write progress - {e}");
+            return;
+        }
+    }
+}
+
+pub(crate) fn progress_to_json(progress_data: &ProgressData, eta_seconds: Option<u64>) -> serde_json::Value {
+    serde_json::json!({
+        "stage": progress_data.stage,
+        // Translated label contains unicode isolation marks around arguments, which are not needed outside of GUI
+        "label": progress_data.to_display().label.replace(['\u{2068}', '\u{2069}'], ""),
+        "current_stage_idx": progress_data.stage.current_stage_idx(),
+        "max_stage_idx": progress_data.stage.max_stage_idx(),
+        "entries_checked": progress_data.entries_checked,
+        "entries_to_check": progress_data.entries_to_check,
+        "bytes_checked": progress_data.bytes_checked,
+        "bytes_to_check": progress_data.bytes_to_check,
+        "eta_seconds": eta_seconds,
+    })
+}
+
+// Remaining time is estimated from average speed since start of current stage
+#[derive(Default)]
+pub(crate) struct EtaEstimator {
+    stage_start: Option<(ToolStage, Instant)>,
+}
+
+impl EtaEstimator {
+    pub(crate) fn update(&mut self, progress_data: &ProgressData, now: Instant) -> Option<u64> {
+        let stage_start = match self.stage_start {
+            Some((stage, start)) if stage == progress_data.stage => start,
+            _ => {
+                self.stage_start = Some((progress_data.stage, now));
+                now
+            }
+        };
+
+        if progress_data.stage.is_indeterminate() {
+            return None;
+        }
+        let (checked, to_check) = if progress_data.bytes_to_check != 0 {
+            (progress_data.bytes_checked, progress_data.bytes_to_check)
+        } else {
+            (progress_data.entries_checked as u64, progress_data.entries_to_check as u64)
+        };
+        if checked == 0 || checked > to_check {
+            return None;
+        }
+
+        let elapsed = now.duration_since(stage_start).as_secs_f64();
+        Some((elapsed * (to_check - checked) as f64 / checked as f64).round() as u64)
+    }
+}
+
 pub(crate) fn get_progress_spinner() -> ProgressBar {
     let pb = ProgressBar::new_spinner();
     pb.enable_steady_tick(Duration::from_millis(120));
@@ -54,3 +133,34 @@ pub(crate) fn get_progress_known_values(max_value: u64) -> ProgressBar {
     );
     pb
 }
+
+#[cfg(test)]
+mod tests {
+    use czkawka_core::common::progress_data::DuplicateStage;
+
+    use super::*;
+
+    #[test]
+    fn test_eta_and_json_progress() {
+        let start = Instant::now();
+        let mut eta_estimator = EtaEstimator::default();
+        let mut progress_data = ProgressData::new(ToolStage::Duplicate(DuplicateStage::FullHashing), 10, 1000);
+
+        assert_eq!(eta_estimator.update(&progress_data, start), None);
+        progress_data.bytes_checked = 250;
+        assert_eq!(eta_estimator.update(&progress_data, start + Duration::from_secs(10)), Some(30));
+
+        let json = progress_to_json(&progress_data, Some(30));
+        assert_eq!(json["stage"], serde_json::json!({"Duplicate": "FullHashing"}));
+        assert_eq!(json["current_stage_idx"], 6);
+        assert_eq!(json["bytes_checked"], 250);
+        assert_eq!(json["eta_seconds"], 30);
+
+        // New stage starts measuring time from beginning
+        let progress_data = ProgressData {
+            entries_checked: 5,
+            ..ProgressData::new(ToolStage::Duplicate(DuplicateStage::PreHashing), 10, 0)
+        };
+        assert_eq!(eta_estimator.update(&progress_data, start + Duration::from_secs(20)), Some(0));
+    }
+}
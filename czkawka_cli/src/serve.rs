@@ -0,0 +1,678 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::TcpListener;
+#[cfg(target_family = "unix")]
+use std::os::unix::fs::OpenOptionsExt;
+#[cfg(target_family = "unix")]
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use clap::{CommandFactory, Parser};
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use czkawka_core::common::consts::DEFAULT_THREAD_SIZE;
+use czkawka_core::common::model::WorkContinueStatus;
+use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::traits::{AllTraits, FixingItems};
+use czkawka_core::helpers::messages::Messages;
+use log::{debug, error, info};
+use serde_json::{Value, json};
+
+use crate::commands::{
+    Args, Commands, DMethod, NON_TOOL_COMMANDS, SDMethod, ServeAdvancedDeleteArgs, ServeArgs, ServeSimpleDeleteArgs, cli_args_from_json, deletes_after_search, parse_tool_command,
+    tool_common_cli_items,
+};
+use crate::progress::{EtaEstimator, progress_to_json};
+use crate::{
+    CliOutput, prepare_bad_extensions, prepare_bad_names, prepare_biggest_files, prepare_broken_files, prepare_duplicate_finder, prepare_duplicate_folders, prepare_empty_files,
+    prepare_empty_folders, prepare_exif_remover, prepare_invalid_symlinks, prepare_same_music, prepare_similar_images, prepare_similar_videos, prepare_temporary,
+    prepare_video_optimizer, set_advanced_delete, set_simple_delete,
+};
+
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SCAN_ERROR: i64 = -32000;
+const UNAUTHORIZED: i64 = -32001;
+
+type ConnectionWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+type FixFunction<T> = Box<dyn FnOnce(&mut T, &Arc<AtomicBool>, &Sender<ProgressData>)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeleteKind {
+    None,
+    Simple,
+    Advanced,
+}
+
+enum DeleteOptions {
+    Simple(SDMethod),
+    Advanced(DMethod),
+}
+
+enum ScanRequest {
+    Results(Sender<Result<Value, String>>),
+    Delete(DeleteOptions, Sender<Result<Value, String>>),
+    Fix(Sender<Result<Value, String>>),
+}
+
+#[derive(Clone)]
+struct ScanHandle {
+    delete_kind: DeleteKind,
+    stop_flag: Arc<AtomicBool>,
+    // Set while scan, deleting or fixing is in progress
+    running: Arc<AtomicBool>,
+    progress: Arc<Mutex<Option<Value>>>,
+    request_sender: Sender<ScanRequest>,
+}
+
+struct Server {
+    scans: Mutex<BTreeMap<u64, ScanHandle>>,
+    next_scan_id: AtomicU64,
+    // Generated at every start, so only programs which can read it from output or token file may use server
+    token: String,
+    // Global thread pool can be built only once, so number of threads is set by first scan and cannot be changed later
+    thread_number: Mutex<Option<usize>>,
+}
+
+impl Server {
+    fn new(token: String) -> Self {
+        Self {
+            scans: Mutex::new(BTreeMap::new()),
+            next_scan_id: AtomicU64::new(0),
+            token,
+            thread_number: Mutex::new(None),
+        }
+    }
+
+    fn set_thread_number(&self, thread_number: usize) -> Result<(), RpcError> {
+        let mut current_thread_number = self.thread_number.lock().expect("Cannot fail");
+        match *current_thread_number {
+            Some(current) if current != thread_number => Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Number of threads was already set to {current} by previous scan and cannot be changed without restarting server"),
+            )),
+            _ => {
+                *current_thread_number = Some(thread_number);
+                Ok(())
+            }
+        }
+    }
+
+    // Compares every byte, so time of comparison doesn't reveal how many first characters are valid
+    fn is_valid_token(&self, token: &str) -> bool {
+        self.token.len() == token.len() && self.token.bytes().zip(token.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+/// Line based JSON-RPC 2.0 server.
+///
+/// Every request and response is a single line of JSON. Progress of scans is sent as `progress` notifications and end of scan as `finished` notification,
+/// both only to connection which started the scan.
+/// First request of every connection must be `auth` with token generated at start of server. Connection is closed after failed authentication or any line which is not valid JSON.
+pub(crate) fn serve(serve: &ServeArgs, stop_flag: &Arc<AtomicBool>) -> CliOutput {
+    let token = format!("{:032x}", rand::random::<u128>());
+    let token_result = match &serve.token_file {
+        Some(token_file) => save_token(token_file, &token),
+        None => {
+            print_token(&token);
+            Ok(())
+        }
+    };
+    let server = Arc::new(Server::new(token));
+
+    let result = token_result.and_then(|()| match (&serve.address, &serve.socket) {
+        (Some(address), _) => TcpListener::bind(address).map_err(|e| format!("Failed to listen on {address} - {e}")).and_then(|listener| {
+            info!("Listening on {address}");
+            accept_connections(
+                &server,
+                stop_flag,
+                || listener.accept().map(|(stream, _)| stream),
+                |stream| {
+                    stream.set_nonblocking(false)?;
+                    Ok((stream.try_clone()?, stream))
+                },
+                |nonblocking| listener.set_nonblocking(nonblocking),
+            )
+        }),
+        #[cfg(target_family = "unix")]
+        (None, Some(socket)) => UnixListener::bind(socket)
+            .map_err(|e| format!("Failed to listen on socket \"{}\" - {e}", socket.to_string_lossy()))
+            .and_then(|listener| {
+                info!("Listening on socket \"{}\"", socket.to_string_lossy());
+                let result = accept_connections(
+                    &server,
+                    stop_flag,
+                    || listener.accept().map(|(stream, _)| stream),
+                    |stream| {
+                        stream.set_nonblocking(false)?;
+                        Ok((stream.try_clone()?, stream))
+                    },
+                    |nonblocking| listener.set_nonblocking(nonblocking),
+                );
+                if let Err(e) = std::fs::remove_file(socket) {
+                    error!("Failed to remove socket \"{}\" - {e}", socket.to_string_lossy());
+                }
+                result
+            }),
+        #[cfg(not(target_family = "unix"))]
+        (None, Some(_)) => Err("Unix sockets are only available on Unix systems".to_string()),
+        (None, None) => Err("Address or socket must be set".to_string()),
+    });
+
+    for scan in server.scans.lock().expect("Cannot fail").values() {
+        scan.stop_flag.store(true, Ordering::Relaxed);
+    }
+
+    CliOutput {
+        found_any_files: result.is_err(),
+        ignored_error_code_on_found: false,
+        output: result.err().map(|e| format!("Error: {e}")).unwrap_or_default(),
+    }
+}
+
+#[expect(clippy::print_stdout)]
+fn print_token(token: &str) {
+    println!("Token: {token}");
+}
+
+fn save_token(token_file: &Path, token: &str) -> Result<(), String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(target_family = "unix")]
+    options.mode(0o600);
+    options
+        .open(token_file)
+        .and_then(|mut file| file.write_all(token.as_bytes()))
+        .map_err(|e| format!("Failed to save token to \"{}\" - {e}", token_file.to_string_lossy()))
+}
+
+fn accept_connections<S, R, W>(
+    server: &Arc<Server>,
+    stop_flag: &Arc<AtomicBool>,
+    accept: impl Fn() -> std::io::Result<S>,
+    split: impl Fn(S) -> std::io::Result<(R, W)>,
+    set_nonblocking: impl Fn(bool) -> std::io::Result<()>,
+) -> Result<(), String>
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    // Non-blocking accept allows to notice Ctrl+C
+    set_nonblocking(true).map_err(|e| format!("Failed to set listener as non-blocking - {e}"))?;
+    while !stop_flag.load(Ordering::Relaxed) {
+        let stream = match accept() {
+            Ok(stream) => stream,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                sleep(ACCEPT_INTERVAL);
+                continue;
+            }
+            Err(e) => {
+                error!("Failed to accept connection - {e}");
+                continue;
+            }
+        };
+        let (reader, writer) = match split(stream) {
+            Ok(streams) => streams,
+            Err(e) => {
+                error!("Failed to prepare connection - {e}");
+                continue;
+            }
+        };
+        let server = server.clone();
+        let writer: ConnectionWriter = Arc::new(Mutex::new(Box::new(writer)));
+        thread::spawn(move || handle_connection(reader, &writer, &server));
+    }
+    Ok(())
+}
+
+fn handle_connection<R: Read>(reader: R, writer: &ConnectionWriter, server: &Arc<Server>) {
+    debug!("New connection");
+    let mut authenticated = false;
+    for line in BufReader::new(reader).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                debug!("Connection closed - {e}");
+                return;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, keep_connection) = handle_line(&line, &mut authenticated, writer, server);
+        send_line(writer, &response);
+        if !keep_connection {
+            debug!("Connection closed after invalid request");
+            return;
+        }
+    }
+    debug!("Connection closed");
+}
+
+fn send_line(writer: &ConnectionWriter, value: &Value) {
+    let mut writer = writer.lock().expect("Cannot fail");
+    // Client may disconnect at any time, e.g. before progress of its scan ends
+    if let Err(e) = writeln!(writer, "{value}").and_then(|()| writer.flush()) {
+        debug!("Failed to send message to client - {e}");
+    }
+}
+
+fn send_notification(writer: &ConnectionWriter, method: &str, params: &Value) {
+    send_line(writer, &json!({"jsonrpc": "2.0", "method": method, "params": params}));
+}
+
+// Returns response and information whether connection may be still used
+fn handle_line(line: &str, authenticated: &mut bool, writer: &ConnectionWriter, server: &Arc<Server>) -> (Value, bool) {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        // Other protocols(e.g. HTTP request sent by web page) start with line which is not JSON, so connection is closed before reading anything else
+        Err(e) => return (error_response(&Value::Null, &RpcError::new(PARSE_ERROR, format!("Invalid JSON - {e}"))), false),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return (error_response(&id, &RpcError::new(INVALID_PARAMS, "Missing method")), *authenticated);
+    };
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    if !*authenticated {
+        if method != "auth" {
+            return (error_response(&id, &RpcError::new(UNAUTHORIZED, "First request must be auth")), false);
+        }
+        if !params.get("token").and_then(Value::as_str).is_some_and(|token| server.is_valid_token(token)) {
+            return (error_response(&id, &RpcError::new(UNAUTHORIZED, "Invalid token")), false);
+        }
+        *authenticated = true;
+        return (json!({"jsonrpc": "2.0", "id": id, "result": {"authenticated": true}}), true);
+    }
+
+    let response = match handle_request(method, &params, writer, server) {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(e) => error_response(&id, &e),
+    };
+    (response, true)
+}
+
+fn error_response(id: &Value, error: &RpcError) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": error.code, "message": error.message}})
+}
+
+fn handle_request(method: &str, params: &Value, writer: &ConnectionWriter, server: &Arc<Server>) -> Result<Value, RpcError> {
+    match method {
+        "tools" => Ok(list_tools()),
+        "start" => start_scan(params, writer, server),
+        "progress" => with_scan(params, server, |scan_id, scan| {
+            Ok(json!({"scan_id": scan_id, "running": scan.running.load(Ordering::Relaxed), "progress": *scan.progress.lock().expect("Cannot fail")}))
+        }),
+        "cancel" => with_scan(params, server, |scan_id, scan| {
+            scan.stop_flag.store(true, Ordering::Relaxed);
+            Ok(json!({"scan_id": scan_id}))
+        }),
+        "results" => with_finished_scan(params, server, |_, scan| send_scan_request(scan, ScanRequest::Results)),
+        "delete" => with_finished_scan(params, server, |_, scan| {
+            let args = cli_args_from_json(params.get("args")).map_err(|e| RpcError::new(INVALID_PARAMS, e))?;
+            let delete_options = match scan.delete_kind {
+                DeleteKind::None => return Err(RpcError::new(SCAN_ERROR, "This tool doesn't delete files")),
+                DeleteKind::Simple => ServeSimpleDeleteArgs::try_parse_from(std::iter::once("delete".to_string()).chain(args))
+                    .map(|args| DeleteOptions::Simple(args.delete_method))
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?,
+                DeleteKind::Advanced => ServeAdvancedDeleteArgs::try_parse_from(std::iter::once("delete".to_string()).chain(args))
+                    .map(|args| DeleteOptions::Advanced(args.delete_method))
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?,
+            };
+            send_scan_request(scan, |reply| ScanRequest::Delete(delete_options, reply))
+        }),
+        "fix" => with_finished_scan(params, server, |_, scan| send_scan_request(scan, ScanRequest::Fix)),
+        "close" => {
+            let scan_id = get_scan_id(params)?;
+            let scan = server.scans.lock().expect("Cannot fail").remove(&scan_id).ok_or_else(|| unknown_scan(scan_id))?;
+            // Scan thread ends after finishing current operation, when channel is closed
+            scan.stop_flag.store(true, Ordering::Relaxed);
+            Ok(json!({"scan_id": scan_id}))
+        }
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method \"{method}\""))),
+    }
+}
+
+fn list_tools() -> Value {
+    Args::command()
+        .get_subcommands()
+        .filter(|command| !NON_TOOL_COMMANDS.contains(&command.get_name()))
+        .map(|command| {
+            json!({
+                "name": command.get_name(),
+                "about": command.get_about().map(ToString::to_string),
+                "subcommands": command.get_subcommands().map(|subcommand| subcommand.get_name().to_string()).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
+
+fn get_scan_id(params: &Value) -> Result<u64, RpcError> {
+    params
+        .get("scan_id")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing scan_id"))
+}
+
+fn unknown_scan(scan_id: u64) -> RpcError {
+    RpcError::new(INVALID_PARAMS, format!("Unknown scan {scan_id}"))
+}
+
+// Handle is cloned, so lock is not held while waiting for scan thread and other requests(e.g. cancel) may be handled meanwhile
+fn with_scan(params: &Value, server: &Server, func: impl FnOnce(u64, &ScanHandle) -> Result<Value, RpcError>) -> Result<Value, RpcError> {
+    let scan_id = get_scan_id(params)?;
+    let scan = server.scans.lock().expect("Cannot fail").get(&scan_id).cloned().ok_or_else(|| unknown_scan(scan_id))?;
+    func(scan_id, &scan)
+}
+
+fn with_finished_scan(params: &Value, server: &Server, func: impl FnOnce(u64, &ScanHandle) -> Result<Value, RpcError>) -> Result<Value, RpcError> {
+    with_scan(params, server, |scan_id, scan| {
+        if scan.running.load(Ordering::Relaxed) {
+            return Err(RpcError::new(SCAN_ERROR, format!("Scan {scan_id} is still running")));
+        }
+        func(scan_id, scan)
+    })
+}
+
+fn send_scan_request(scan: &ScanHandle, request: impl FnOnce(Sender<Result<Value, String>>) -> ScanRequest) -> Result<Value, RpcError> {
+    let (reply_sender, reply_receiver) = unbounded();
+    scan.request_sender
+        .send(request(reply_sender))
+        .map_err(|_| RpcError::new(SCAN_ERROR, "Scan is no longer available"))?;
+    reply_receiver
+        .recv()
+        .map_err(|_| RpcError::new(SCAN_ERROR, "Scan is no longer available"))?
+        .map_err(|e| RpcError::new(SCAN_ERROR, e))
+}
+
+fn parse_start_params(params: &Value) -> Result<Commands, RpcError> {
+    let Some(tool) = params.get("tool").and_then(Value::as_str) else {
+        return Err(RpcError::new(INVALID_PARAMS, "Missing tool"));
+    };
+    let subcommand = params.get("subcommand").and_then(Value::as_str);
+    parse_tool_command(tool, params.get("args"), subcommand, params.get("subcommand_args")).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+}
+
+fn start_scan(params: &Value, writer: &ConnectionWriter, server: &Arc<Server>) -> Result<Value, RpcError> {
+    let command = parse_start_params(params)?;
+    // Files may be deleted only by explicit delete request, after client checked results
+    if deletes_after_search(&command) {
+        return Err(RpcError::new(INVALID_PARAMS, "Delete arguments are not allowed in start, use delete request after scan"));
+    }
+    if let Some(common_cli_items) = tool_common_cli_items(&command) {
+        server.set_thread_number(common_cli_items.thread_number)?;
+    }
+    let delete_kind = match &command {
+        Commands::Duplicates(_) | Commands::DuplicateFolders(_) | Commands::SimilarImages(_) | Commands::SameMusic(_) | Commands::SimilarVideos(_) => DeleteKind::Advanced,
+        Commands::EmptyFolders(_)
+        | Commands::BiggestFiles(_)
+        | Commands::EmptyFiles(_)
+        | Commands::Temporary(_)
+        | Commands::InvalidSymlinks(_)
+        | Commands::BrokenFiles(_)
+        | Commands::BadNames(_) => DeleteKind::Simple,
+        _ => DeleteKind::None,
+    };
+
+    let scan_id = server.next_scan_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let (request_sender, request_receiver) = unbounded();
+    let (progress_sender, progress_receiver) = unbounded();
+    let scan = ScanHandle {
+        delete_kind,
+        stop_flag: Arc::new(AtomicBool::new(false)),
+        running: Arc::new(AtomicBool::new(true)),
+        progress: Arc::new(Mutex::new(None)),
+        request_sender,
+    };
+
+    let progress = scan.progress.clone();
+    let progress_writer = writer.clone();
+    thread::spawn(move || forward_progress(scan_id, &progress_receiver, &progress, &progress_writer));
+
+    let stop_flag = scan.stop_flag.clone();
+    let running = scan.running.clone();
+    let writer = writer.clone();
+    thread::Builder::new()
+        .stack_size(DEFAULT_THREAD_SIZE)
+        .spawn(move || {
+            let scan = ScanThread {
+                scan_id,
+                stop_flag,
+                running,
+                progress_sender,
+                writer,
+            };
+            match command {
+                Commands::Duplicates(args) => scan.run(prepare_duplicate_finder(&args), None, &request_receiver),
+                Commands::DuplicateFolders(args) => scan.run(prepare_duplicate_folders(&args), None, &request_receiver),
+                Commands::EmptyFolders(args) => scan.run(prepare_empty_folders(&args), None, &request_receiver),
+                Commands::BiggestFiles(args) => scan.run(prepare_biggest_files(&args), None, &request_receiver),
+                Commands::EmptyFiles(args) => scan.run(prepare_empty_files(&args), None, &request_receiver),
+                Commands::Temporary(args) => scan.run(prepare_temporary(&args), None, &request_receiver),
+                Commands::SimilarImages(args) => scan.run(prepare_similar_images(&args), None, &request_receiver),
+                Commands::SameMusic(args) => scan.run(prepare_same_music(&args), None, &request_receiver),
+                Commands::InvalidSymlinks(args) => scan.run(prepare_invalid_symlinks(&args), None, &request_receiver),
+                Commands::BrokenFiles(args) => scan.run(prepare_broken_files(&args), None, &request_receiver),
+                Commands::SimilarVideos(args) => scan.run(prepare_similar_videos(&args), None, &request_receiver),
+                Commands::BadExtensions(args) => {
+                    let (tool, fix_params) = prepare_bad_extensions(&args);
+                    scan.run(tool, fix_function(fix_params), &request_receiver);
+                }
+                Commands::BadNames(args) => {
+                    let (tool, fix_params) = prepare_bad_names(&args);
+                    scan.run(tool, fix_function(fix_params), &request_receiver);
+                }
+                Commands::VideoOptimizer(args) => {
+                    let (tool, fix_params) = prepare_video_optimizer(&args);
+                    scan.run(tool, fix_function(fix_params), &request_receiver);
+                }
+                Commands::ExifRemover(args) => {
+                    let (tool, fix_params) = prepare_exif_remover(&args);
+                    scan.run(tool, fix_function(fix_params), &request_receiver);
+                }
+                Commands::Undo(_) | Commands::Restore(_) | Commands::Cache(_) | Commands::Watch(_) | Commands::Serve(_) | Commands::Run(_) => {
+                    unreachable!("Checked before parsing")
+                }
+            }
+        })
+        .map_err(|e| RpcError::new(SCAN_ERROR, format!("Failed to start scan - {e}")))?;
+
+    server.scans.lock().expect("Cannot fail").insert(scan_id, scan);
+    info!("Started scan {scan_id}");
+    Ok(json!({"scan_id": scan_id}))
+}
+
+fn fix_function<T: FixingItems + 'static>(fix_params: Option<T::FixParams>) -> Option<FixFunction<T>>
+where
+    T::FixParams: 'static,
+{
+    fix_params.map(|fix_params| -> FixFunction<T> { Box::new(move |tool: &mut T, stop_flag, progress_sender| tool.fix_items(stop_flag, Some(progress_sender), fix_params)) })
+}
+
+fn forward_progress(scan_id: u64, progress_receiver: &Receiver<ProgressData>, progress: &Mutex<Option<Value>>, writer: &ConnectionWriter) {
+    let mut eta_estimator = EtaEstimator::default();
+    for progress_data in progress_receiver {
+        let eta_seconds = eta_estimator.update(&progress_data, Instant::now());
+        let progress_json = progress_to_json(&progress_data, eta_seconds);
+        *progress.lock().expect("Cannot fail") = Some(progress_json.clone());
+        send_notification(writer, "progress", &json!({"scan_id": scan_id, "progress": progress_json}));
+    }
+}
+
+struct ScanThread {
+    scan_id: u64,
+    stop_flag: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    progress_sender: Sender<ProgressData>,
+    writer: ConnectionWriter,
+}
+
+impl ScanThread {
+    // Tool is kept in its thread after search, so results can be fetched and deleted or fixed later
+    fn run<T: AllTraits>(self, mut tool: T, mut fix: Option<FixFunction<T>>, request_receiver: &Receiver<ScanRequest>) {
+        tool.search(&self.stop_flag, Some(&self.progress_sender));
+        self.finish(&tool, "finished");
+
+        for request in request_receiver {
+            match request {
+                ScanRequest::Results(reply) => {
+                    let mut results = Vec::new();
+                    let results = tool
+                        .write_results_as_json(&mut results, false)
+                        .map_err(|e| e.to_string())
+                        .and_then(|()| serde_json::from_slice::<Value>(&results).map_err(|e| e.to_string()))
+                        .map(|results| json!({"found_any_items": tool.found_any_items(), "results": results, "messages": messages_to_json(tool.get_text_messages())}));
+                    let _ = reply.send(results);
+                }
+                ScanRequest::Delete(delete_options, reply) => {
+                    self.start_action();
+                    match delete_options {
+                        DeleteOptions::Simple(delete_method) => set_simple_delete(&mut tool, delete_method),
+                        DeleteOptions::Advanced(delete_method) => set_advanced_delete(&mut tool, delete_method),
+                    }
+                    let stopped = tool.delete_files(&self.stop_flag, Some(&self.progress_sender)) == WorkContinueStatus::Stop;
+                    self.finish(&tool, "deleted");
+                    let _ = reply.send(Ok(json!({"stopped": stopped, "messages": messages_to_json(tool.get_text_messages())})));
+                }
+                ScanRequest::Fix(reply) => {
+                    let Some(fix) = fix.take() else {
+                        let _ = reply.send(Err("Scan was started without fix arguments or items were already fixed".to_string()));
+                        continue;
+                    };
+                    self.start_action();
+                    fix(&mut tool, &self.stop_flag, &self.progress_sender);
+                    self.finish(&tool, "fixed");
+                    let _ = reply.send(Ok(json!({"messages": messages_to_json(tool.get_text_messages())})));
+                }
+            }
+        }
+        debug!("Scan {} closed", self.scan_id);
+    }
+
+    fn start_action(&self) {
+        // Stop flag may be set by cancelling previous action
+        self.stop_flag.store(false, Ordering::Relaxed);
+        self.running.store(true, Ordering::Relaxed);
+    }
+
+    fn finish<T: AllTraits>(&self, tool: &T, notification: &str) {
+        self.running.store(false, Ordering::Relaxed);
+        send_notification(
+            &self.writer,
+            notification,
+            &json!({"scan_id": self.scan_id, "stopped": self.stop_flag.load(Ordering::Relaxed), "found_any_items": tool.found_any_items()}),
+        );
+    }
+}
+
+fn messages_to_json(messages: &Messages) -> Value {
+    json!({
+        "critical": messages.critical,
+        "messages": messages.messages,
+        "warnings": messages.warnings,
+        "errors": messages.errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start_params() {
+        let command = parse_start_params(&json!({"tool": "dup", "args": {"directories": ["/a"], "search-method": "SIZE"}}));
+        assert!(matches!(command, Ok(Commands::Duplicates(args)) if args.common_cli_items.directories.len() == 1));
+        assert!(matches!(
+            parse_start_params(&json!({"tool": "video-optimizer", "args": {"directories": "/a"}, "subcommand": "transcode", "subcommand_args": {"target-codec": "h265"}})),
+            Ok(Commands::VideoOptimizer(_))
+        ));
+        parse_start_params(&json!({"tool": "undo", "args": {"journal": "/a"}})).unwrap_err();
+        parse_start_params(&json!({"tool": "dup", "args": {"unknown-argument": true}})).unwrap_err();
+
+        let deleting = parse_start_params(&json!({"tool": "dup", "args": {"directories": ["/a"], "delete-method": "AEN"}})).expect("Cannot parse params");
+        assert!(deletes_after_search(&deleting));
+        let deleting = parse_start_params(&json!({"tool": "empty-files", "args": {"directories": ["/a"], "delete-files": true}})).expect("Cannot parse params");
+        assert!(deletes_after_search(&deleting));
+        let searching = parse_start_params(&json!({"tool": "dup", "args": {"directories": ["/a"]}})).expect("Cannot parse params");
+        assert!(!deletes_after_search(&searching));
+    }
+
+    #[test]
+    fn test_authentication() {
+        let server = Arc::new(Server::new("secret".to_string()));
+        let writer: ConnectionWriter = Arc::new(Mutex::new(Box::new(std::io::sink())));
+
+        let mut authenticated = false;
+        let (response, keep_connection) = handle_line(r#"{"id": 1, "method": "list"}"#, &mut authenticated, &writer, &server);
+        assert!(!keep_connection);
+        assert_eq!(response["error"]["code"], UNAUTHORIZED);
+
+        let (_, keep_connection) = handle_line(r#"{"id": 1, "method": "auth", "params": {"token": "secreT"}}"#, &mut authenticated, &writer, &server);
+        assert!(!keep_connection && !authenticated);
+
+        let (response, keep_connection) = handle_line(r#"{"id": 1, "method": "auth", "params": {"token": "secret"}}"#, &mut authenticated, &writer, &server);
+        assert!(keep_connection && authenticated);
+        assert_eq!(response["result"]["authenticated"], true);
+
+        let (response, keep_connection) = handle_line("GET / HTTP/1.1", &mut authenticated, &writer, &server);
+        assert!(!keep_connection);
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+
+    #[test]
+    fn test_thread_number_cannot_change() {
+        let server = Server::new("secret".to_string());
+        server.set_thread_number(4).expect("First scan sets number of threads");
+        server.set_thread_number(4).expect("Same number of threads is allowed");
+        assert_eq!(server.set_thread_number(2).unwrap_err().code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_scan_can_be_cancelled_while_waiting_for_results() {
+        let server = Arc::new(Server::new("secret".to_string()));
+        let writer: ConnectionWriter = Arc::new(Mutex::new(Box::new(std::io::sink())));
+        let (request_sender, request_receiver) = unbounded();
+        let scan = ScanHandle {
+            delete_kind: DeleteKind::None,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            running: Arc::new(AtomicBool::new(false)),
+            progress: Arc::new(Mutex::new(None)),
+            request_sender,
+        };
+        let stop_flag = scan.stop_flag.clone();
+        server.scans.lock().expect("Cannot fail").insert(1, scan);
+
+        let results_server = server.clone();
+        let results_writer = writer.clone();
+        let results = thread::spawn(move || handle_request("results", &json!({"scan_id": 1}), &results_writer, &results_server));
+        let Ok(ScanRequest::Results(reply)) = request_receiver.recv() else {
+            panic!("Expected results request");
+        };
+
+        // Results request is still waiting for reply
+        handle_request("cancel", &json!({"scan_id": 1}), &writer, &server).expect("Cannot cancel scan");
+        assert!(stop_flag.load(Ordering::Relaxed));
+
+        reply.send(Ok(json!([]))).expect("Cannot send reply");
+        assert_eq!(results.join().expect("Thread panicked").expect("Cannot get results"), json!([]));
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crossbeam_channel::{Sender, unbounded};
+use czkawka_core::common::dir_snapshot::invalidate_dir_snapshot;
+use czkawka_core::common::fs_watcher::FsWatcher;
+use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::tool_data::CommonData;
+use czkawka_core::common::traits::Search;
+use log::{error, info, warn};
+use serde_json::json;
+
+use crate::commands::{WatchArgs, WatchTool};
+use crate::{CliOutput, prepare_duplicate_finder, prepare_similar_images};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchEventFormat {
+    Log,
+    Json,
+}
+
+pub(crate) fn watch(watch: WatchArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let WatchArgs {
+        tool,
+        events,
+        hook,
+        debounce,
+        poll_interval,
+        report_existing,
+    } = watch;
+
+    // Files would be deleted again after every rescan, without any chance to check results
+    if watch_tool_deletes(&tool) {
+        return CliOutput {
+            found_any_files: true,
+            ignored_error_code_on_found: false,
+            output: "Error: Delete arguments cannot be used in watch mode, use hook to handle found files".to_string(),
+        };
+    }
+
+    let mut fs_watcher: Option<FsWatcher> = None;
+    let mut previous_group_files: Option<HashSet<PathBuf>> = None;
+    loop {
+        let (event_name, groups) = match &tool {
+            WatchTool::Duplicates(args) => {
+                let mut finder = prepare_duplicate_finder(args);
+                let (results_sender, results_receiver) = unbounded();
+                finder.set_results_sender(results_sender);
+                run_scan(&mut finder, stop_flag, progress_sender, &mut fs_watcher, poll_interval);
+                (
+                    "duplicates",
+                    results_receiver.try_iter().map(|group| group.into_iter().map(|entry| entry.path).collect()).collect(),
+                )
+            }
+            WatchTool::SimilarImages(args) => {
+                let mut finder = prepare_similar_images(args);
+                let (results_sender, results_receiver) = unbounded();
+                finder.set_results_sender(results_sender);
+                run_scan(&mut finder, stop_flag, progress_sender, &mut fs_watcher, poll_interval);
+                (
+                    "similar_images",
+                    results_receiver.try_iter().map(|group| group.into_iter().map(|entry| entry.path).collect()).collect(),
+                )
+            }
+        };
+        if stop_flag.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let (new_groups, group_files) = find_new_groups(groups, previous_group_files.as_ref(), report_existing);
+        for (group, new_files) in new_groups {
+            report_group(event_name, &group, &new_files, events, hook.as_ref());
+        }
+        previous_group_files = Some(group_files);
+
+        let Some(fs_watcher) = fs_watcher.as_mut() else {
+            break;
+        };
+        let Some(changed_paths) = fs_watcher.wait_for_changes(stop_flag, Duration::from_secs(debounce)) else {
+            break;
+        };
+        info!("Detected {} changes, scanning again", changed_paths.len());
+        for warning in invalidate_dir_snapshot(&changed_paths) {
+            warn!("{warning}");
+        }
+    }
+
+    CliOutput {
+        found_any_files: false,
+        ignored_error_code_on_found: false,
+        output: String::new(),
+    }
+}
+
+fn run_scan<T: CommonData + Search>(tool: &mut T, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>, fs_watcher: &mut Option<FsWatcher>, poll_interval: u64) {
+    // Watcher is created before first search, so changes made while it is running are not missed
+    // They are read after search, including files in folders created during search, and trigger next rescan
+    let fs_watcher = fs_watcher.get_or_insert_with(|| FsWatcher::new(tool, Duration::from_secs(poll_interval)));
+
+    // Unchanged folders and already hashed files are taken from snapshot and cache, so rescans only check what changed
+    // Without inotify it is unknown which folders changed, so snapshot cannot be invalidated and all folders are read again
+    tool.set_use_dir_snapshot(fs_watcher.tracks_changes());
+    tool.search(stop_flag, Some(progress_sender));
+
+    for error in &tool.get_text_messages().errors {
+        error!("{error}");
+    }
+}
+
+fn watch_tool_deletes(tool: &WatchTool) -> bool {
+    match tool {
+        WatchTool::Duplicates(args) => args.delete_method.deletes(),
+        WatchTool::SimilarImages(args) => args.delete_method.deletes(),
+    }
+}
+
+/// Returns groups with files which were not part of any group during previous scan, together with these files, and files from all groups.
+/// Groups found during first scan are returned only with `report_existing`.
+fn find_new_groups(groups: Vec<Vec<PathBuf>>, previous_group_files: Option<&HashSet<PathBuf>>, report_existing: bool) -> (Vec<(Vec<PathBuf>, Vec<PathBuf>)>, HashSet<PathBuf>) {
+    let mut new_groups = Vec::new();
+    let mut group_files = HashSet::new();
+    for group in groups {
+        let new_files: Vec<PathBuf> = match previous_group_files {
+            Some(previous_group_files) => group.iter().filter(|path| !previous_group_files.contains(*path)).cloned().collect(),
+            None if report_existing => group.clone(),
+            None => Vec::new(),
+        };
+        group_files.extend(group.iter().cloned());
+        if !new_files.is_empty() {
+            new_groups.push((group, new_files));
+        }
+    }
+    (new_groups, group_files)
+}
+
+fn report_group(event_name: &str, group: &[PathBuf], new_files: &[PathBuf], events: WatchEventFormat, hook: Option<&PathBuf>) {
+    let event = json!({
+        "event": event_name,
+        "new_files": new_files,
+        "group": group,
+    });
+    match events {
+        WatchEventFormat::Log => info!(
+            "Found {} new {event_name} in group: {}",
+            new_files.len(),
+            group.iter().map(|path| format!("\"{}\"", path.to_string_lossy())).collect::<Vec<_>>().join(", ")
+        ),
+        #[expect(clippy::print_stdout)]
+        WatchEventFormat::Json => println!("{event}"),
+    }
+    if let Some(hook) = hook {
+        run_hook(hook, new_files, &event.to_string());
+    }
+}
+
+fn run_hook(hook: &PathBuf, new_files: &[PathBuf], event: &str) {
+    match Command::new(hook).args(new_files).env("CZKAWKA_EVENT", event).status() {
+        Ok(status) if !status.success() => warn!("Hook \"{}\" exited with {status}", hook.to_string_lossy()),
+        Ok(_) => {}
+        Err(e) => error!("Failed to run hook \"{}\" - {e}", hook.to_string_lossy()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::commands::{Args, Commands};
+
+    #[test]
+    fn test_find_new_groups() {
+        let groups = vec![vec![PathBuf::from("/a"), PathBuf::from("/b")]];
+        let (new_groups, group_files) = find_new_groups(groups.clone(), None, false);
+        assert!(new_groups.is_empty());
+        assert_eq!(group_files.len(), 2);
+        assert_eq!(find_new_groups(groups.clone(), None, true).0.len(), 1);
+
+        let groups = vec![
+            groups[0].clone(),
+            vec![PathBuf::from("/c"), PathBuf::from("/d")],
+            vec![PathBuf::from("/a"), PathBuf::from("/b"), PathBuf::from("/e")],
+        ];
+        let (new_groups, group_files) = find_new_groups(groups, Some(&group_files), false);
+        assert_eq!(new_groups.len(), 2);
+        assert_eq!(new_groups[0].1, vec![PathBuf::from("/c"), PathBuf::from("/d")]);
+        assert_eq!(new_groups[1].1, vec![PathBuf::from("/e")]);
+        assert_eq!(group_files.len(), 5);
+    }
+
+    #[test]
+    fn test_watch_tool_deletes() {
+        let parse_watch_tool = |args: &[&str]| match Args::try_parse_from([&["czkawka_cli", "watch"], args].concat()).expect("Cannot parse args").command {
+            Commands::Watch(watch_args) => watch_args.tool,
+            _ => panic!("Expected watch command"),
+        };
+        assert!(!watch_tool_deletes(&parse_watch_tool(&["dup", "-d", "/a"])));
+        assert!(watch_tool_deletes(&parse_watch_tool(&["dup", "-d", "/a", "-D", "AEN"])));
+        assert!(watch_tool_deletes(&parse_watch_tool(&["image", "-d", "/a", "-D", "AEO"])));
+    }
+}
@@ -25,6 +25,8 @@ core_must_be_directory_or_file = Provided path must point to a vaild directory o
 core_excluded_paths_pointless_slash = Excluding / is pointless, because it means no files will be scanned
 core_paths_unable_to_get_device_id = Unable to get device id from folder { $path }
 core_excluded_items_wildcard_required = Excluded Items Warning: Wildcard * is required in expression, ignoring { $expression }
+core_excluded_items_invalid_regex = Excluded Items Warning: Invalid regular expression { $expression }, reason { $reason }
+core_cannot_read_ignore_file = Cannot read ignore file {$file}, reason {$reason}
 
 core_needs_allowed_extensions_limited_by_tool = Cannot start scan, when all extensions available in this tool ({ $extensions }) were excluded from scan
 core_needs_allowed_extensions = Cannot start scan, when all extensions were excluded from scan
@@ -43,6 +45,7 @@ core_messages_limit_reached_lines = Number of messages exceeded the set limit ({
 core_error_moving_to_trash = Error while moving "{ $file }" to the trash: { $error }
 core_error_removing = Error while removing "{ $file }": { $error }
 core_hardlink_unsupported_filesystem = The filesystem at "{ $path }" does not support hard links ({ $reason })
+core_reflink_unsupported_filesystem = The filesystem at "{ $path }" does not support reflinks, file was left unchanged ({ $reason })
 
 core_no_similarity_method_selected = Cannot find similar music files without a selected similarity method
 
@@ -62,6 +65,8 @@ core_failed_to_crop_video_file = Failed to crop video file "{ $file }": { $reaso
 core_cropped_video_not_created = Cropped video file was not created: { $temp }
 core_unable_check_hash_of_file = Unable to check hash of file "{ $file }", reason { $reason }
 core_error_checking_hash_of_file = Error happened when checking hash of file "{ $file }", reason { $reason }
+core_unable_verify_content_of_file = Unable to verify content of file "{ $file }", reason { $reason }
+core_content_different_than_original = File "{ $file }" was skipped, because its content is different than content of "{ $original }"
 core_image_zero_dimensions = Image has zero width or height "{ $path }"
 core_image_open_failed = Cannot open image file "{ $path }": { $reason }
 core_not_directory_remove = Trying to remove folder "{ $path }" which is not a directory
@@ -156,6 +161,8 @@ stage_moving_files = Moving { $items_stats } file ({ $size_stats })
 stage_moving_no_size_files = Moving { $items_stats } file
 stage_hardlinking_files = Hardlinking { $items_stats } file ({ $size_stats })
 stage_hardlinking_no_size_files = Hardlinking { $items_stats } file
+stage_reflinking_files = Reflinking { $items_stats } file ({ $size_stats })
+stage_reflinking_no_size_files = Reflinking { $items_stats } file
 stage_symlinking_files = Symlinking { $items_stats } file ({ $size_stats })
 stage_symlinking_no_size_files = Symlinking { $items_stats } file
 stage_optimizing_videos = Optimized { $items_stats } video ({ $size_stats })
@@ -1,6 +1,9 @@
 #![allow(clippy::useless_let_if_seq)]
 
 mod cleaning;
+mod database;
+mod management;
+mod volumes;
 
 use std::collections::BTreeMap;
 use std::io::{BufReader, BufWriter};
@@ -13,14 +16,19 @@ use fun_time::fun_time;
 use humansize::{BINARY, format_size};
 use indexmap::IndexMap;
 use log::{debug, error};
+pub use management::{
+    CacheExportStatistics, CacheFileInfo, CacheImportStatistics, CachePruneStatistics, export_cache_files, import_cache_files, list_cache_files, prune_cache_files,
+};
 use once_cell::sync::Lazy;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 
 use crate::common::cache::cleaning::{should_clean_cache, update_cleaning_timestamp};
+use crate::common::cache::database::{get_database_path, load_entries, remove_outdated_entries, save_entries};
+use crate::common::cache::volumes::{VolumePathEntries, VolumeResolver};
 use crate::common::config_cache_path::open_cache_folder;
 use crate::common::tool_data::CommonData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::CacheEntry;
 use crate::flc;
 use crate::helpers::messages::Messages;
 
@@ -41,6 +49,16 @@ static CACHE_CLEANING_INTERVAL_SECONDS: Lazy<u64> = Lazy::new(|| {
         .unwrap_or(7 * 24 * 60 * 60)
 });
 
+/// Storage used by cache of tools
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheBackend {
+    /// Separate bincode file for every tool and its settings, always loaded and saved as a whole
+    #[default]
+    Bincode,
+    /// Single embedded key-value database shared by all tools, entries are read and written per path in transactions
+    Database,
+}
+
 fn get_cache_size(file_name: &Path) -> String {
     fs::metadata(file_name).map_or_else(|_| "<unknown size>".to_string(), |metadata| format_size(metadata.len(), BINARY))
 }
@@ -48,16 +66,21 @@ fn get_cache_size(file_name: &Path) -> String {
 #[fun_time(message = "save_cache_to_file_generalized", level = "debug")]
 pub fn save_cache_to_file_generalized<T>(cache_file_name: &str, hashmap: &BTreeMap<String, T>, save_also_as_json: bool, minimum_file_size: u64) -> Messages
 where
-    T: Serialize + ResultEntry + Sized + Send + Sync,
+    T: Serialize + CacheEntry + Clone + Sized + Send + Sync,
 {
     let mut text_messages = Messages::new();
     if let Some(((file_handler, cache_file), (file_handler_json, cache_file_json))) = open_cache_folder(cache_file_name, true, save_also_as_json, &mut text_messages.warnings) {
         let hashmap_to_save = hashmap.values().filter(|t| t.get_size() >= minimum_file_size).collect::<Vec<_>>();
+        let volume_resolver = VolumeResolver::new();
+        let entries_to_save = VolumePathEntries {
+            entries: &hashmap_to_save,
+            volume_resolver: &volume_resolver,
+        };
 
         {
             let writer = BufWriter::new(file_handler.expect("Cannot fail, because for saving, this always exists"));
             let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
-            if let Err(e) = options.serialize_into(writer, &hashmap_to_save) {
+            if let Err(e) = options.serialize_into(writer, &entries_to_save) {
                 text_messages
                     .warnings
                     .push(flc!("core_failed_to_write_data_to_cache", file = cache_file.to_string_lossy(), reason = e.to_string()));
@@ -68,7 +91,7 @@ where
         }
         if save_also_as_json && let Some(file_handler_json) = file_handler_json {
             let writer = BufWriter::new(file_handler_json);
-            if let Err(e) = serde_json::to_writer(writer, &hashmap_to_save) {
+            if let Err(e) = serde_json::to_writer(writer, &entries_to_save) {
                 text_messages
                     .warnings
                     .push(flc!("core_failed_to_write_data_to_cache", file = cache_file_json.to_string_lossy(), reason = e.to_string()));
@@ -90,6 +113,53 @@ where
     text_messages
 }
 
+pub(crate) fn save_cache_generalized<T>(
+    cache_backend: CacheBackend,
+    cache_file_name: &str,
+    hashmap: &BTreeMap<String, T>,
+    save_also_as_json: bool,
+    minimum_file_size: u64,
+) -> Messages
+where
+    T: Serialize + CacheEntry + Clone + Sized + Send + Sync,
+{
+    match cache_backend {
+        CacheBackend::Bincode => save_cache_to_file_generalized(cache_file_name, hashmap, save_also_as_json, minimum_file_size),
+        CacheBackend::Database => save_cache_to_database_generalized(cache_file_name, hashmap, minimum_file_size),
+    }
+}
+
+#[fun_time(message = "save_cache_to_database_generalized", level = "debug")]
+fn save_cache_to_database_generalized<T>(cache_file_name: &str, hashmap: &BTreeMap<String, T>, minimum_file_size: u64) -> Messages
+where
+    T: Serialize + CacheEntry + Clone + Sized + Send + Sync,
+{
+    let mut text_messages = Messages::new();
+    let Some(database_path) = get_database_path() else {
+        debug!("Failed to save cache to database because cache folder not exists");
+        return text_messages;
+    };
+
+    let volume_resolver = VolumeResolver::new();
+    let entries_to_save = hashmap
+        .values()
+        .filter(|t| t.get_size() >= minimum_file_size)
+        .map(|t| (volume_resolver.cache_key(t.get_path()), volume_resolver.to_cache_entry(t)));
+    match save_entries(&database_path, cache_file_name, entries_to_save) {
+        Ok(saved) => {
+            text_messages.messages.push(flc!("core_properly_saved_cache_entries", count = saved));
+            debug!("Properly saved to database {saved} cache entries of {cache_file_name}.");
+        }
+        Err(e) => {
+            text_messages
+                .warnings
+                .push(flc!("core_failed_to_write_data_to_cache", file = database_path.to_string_lossy(), reason = e.clone()));
+            debug!("Failed to save cache to database \"{}\" - {e}", database_path.to_string_lossy());
+        }
+    }
+    text_messages
+}
+
 pub(crate) fn extract_loaded_cache<T>(
     loaded_hash_map: &BTreeMap<String, T>,
     files_to_check: BTreeMap<String, T>,
@@ -110,7 +180,19 @@ pub(crate) fn extract_loaded_cache<T>(
 #[fun_time(message = "load_cache_from_file_generalized_by_path", level = "debug")]
 pub fn load_cache_from_file_generalized_by_path<T>(cache_file_name: &str, delete_outdated_cache: bool, used_files: &BTreeMap<String, T>) -> (Messages, Option<BTreeMap<String, T>>)
 where
-    for<'a> T: Deserialize<'a> + ResultEntry + Sized + Send + Sync + Clone,
+    for<'a> T: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
+{
+    load_cache_generalized_by_path(CacheBackend::Bincode, cache_file_name, delete_outdated_cache, used_files)
+}
+
+fn load_cache_generalized_by_path<T>(
+    cache_backend: CacheBackend,
+    cache_file_name: &str,
+    delete_outdated_cache: bool,
+    used_files: &BTreeMap<String, T>,
+) -> (Messages, Option<BTreeMap<String, T>>)
+where
+    for<'a> T: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
 {
     let check_file = |file_entry: &T| {
         let file_entry_path_str = file_entry.get_path().to_string_lossy();
@@ -126,7 +208,10 @@ where
         true
     };
 
-    let (text_messages, vec_loaded_cache) = load_cache_from_file_generalized(cache_file_name, delete_outdated_cache, check_file);
+    let (text_messages, vec_loaded_cache) = match cache_backend {
+        CacheBackend::Bincode => load_cache_from_file_generalized(cache_file_name, delete_outdated_cache, check_file),
+        CacheBackend::Database => load_cache_from_database_generalized(cache_file_name, delete_outdated_cache, used_files.keys().map(String::as_str).collect(), check_file),
+    };
     let Some(vec_loaded_entries) = vec_loaded_cache else {
         return (text_messages, None);
     };
@@ -150,7 +235,19 @@ pub fn load_cache_from_file_generalized_by_size<T>(
     cache_not_converted: &BTreeMap<u64, Vec<T>>,
 ) -> (Messages, Option<BTreeMap<u64, Vec<T>>>)
 where
-    for<'a> T: Deserialize<'a> + ResultEntry + Sized + Send + Sync + Clone,
+    for<'a> T: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
+{
+    load_cache_generalized_by_size(CacheBackend::Bincode, cache_file_name, delete_outdated_cache, cache_not_converted)
+}
+
+fn load_cache_generalized_by_size<T>(
+    cache_backend: CacheBackend,
+    cache_file_name: &str,
+    delete_outdated_cache: bool,
+    cache_not_converted: &BTreeMap<u64, Vec<T>>,
+) -> (Messages, Option<BTreeMap<u64, Vec<T>>>)
+where
+    for<'a> T: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
 {
     debug!("Converting cache BtreeMap<u64, Vec<T>> into IndexMap<String, (u64, u64)>");
     let used_files: IndexMap<String, (u64, u64)> = cache_not_converted
@@ -176,7 +273,10 @@ where
         true
     };
 
-    let (text_messages, vec_loaded_cache) = load_cache_from_file_generalized(cache_file_name, delete_outdated_cache, check_file);
+    let (text_messages, vec_loaded_cache) = match cache_backend {
+        CacheBackend::Bincode => load_cache_from_file_generalized(cache_file_name, delete_outdated_cache, check_file),
+        CacheBackend::Database => load_cache_from_database_generalized(cache_file_name, delete_outdated_cache, used_files.keys().map(String::as_str).collect(), check_file),
+    };
     let Some(vec_loaded_entries) = vec_loaded_cache else {
         return (text_messages, None);
     };
@@ -199,7 +299,7 @@ where
 #[fun_time(message = "load_cache_from_file_generalized", level = "debug")]
 fn load_cache_from_file_generalized<T, F>(cache_file_name: &str, delete_outdated_cache: bool, check_func: F) -> (Messages, Option<Vec<T>>)
 where
-    for<'a> T: Deserialize<'a> + ResultEntry + Sized + Send + Sync + Clone,
+    for<'a> T: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
     F: Fn(&T) -> bool + Send + Sync,
 {
     let mut text_messages = Messages::new();
@@ -239,6 +339,10 @@ where
             };
         }
 
+        // Entries saved with volume paths are moved to current mount point of their volume
+        let volume_resolver = VolumeResolver::new();
+        vec_loaded_entries.par_iter_mut().for_each(|file_entry| volume_resolver.resolve_entry(file_entry));
+
         let should_clean = should_clean_cache(cache_file_name);
         debug!(
             "Starting removing outdated cache entries (removing non existent files from cache - {delete_outdated_cache}, should_clean - {should_clean}, entries number - {})",
@@ -256,7 +360,7 @@ where
                     return false;
                 }
 
-                if effective_delete_outdated && !file_entry.get_path().exists() {
+                if effective_delete_outdated && !volume_resolver.may_exist(file_entry.get_path()) {
                     return false;
                 }
 
@@ -288,6 +392,62 @@ where
     (text_messages, None)
 }
 
+// Only entries of currently checked files are read from database, instead of whole cache
+#[fun_time(message = "load_cache_from_database_generalized", level = "debug")]
+fn load_cache_from_database_generalized<T, F>(cache_file_name: &str, delete_outdated_cache: bool, paths: Vec<&str>, check_func: F) -> (Messages, Option<Vec<T>>)
+where
+    for<'a> T: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
+    F: Fn(&T) -> bool + Send + Sync,
+{
+    let mut text_messages = Messages::new();
+    let Some(database_path) = get_database_path() else {
+        debug!("Failed to load cache from database because cache folder not exists");
+        return (text_messages, None);
+    };
+
+    let volume_resolver = VolumeResolver::new();
+    if delete_outdated_cache && should_clean_cache(cache_file_name) {
+        match remove_outdated_entries(&database_path, cache_file_name, &volume_resolver) {
+            Ok(removed) => {
+                debug!("Removed {removed} outdated entries of {cache_file_name} from cache database");
+                update_cleaning_timestamp(cache_file_name);
+            }
+            Err(e) => text_messages
+                .warnings
+                .push(flc!("core_failed_to_write_data_to_cache", file = database_path.to_string_lossy(), reason = e)),
+        }
+    }
+
+    let keys: Vec<(String, String)> = paths.iter().map(|path| (volume_resolver.cache_key(Path::new(path)), (*path).to_string())).collect();
+    match load_entries::<T>(&database_path, cache_file_name, &keys) {
+        Ok(loaded_entries) => {
+            let loaded_entries: Vec<T> = loaded_entries
+                .into_iter()
+                .map(|mut file_entry| {
+                    volume_resolver.resolve_entry(&mut file_entry);
+                    file_entry
+                })
+                .filter(|file_entry| check_func(file_entry))
+                .collect();
+            text_messages.messages.push(flc!("core_properly_loaded_cache_entries", count = loaded_entries.len()));
+            debug!(
+                "Loaded cache from database {cache_file_name} - {} results for {} checked files - size {}",
+                loaded_entries.len(),
+                paths.len(),
+                get_cache_size(&database_path)
+            );
+            (text_messages, Some(loaded_entries))
+        }
+        Err(e) => {
+            text_messages
+                .warnings
+                .push(flc!("core_failed_to_load_data_from_cache", file = database_path.to_string_lossy(), reason = e.clone()));
+            error!("Failed to load cache from database {} - {e}", database_path.to_string_lossy());
+            (text_messages, None)
+        }
+    }
+}
+
 pub(crate) fn load_and_split_cache_generalized_by_size<C: CommonData, K>(
     cache_file_name: &str,
     use_cache: bool,
@@ -295,13 +455,13 @@ pub(crate) fn load_and_split_cache_generalized_by_size<C: CommonData, K>(
     common_data: &mut C,
 ) -> (BTreeMap<u64, Vec<K>>, BTreeMap<u64, Vec<K>>, BTreeMap<u64, Vec<K>>)
 where
-    for<'a> K: Deserialize<'a> + ResultEntry + Sized + Send + Sync + Clone,
+    for<'a> K: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
 {
     if !use_cache {
         return (Default::default(), Default::default(), items_to_check);
     }
 
-    let (messages, loaded_items) = load_cache_from_file_generalized_by_size::<K>(cache_file_name, common_data.get_delete_outdated_cache(), &items_to_check);
+    let (messages, loaded_items) = load_cache_generalized_by_size::<K>(common_data.get_cache_backend(), cache_file_name, common_data.get_delete_outdated_cache(), &items_to_check);
     common_data.get_text_messages_mut().extend_with_another_messages(messages);
     let loaded_hash_map = loaded_items.unwrap_or_default();
 
@@ -343,7 +503,7 @@ pub(crate) fn load_and_split_cache_generalized_by_path<C: CommonData, K>(
     common_data: &mut C,
 ) -> (BTreeMap<String, K>, BTreeMap<String, K>, BTreeMap<String, K>)
 where
-    for<'a> K: Deserialize<'a> + ResultEntry + Sized + Send + Sync + Clone,
+    for<'a> K: Deserialize<'a> + CacheEntry + Sized + Send + Sync + Clone,
 {
     if !common_data.get_use_cache() {
         return (Default::default(), Default::default(), items_to_check);
@@ -354,7 +514,7 @@ where
     let mut records_already_cached: BTreeMap<String, K> = Default::default();
     let mut non_cached_files_to_check: BTreeMap<String, K> = Default::default();
 
-    let (messages, loaded_items) = load_cache_from_file_generalized_by_path::<K>(cache_file_name, common_data.get_delete_outdated_cache(), &items_to_check);
+    let (messages, loaded_items) = load_cache_generalized_by_path::<K>(common_data.get_cache_backend(), cache_file_name, common_data.get_delete_outdated_cache(), &items_to_check);
     common_data.get_text_messages_mut().extend_with_another_messages(messages);
     loaded_hash_map = loaded_items.unwrap_or_default();
 
@@ -377,7 +537,7 @@ where
 
 pub(crate) fn save_and_connect_cache_generalized_by_path<C: CommonData, K>(cache_file_name: &str, vec_file_entry: &[K], loaded_hash_map: BTreeMap<String, K>, common_data: &mut C)
 where
-    K: Serialize + ResultEntry + Sized + Send + Sync + Clone,
+    K: Serialize + CacheEntry + Sized + Send + Sync + Clone,
 {
     if !common_data.get_use_cache() {
         return;
@@ -387,16 +547,19 @@ where
     for file_entry in vec_file_entry.iter().cloned() {
         all_results.insert(file_entry.get_path().to_string_lossy().to_string(), file_entry);
     }
-    for (name, file_entry) in loaded_hash_map {
-        all_results.insert(name, file_entry);
+    // Database still contains loaded entries, so only current results need to be written
+    if common_data.get_cache_backend() == CacheBackend::Bincode {
+        for (name, file_entry) in loaded_hash_map {
+            all_results.insert(name, file_entry);
+        }
     }
 
-    let messages = save_cache_to_file_generalized(cache_file_name, &all_results, common_data.get_save_also_as_json(), 0);
+    let messages = save_cache_generalized(common_data.get_cache_backend(), cache_file_name, &all_results, common_data.get_save_also_as_json(), 0);
     common_data.get_text_messages_mut().extend_with_another_messages(messages);
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::collections::BTreeMap;
     use std::fs;
     use std::path::PathBuf;
@@ -406,6 +569,7 @@ mod tests {
 
     use super::*;
     use crate::common::config_cache_path::set_config_cache_path_test;
+    use crate::common::traits::ResultEntry;
 
     static INIT: Once = Once::new();
 
@@ -445,6 +609,12 @@ mod tests {
         }
     }
 
+    impl CacheEntry for TestEntry {
+        fn set_path(&mut self, path: PathBuf) {
+            self.path = path;
+        }
+    }
+
     impl TestEntry {
         fn new(path: &str, size: u64, modified_date: u64, value: u32) -> Self {
             Self {
@@ -695,6 +865,42 @@ mod tests {
         assert!(messages.warnings.is_empty(), "Should not have warnings when saving with JSON");
     }
 
+    #[test]
+    fn test_save_and_load_cache_database() {
+        setup_cache_path();
+        let temp_dir = TempDir::new().unwrap();
+        let existing_file = temp_dir.path().join("existing.txt");
+        let removed_file = temp_dir.path().join("removed.txt");
+        fs::write(&existing_file, "test content").unwrap();
+
+        let mut cache_to_save = BTreeMap::new();
+        for entry in [
+            TestEntry::new(existing_file.to_str().unwrap(), 100, 1000, 1),
+            TestEntry::new(removed_file.to_str().unwrap(), 200, 2000, 2),
+            TestEntry::new("/tmp/small", 10, 3000, 3),
+        ] {
+            cache_to_save.insert(entry.path.to_string_lossy().to_string(), entry);
+        }
+
+        let cache_name = format!("test_cache_database_{}", std::process::id());
+        let messages = save_cache_generalized(CacheBackend::Database, &cache_name, &cache_to_save, false, 100);
+        assert!(messages.warnings.is_empty(), "Should not have warnings when saving");
+
+        // Only requested entries with the same size and modification date are loaded
+        let mut files_to_check = cache_to_save.clone();
+        files_to_check.get_mut(existing_file.to_str().unwrap()).unwrap().modified_date = 1001;
+        let (messages, loaded_cache) = load_cache_generalized_by_path(CacheBackend::Database, &cache_name, false, &files_to_check);
+        assert!(messages.warnings.is_empty(), "Should not have warnings when loading");
+        let loaded = loaded_cache.unwrap();
+        assert_eq!(loaded.keys().collect::<Vec<_>>(), vec![removed_file.to_str().unwrap()]);
+
+        // Entries of removed files are deleted when cleaning outdated entries
+        let (_, loaded_cache) = load_cache_generalized_by_path(CacheBackend::Database, &cache_name, true, &cache_to_save);
+        let loaded = loaded_cache.unwrap();
+        assert_eq!(loaded.keys().collect::<Vec<_>>(), vec![existing_file.to_str().unwrap()]);
+        assert_eq!(loaded[existing_file.to_str().unwrap()].value, 1);
+    }
+
     #[test]
     fn test_get_cache_size_nonexistent() {
         let nonexistent_path = Path::new("/nonexistent/path/to/cache.bin");
@@ -11,6 +11,7 @@ use log::{debug, error};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::common::cache::volumes::{ResolvedPath, VolumeResolver};
 use crate::common::cache::{
     CACHE_BROKEN_FILES_VERSION, CACHE_CLEANING_INTERVAL_SECONDS, CACHE_DUPLICATE_PREHASH_VERSION, CACHE_DUPLICATE_VERSION, CACHE_IMAGE_VERSION, CACHE_VERSION,
     CACHE_VIDEO_OPTIMIZE_VERSION, CACHE_VIDEO_VERSION, CLEANING_TIMESTAMPS_FILE, MEMORY_LIMIT,
@@ -137,8 +138,8 @@ pub(crate) fn update_cleaning_timestamp(cache_file_name: &str) {
     }
 }
 
-#[derive(Debug)]
-enum CacheType {
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CacheType {
     Duplicates,
     MusicTags,
     MusicFingerprints,
@@ -151,7 +152,7 @@ enum CacheType {
 }
 
 impl CacheType {
-    fn from_filename(filename: &str) -> Option<Self> {
+    pub(crate) fn from_filename(filename: &str) -> Option<Self> {
         if filename.starts_with("cache_duplicates_")
             && ((filename.contains("_prehash_") && filename.ends_with(&format!("_{CACHE_DUPLICATE_PREHASH_VERSION}.bin")))
                 || (!filename.contains("_prehash_") && filename.ends_with(&format!("_{CACHE_DUPLICATE_VERSION}.bin"))))
@@ -177,6 +178,31 @@ impl CacheType {
             None
         }
     }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Duplicates => "duplicates",
+            Self::MusicTags => "music tags",
+            Self::MusicFingerprints => "music fingerprints",
+            Self::SimilarImages => "similar images",
+            Self::SimilarVideos => "similar videos",
+            Self::BrokenFiles => "broken files",
+            Self::ExifRemover => "exif remover",
+            Self::VideoTranscode => "video transcode",
+            Self::VideoCrop => "video crop",
+        }
+    }
+
+    pub(crate) fn version(self) -> u8 {
+        match self {
+            Self::Duplicates => CACHE_DUPLICATE_VERSION,
+            Self::MusicTags | Self::MusicFingerprints | Self::ExifRemover => CACHE_VERSION,
+            Self::SimilarImages => CACHE_IMAGE_VERSION,
+            Self::SimilarVideos => CACHE_VIDEO_VERSION,
+            Self::BrokenFiles => CACHE_BROKEN_FILES_VERSION,
+            Self::VideoTranscode | Self::VideoCrop => CACHE_VIDEO_OPTIMIZE_VERSION,
+        }
+    }
 }
 
 #[fun_time(message = "clean_all_cache_files", level = "debug")]
@@ -319,6 +345,7 @@ where
     all_entries.store(original_count, Ordering::Relaxed);
 
     let checked_entries_clone = checked_entries.clone();
+    let volume_resolver = VolumeResolver::new();
 
     let filtered_entries: Vec<T> = entries
         .into_par_iter()
@@ -329,7 +356,13 @@ where
 
             checked_entries_clone.fetch_add(1, Ordering::Relaxed);
 
-            let Ok(metadata) = fs::metadata(cached_entry.get_path()) else {
+            let path = match volume_resolver.resolve(cached_entry.get_path()) {
+                ResolvedPath::NotVolumePath => cached_entry.get_path().to_path_buf(),
+                ResolvedPath::Mounted(path) => path,
+                // File on not mounted volume cannot be checked
+                ResolvedPath::NotMounted => return Some(Some(cached_entry)),
+            };
+            let Ok(metadata) = fs::metadata(path) else {
                 return Some(None);
             };
             if metadata.len() != cached_entry.get_size() {
@@ -0,0 +1,304 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::sleep;
+use std::time::Duration;
+
+use bincode::Options;
+use log::{debug, error};
+use redb::{Database, DatabaseError, ReadableDatabase, ReadableTable, ReadableTableMetadata, TableDefinition, TableError, TableHandle};
+use serde::{Deserialize, Serialize};
+
+use crate::common::cache::MEMORY_LIMIT;
+use crate::common::cache::volumes::VolumeResolver;
+use crate::common::config_cache_path::get_config_cache_path;
+
+pub(crate) const CACHE_DATABASE_FILE: &str = "cache_database_v1.redb";
+
+pub(crate) fn get_database_path() -> Option<PathBuf> {
+    get_config_cache_path().map(|config| config.cache_folder.join(CACHE_DATABASE_FILE))
+}
+
+// Database file can be opened only once at the same time, so it is kept open only while used by load or save
+// Other processes wait until it is closed - transactions are short, so wait is limited
+const OPEN_RETRIES: u32 = 100;
+const OPEN_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+// Tools running at the same time in this process share one handle, instead of waiting for each other
+static OPENED_DATABASE: Mutex<Option<(PathBuf, Weak<Database>)>> = Mutex::new(None);
+
+fn open_database(database_path: &Path) -> Result<Arc<Database>, String> {
+    let mut opened_database = OPENED_DATABASE.lock().map_err(|e| e.to_string())?;
+    if let Some((path, database)) = opened_database.as_ref()
+        && path == database_path
+        && let Some(database) = database.upgrade()
+    {
+        return Ok(database);
+    }
+
+    let mut retries = 0;
+    let database = loop {
+        match Database::create(database_path) {
+            Ok(database) => break Arc::new(database),
+            Err(DatabaseError::DatabaseAlreadyOpen) if retries < OPEN_RETRIES => {
+                retries += 1;
+                sleep(OPEN_RETRY_DELAY);
+            }
+            Err(DatabaseError::DatabaseAlreadyOpen) => return Err("cache database is used by another process for too long, so cache is not used".to_string()),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+    *opened_database = Some((database_path.to_path_buf(), Arc::downgrade(&database)));
+    Ok(database)
+}
+
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT)
+}
+
+/// Loads entries saved under given keys, every cache file name has its own table.
+/// Each key is paired with absolute path of file, under which older versions saved entries of files on volumes,
+/// such entries are loaded too and moved to the new key, so they are not left in database forever.
+/// Entries which cannot be deserialized are skipped, so they will be computed and saved again.
+pub(crate) fn load_entries<T>(database_path: &Path, table_name: &str, keys: &[(String, String)]) -> Result<Vec<T>, String>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    let database = open_database(database_path)?;
+    let table_definition = TableDefinition::<&str, &[u8]>::new(table_name);
+    let mut entries = Vec::new();
+    let mut migrated_entries = Vec::new();
+    {
+        let read_transaction = database.begin_read().map_err(|e| e.to_string())?;
+        let table = match read_transaction.open_table(table_definition) {
+            Ok(table) => table,
+            Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        for (key, absolute_path) in keys {
+            let value = match table.get(key.as_str()).map_err(|e| e.to_string())? {
+                Some(value) => value.value().to_vec(),
+                None if key != absolute_path => match table.get(absolute_path.as_str()).map_err(|e| e.to_string())? {
+                    Some(value) => {
+                        let value = value.value().to_vec();
+                        migrated_entries.push((key, absolute_path, value.clone()));
+                        value
+                    }
+                    None => continue,
+                },
+                None => continue,
+            };
+            match bincode_options().deserialize(&value) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => error!("Failed to deserialize cache entry of \"{key}\" from table {table_name} - {e}"),
+            }
+        }
+    }
+
+    if !migrated_entries.is_empty() {
+        let write_transaction = database.begin_write().map_err(|e| e.to_string())?;
+        {
+            let mut table = write_transaction.open_table(table_definition).map_err(|e| e.to_string())?;
+            for (key, absolute_path, value) in &migrated_entries {
+                table.insert(key.as_str(), value.as_slice()).map_err(|e| e.to_string())?;
+                table.remove(absolute_path.as_str()).map_err(|e| e.to_string())?;
+            }
+        }
+        write_transaction.commit().map_err(|e| e.to_string())?;
+        debug!("Moved {} entries saved with absolute paths to volume paths in table {table_name}", migrated_entries.len());
+    }
+    Ok(entries)
+}
+
+/// Inserts or replaces entries in single transaction, so killed scan leaves database in previous state.
+pub(crate) fn save_entries<T>(database_path: &Path, table_name: &str, entries: impl Iterator<Item = (String, T)>) -> Result<usize, String>
+where
+    T: Serialize,
+{
+    let database = open_database(database_path)?;
+    let write_transaction = database.begin_write().map_err(|e| e.to_string())?;
+    let mut saved = 0;
+    {
+        let mut table = write_transaction.open_table(TableDefinition::<&str, &[u8]>::new(table_name)).map_err(|e| e.to_string())?;
+        for (key, entry) in entries {
+            let value = bincode_options().serialize(&entry).map_err(|e| e.to_string())?;
+            table.insert(key.as_str(), value.as_slice()).map_err(|e| e.to_string())?;
+            saved += 1;
+        }
+    }
+    write_transaction.commit().map_err(|e| e.to_string())?;
+    debug!("Saved {saved} entries to table {table_name} in cache database");
+    Ok(saved)
+}
+
+/// Removes entries of files which no longer exist, returns number of removed entries.
+pub(crate) fn remove_outdated_entries(database_path: &Path, table_name: &str, volume_resolver: &VolumeResolver) -> Result<usize, String> {
+    let database = open_database(database_path)?;
+    let write_transaction = database.begin_write().map_err(|e| e.to_string())?;
+    let mut removed = 0;
+    {
+        let mut table = write_transaction.open_table(TableDefinition::<&str, &[u8]>::new(table_name)).map_err(|e| e.to_string())?;
+        table
+            .retain(|path, _| {
+                let exists = volume_resolver.may_exist(Path::new(path));
+                if !exists {
+                    removed += 1;
+                }
+                exists
+            })
+            .map_err(|e| e.to_string())?;
+    }
+    write_transaction.commit().map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
+/// Loads all entries of table, entries which cannot be deserialized are skipped.
+pub(crate) fn load_all_entries<T>(database_path: &Path, table_name: &str) -> Result<Vec<T>, String>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    let database = open_database(database_path)?;
+    let read_transaction = database.begin_read().map_err(|e| e.to_string())?;
+    let table = match read_transaction.open_table(TableDefinition::<&str, &[u8]>::new(table_name)) {
+        Ok(table) => table,
+        Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut entries = Vec::new();
+    for item in table.iter().map_err(|e| e.to_string())? {
+        let (key, value) = item.map_err(|e| e.to_string())?;
+        match bincode_options().deserialize(value.value()) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => error!("Failed to deserialize cache entry of \"{}\" from table {table_name} - {e}", key.value()),
+        }
+    }
+    Ok(entries)
+}
+
+/// Inserts entries under keys which are not used yet, existing entries are kept.
+/// Returns number of added and skipped entries.
+pub(crate) fn insert_missing_entries<T>(database_path: &Path, table_name: &str, entries: impl Iterator<Item = (String, T)>) -> Result<(usize, usize), String>
+where
+    T: Serialize,
+{
+    let database = open_database(database_path)?;
+    let write_transaction = database.begin_write().map_err(|e| e.to_string())?;
+    let mut added = 0;
+    let mut skipped = 0;
+    {
+        let mut table = write_transaction.open_table(TableDefinition::<&str, &[u8]>::new(table_name)).map_err(|e| e.to_string())?;
+        for (key, entry) in entries {
+            if table.get(key.as_str()).map_err(|e| e.to_string())?.is_some() {
+                skipped += 1;
+                continue;
+            }
+            let value = bincode_options().serialize(&entry).map_err(|e| e.to_string())?;
+            table.insert(key.as_str(), value.as_slice()).map_err(|e| e.to_string())?;
+            added += 1;
+        }
+    }
+    write_transaction.commit().map_err(|e| e.to_string())?;
+    Ok((added, skipped))
+}
+
+/// Removes entries chosen by `should_remove`, in dry run only counts them.
+/// Entries which cannot be deserialized are kept. Returns number of removed entries.
+pub(crate) fn remove_entries<T>(database_path: &Path, table_name: &str, dry_run: bool, should_remove: impl Fn(&T) -> bool) -> Result<usize, String>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    let is_removed = |value: &[u8]| bincode_options().deserialize::<T>(value).is_ok_and(|entry| should_remove(&entry));
+    let database = open_database(database_path)?;
+    if dry_run {
+        let read_transaction = database.begin_read().map_err(|e| e.to_string())?;
+        let table = read_transaction.open_table(TableDefinition::<&str, &[u8]>::new(table_name)).map_err(|e| e.to_string())?;
+        let mut removed = 0;
+        for item in table.iter().map_err(|e| e.to_string())? {
+            let (_key, value) = item.map_err(|e| e.to_string())?;
+            if is_removed(value.value()) {
+                removed += 1;
+            }
+        }
+        return Ok(removed);
+    }
+
+    let write_transaction = database.begin_write().map_err(|e| e.to_string())?;
+    let mut removed = 0;
+    {
+        let mut table = write_transaction.open_table(TableDefinition::<&str, &[u8]>::new(table_name)).map_err(|e| e.to_string())?;
+        table
+            .retain(|_key, value| {
+                let remove = is_removed(value);
+                if remove {
+                    removed += 1;
+                }
+                !remove
+            })
+            .map_err(|e| e.to_string())?;
+    }
+    write_transaction.commit().map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
+/// Returns name and number of entries of every table in database.
+pub(crate) fn table_entry_counts(database_path: &Path) -> Result<Vec<(String, u64)>, String> {
+    let database = open_database(database_path)?;
+    let read_transaction = database.begin_read().map_err(|e| e.to_string())?;
+    let mut counts = Vec::new();
+    for table_handle in read_transaction.list_tables().map_err(|e| e.to_string())? {
+        let table = read_transaction
+            .open_table(TableDefinition::<&str, &[u8]>::new(table_handle.name()))
+            .map_err(|e| e.to_string())?;
+        counts.push((table_handle.name().to_string(), table.len().map_err(|e| e.to_string())?));
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_shared_in_process_and_awaited_between_processes() {
+        let dir = tempfile::tempdir().expect("Cannot create temp dir");
+        let database_path = dir.path().join(CACHE_DATABASE_FILE);
+        save_entries(&database_path, "table", std::iter::once(("/a".to_string(), 1u64))).expect("Cannot save entries");
+        let keys = [("/a".to_string(), "/a".to_string())];
+
+        // Handle is shared with other tools in the same process
+        let database = open_database(&database_path).expect("Cannot open database");
+        assert_eq!(load_entries::<u64>(&database_path, "table", &keys), Ok(vec![1]));
+        save_entries(&database_path, "table", std::iter::once(("/b".to_string(), 2u64))).expect("Cannot save entries");
+        drop(database);
+
+        // Database opened separately behaves like one used by other process, so it is awaited until closed
+        let other_process_database = Database::create(&database_path).expect("Cannot open database");
+        let closing_thread = std::thread::spawn(move || {
+            sleep(Duration::from_millis(300));
+            drop(other_process_database);
+        });
+        assert_eq!(load_entries::<u64>(&database_path, "table", &keys), Ok(vec![1]));
+        closing_thread.join().expect("Thread panicked");
+    }
+
+    #[test]
+    fn test_entries_with_absolute_paths_are_migrated() {
+        let dir = tempfile::tempdir().expect("Cannot create temp dir");
+        let database_path = dir.path().join(CACHE_DATABASE_FILE);
+        save_entries(&database_path, "table", [("/mnt/disk/a".to_string(), 1u64), ("/b".to_string(), 2u64)].into_iter()).expect("Cannot save entries");
+
+        let keys = [
+            ("volume:1234:/a".to_string(), "/mnt/disk/a".to_string()),
+            ("/b".to_string(), "/b".to_string()),
+            ("volume:1234:/c".to_string(), "/mnt/disk/c".to_string()),
+        ];
+        assert_eq!(load_entries::<u64>(&database_path, "table", &keys), Ok(vec![1, 2]));
+
+        // Entry is available only under new key
+        let new_keys = [("volume:1234:/a".to_string(), "volume:1234:/a".to_string())];
+        assert_eq!(load_entries::<u64>(&database_path, "table", &new_keys), Ok(vec![1]));
+        let old_keys = [("/mnt/disk/a".to_string(), "/mnt/disk/a".to_string())];
+        assert_eq!(load_entries::<u64>(&database_path, "table", &old_keys), Ok(vec![]));
+    }
+}
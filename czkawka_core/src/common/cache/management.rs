@@ -0,0 +1,553 @@
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use bincode::Options;
+use log::{debug, error};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::common::cache::cleaning::CacheType;
+use crate::common::cache::database::{CACHE_DATABASE_FILE, insert_missing_entries, load_all_entries, remove_entries, table_entry_counts};
+use crate::common::cache::{CacheBackend, MEMORY_LIMIT};
+use crate::common::config_cache_path::get_config_cache_path;
+use crate::common::date_filter::timestamp;
+use crate::common::traits::ResultEntry;
+use crate::tools::broken_files::BrokenEntry;
+use crate::tools::duplicate::DuplicateEntry;
+use crate::tools::exif_remover::ExifEntry;
+use crate::tools::same_music::MusicEntry;
+use crate::tools::similar_images::ImagesEntry;
+use crate::tools::similar_videos::VideosEntry;
+use crate::tools::video_optimizer::{VideoCropEntry, VideoTranscodeEntry};
+
+const CACHE_EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub struct CacheFileInfo {
+    /// Name of cache file or `<database file>:<table>` for tables of cache database
+    pub file_name: String,
+    /// `None` for files with outdated version, which are no longer used
+    pub cache_type: Option<&'static str>,
+    pub version: Option<u8>,
+    pub entries: Option<u64>,
+    /// `None` for tables of cache database
+    pub size: Option<u64>,
+    pub modified_date: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CachePruneStatistics {
+    pub removed_entries: usize,
+    /// Cache files and tables of cache database with number of entries removed from them
+    pub pruned_files: Vec<(String, usize)>,
+    /// Cache files removed, because all their entries were pruned
+    pub removed_files: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacheExportStatistics {
+    pub exported_files: usize,
+    pub exported_entries: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacheImportStatistics {
+    pub imported_files: usize,
+    pub added_entries: usize,
+    pub skipped_entries: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheExport {
+    version: u32,
+    files: Vec<ExportedCacheFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportedCacheFile {
+    // Tables of cache database are named like cache files
+    file_name: String,
+    #[serde(default)]
+    from_database: bool,
+    entries: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CacheStorage<'a> {
+    File(&'a Path),
+    DatabaseTable(&'a Path, &'a str),
+}
+
+fn get_cache_folder() -> Result<PathBuf, String> {
+    get_config_cache_path()
+        .map(|config| config.cache_folder)
+        .ok_or_else(|| "Cannot get cache folder path".to_string())
+}
+
+// Json copies of cache files are saved with the same name, but different extension
+fn is_cache_file(file_name: &str, with_json_copies: bool) -> bool {
+    file_name.starts_with("cache_") && (file_name.ends_with(".bin") || (with_json_copies && file_name.ends_with(".json")))
+}
+
+fn cache_files_in_folder(cache_folder: &Path) -> Result<Vec<(PathBuf, String, fs::Metadata)>, String> {
+    let read_dir = fs::read_dir(cache_folder).map_err(|e| format!("Cannot read cache folder \"{}\": {e}", cache_folder.to_string_lossy()))?;
+    let mut files: Vec<_> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            let file_name = entry.file_name().to_str()?.to_string();
+            Some((entry.path(), file_name, metadata))
+        })
+        .collect();
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(files)
+}
+
+// Bincode saves length of vector before its items, so entries don't need to be deserialized to count them
+fn read_entries_count(cache_path: &Path) -> Option<u64> {
+    let file = fs::File::open(cache_path).ok()?;
+    bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT).deserialize_from(BufReader::new(file)).ok()
+}
+
+/// Lists cache files and tables of cache database with number of their entries.
+pub fn list_cache_files() -> Result<Vec<CacheFileInfo>, String> {
+    let cache_folder = get_cache_folder()?;
+    let mut cache_files = Vec::new();
+
+    for (path, file_name, metadata) in cache_files_in_folder(&cache_folder)? {
+        if file_name == CACHE_DATABASE_FILE {
+            let tables = table_entry_counts(&path).unwrap_or_else(|e| {
+                error!("Failed to read tables of cache database \"{}\" - {e}", path.to_string_lossy());
+                Vec::new()
+            });
+            for (table_name, entries) in tables {
+                let cache_type = CacheType::from_filename(&table_name);
+                cache_files.push(CacheFileInfo {
+                    file_name: format!("{CACHE_DATABASE_FILE}:{table_name}"),
+                    cache_type: cache_type.map(CacheType::name),
+                    version: cache_type.map(CacheType::version),
+                    entries: Some(entries),
+                    size: None,
+                    modified_date: None,
+                });
+            }
+            continue;
+        }
+        if !is_cache_file(&file_name, false) {
+            continue;
+        }
+
+        let cache_type = CacheType::from_filename(&file_name);
+        cache_files.push(CacheFileInfo {
+            entries: read_entries_count(&path),
+            cache_type: cache_type.map(CacheType::name),
+            version: cache_type.map(CacheType::version),
+            file_name,
+            size: Some(metadata.len()),
+            modified_date: timestamp(metadata.modified()),
+        });
+    }
+
+    Ok(cache_files)
+}
+
+// Cache files and tables of cache database with entries of current version
+fn cache_storages(cache_folder: &Path) -> Result<Vec<(String, CacheType, bool)>, String> {
+    let mut storages = Vec::new();
+    for (path, file_name, _metadata) in cache_files_in_folder(cache_folder)? {
+        if file_name == CACHE_DATABASE_FILE {
+            for (table_name, _entries) in table_entry_counts(&path)? {
+                if let Some(cache_type) = CacheType::from_filename(&table_name) {
+                    storages.push((table_name, cache_type, true));
+                }
+            }
+        } else if let Some(cache_type) = CacheType::from_filename(&file_name) {
+            storages.push((file_name, cache_type, false));
+        }
+    }
+    Ok(storages)
+}
+
+fn storage_name(file_name: &str, from_database: bool) -> String {
+    if from_database {
+        format!("{CACHE_DATABASE_FILE}:{file_name}")
+    } else {
+        file_name.to_string()
+    }
+}
+
+/// Removes entries of files last modified before given unix timestamp from cache files and tables of cache database.
+/// Cache files without any entries left are removed together with their json copies.
+pub fn prune_cache_files(older_than: u64, dry_run: bool) -> Result<CachePruneStatistics, String> {
+    let cache_folder = get_cache_folder()?;
+    let database_path = cache_folder.join(CACHE_DATABASE_FILE);
+    let mut stats = CachePruneStatistics::default();
+
+    for (file_name, cache_type, from_database) in cache_storages(&cache_folder)? {
+        let cache_path = cache_folder.join(&file_name);
+        let storage = if from_database {
+            CacheStorage::DatabaseTable(&database_path, &file_name)
+        } else {
+            CacheStorage::File(&cache_path)
+        };
+        let name = storage_name(&file_name, from_database);
+        match prune_cache_file(storage, cache_type, older_than, dry_run) {
+            Ok((0, _)) => {}
+            Ok((removed, left)) => {
+                debug!("Pruned {removed} entries from cache \"{name}\"");
+                stats.removed_entries += removed;
+                stats.pruned_files.push((name, removed));
+                if left == 0 && !from_database {
+                    if !dry_run && let Err(e) = remove_cache_file(&cache_path) {
+                        stats.errors.push(format!("{file_name}: {e}"));
+                        continue;
+                    }
+                    stats.removed_files.push(file_name);
+                }
+            }
+            Err(e) => stats.errors.push(format!("{name}: {e}")),
+        }
+    }
+
+    Ok(stats)
+}
+
+fn remove_cache_file(cache_path: &Path) -> Result<(), String> {
+    fs::remove_file(cache_path).map_err(|e| e.to_string())?;
+    // Json copy would be loaded instead of removed file
+    let json_path = cache_path.with_extension("json");
+    if json_path.exists() {
+        fs::remove_file(&json_path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Exports entries of all cache files and tables of cache database with current version to single json file.
+pub fn export_cache_files(export_path: &Path) -> Result<CacheExportStatistics, String> {
+    let cache_folder = get_cache_folder()?;
+    let database_path = cache_folder.join(CACHE_DATABASE_FILE);
+    let mut stats = CacheExportStatistics::default();
+    let mut export = CacheExport {
+        version: CACHE_EXPORT_VERSION,
+        files: Vec::new(),
+    };
+
+    for (file_name, cache_type, from_database) in cache_storages(&cache_folder)? {
+        let cache_path = cache_folder.join(&file_name);
+        let storage = if from_database {
+            CacheStorage::DatabaseTable(&database_path, &file_name)
+        } else {
+            CacheStorage::File(&cache_path)
+        };
+        match export_cache_file(storage, cache_type) {
+            Ok(entries) => {
+                stats.exported_files += 1;
+                stats.exported_entries += entries.len();
+                export.files.push(ExportedCacheFile {
+                    file_name,
+                    from_database,
+                    entries,
+                });
+            }
+            Err(e) => stats.errors.push(format!("{}: {e}", storage_name(&file_name, from_database))),
+        }
+    }
+
+    let file = fs::File::create(export_path).map_err(|e| format!("Cannot create file \"{}\": {e}", export_path.to_string_lossy()))?;
+    serde_json::to_writer(BufWriter::new(file), &export).map_err(|e| format!("Cannot save exported cache to \"{}\": {e}", export_path.to_string_lossy()))?;
+
+    Ok(stats)
+}
+
+/// Merges entries exported on other machine into local cache files or into cache database, depending on given cache backend.
+/// Entries exported from both backends are imported. When both local and imported cache contain entry for the same path, local entry is kept.
+pub fn import_cache_files(import_path: &Path, cache_backend: CacheBackend) -> Result<CacheImportStatistics, String> {
+    let file = fs::File::open(import_path).map_err(|e| format!("Cannot open file \"{}\": {e}", import_path.to_string_lossy()))?;
+    let export: CacheExport = serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("Cannot parse exported cache \"{}\": {e}", import_path.to_string_lossy()))?;
+    if export.version != CACHE_EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported version {} of exported cache, only version {CACHE_EXPORT_VERSION} is supported",
+            export.version
+        ));
+    }
+
+    let cache_folder = get_cache_folder()?;
+    let database_path = cache_folder.join(CACHE_DATABASE_FILE);
+    let mut stats = CacheImportStatistics::default();
+
+    for exported_file in export.files {
+        let file_name = exported_file.file_name;
+        // File name is used to create path, so it must not point outside cache folder
+        let cache_type = CacheType::from_filename(&file_name).filter(|_| !file_name.contains(['/', '\\']));
+        let Some(cache_type) = cache_type else {
+            stats
+                .errors
+                .push(format!("{file_name}: unknown cache file, it was probably exported by different version of Czkawka"));
+            continue;
+        };
+
+        let cache_path = cache_folder.join(&file_name);
+        let storage = match cache_backend {
+            CacheBackend::Bincode => CacheStorage::File(&cache_path),
+            CacheBackend::Database => CacheStorage::DatabaseTable(&database_path, &file_name),
+        };
+        match import_cache_file(storage, cache_type, exported_file.entries) {
+            Ok((added, skipped)) => {
+                stats.imported_files += 1;
+                stats.added_entries += added;
+                stats.skipped_entries += skipped;
+            }
+            Err(e) => stats.errors.push(format!("{file_name}: {e}")),
+        }
+    }
+
+    Ok(stats)
+}
+
+fn prune_cache_file(storage: CacheStorage<'_>, cache_type: CacheType, older_than: u64, dry_run: bool) -> Result<(usize, usize), String> {
+    match cache_type {
+        CacheType::Duplicates => prune_entries::<DuplicateEntry>(storage, older_than, dry_run),
+        CacheType::MusicTags | CacheType::MusicFingerprints => prune_entries::<MusicEntry>(storage, older_than, dry_run),
+        CacheType::SimilarImages => prune_entries::<ImagesEntry>(storage, older_than, dry_run),
+        CacheType::SimilarVideos => prune_entries::<VideosEntry>(storage, older_than, dry_run),
+        CacheType::BrokenFiles => prune_entries::<BrokenEntry>(storage, older_than, dry_run),
+        CacheType::ExifRemover => prune_entries::<ExifEntry>(storage, older_than, dry_run),
+        CacheType::VideoTranscode => prune_entries::<VideoTranscodeEntry>(storage, older_than, dry_run),
+        CacheType::VideoCrop => prune_entries::<VideoCropEntry>(storage, older_than, dry_run),
+    }
+}
+
+fn export_cache_file(storage: CacheStorage<'_>, cache_type: CacheType) -> Result<Vec<serde_json::Value>, String> {
+    match cache_type {
+        CacheType::Duplicates => export_entries::<DuplicateEntry>(storage),
+        CacheType::MusicTags | CacheType::MusicFingerprints => export_entries::<MusicEntry>(storage),
+        CacheType::SimilarImages => export_entries::<ImagesEntry>(storage),
+        CacheType::SimilarVideos => export_entries::<VideosEntry>(storage),
+        CacheType::BrokenFiles => export_entries::<BrokenEntry>(storage),
+        CacheType::ExifRemover => export_entries::<ExifEntry>(storage),
+        CacheType::VideoTranscode => export_entries::<VideoTranscodeEntry>(storage),
+        CacheType::VideoCrop => export_entries::<VideoCropEntry>(storage),
+    }
+}
+
+fn import_cache_file(storage: CacheStorage<'_>, cache_type: CacheType, imported: Vec<serde_json::Value>) -> Result<(usize, usize), String> {
+    match cache_type {
+        CacheType::Duplicates => import_entries::<DuplicateEntry>(storage, imported),
+        CacheType::MusicTags | CacheType::MusicFingerprints => import_entries::<MusicEntry>(storage, imported),
+        CacheType::SimilarImages => import_entries::<ImagesEntry>(storage, imported),
+        CacheType::SimilarVideos => import_entries::<VideosEntry>(storage, imported),
+        CacheType::BrokenFiles => import_entries::<BrokenEntry>(storage, imported),
+        CacheType::ExifRemover => import_entries::<ExifEntry>(storage, imported),
+        CacheType::VideoTranscode => import_entries::<VideoTranscodeEntry>(storage, imported),
+        CacheType::VideoCrop => import_entries::<VideoCropEntry>(storage, imported),
+    }
+}
+
+fn read_cache_entries<T: DeserializeOwned>(cache_path: &Path) -> Result<Vec<T>, String> {
+    let file = fs::File::open(cache_path).map_err(|e| format!("Cannot open file: {e}"))?;
+    bincode::DefaultOptions::new()
+        .with_limit(MEMORY_LIMIT)
+        .deserialize_from(BufReader::new(file))
+        .map_err(|e| format!("Cannot deserialize file: {e}"))
+}
+
+// Entries are saved to temporary file first, so original cache file is never left half written
+fn write_cache_entries<T: Serialize>(cache_path: &Path, entries: &[T]) -> Result<(), String> {
+    let tmp_file_path = cache_path.with_extension("tmp");
+    let tmp_file = fs::File::create(&tmp_file_path).map_err(|e| format!("Cannot create temporary file: {e}"))?;
+    bincode::DefaultOptions::new()
+        .with_limit(MEMORY_LIMIT)
+        .serialize_into(BufWriter::new(tmp_file), entries)
+        .map_err(|e| format!("Cannot serialize data to temporary file: {e}"))?;
+    fs::rename(&tmp_file_path, cache_path).map_err(|e| format!("Cannot replace original cache file: {e}"))
+}
+
+// Returns number of removed entries and number of entries left in cache file
+fn prune_entries<T: DeserializeOwned + Serialize + ResultEntry>(storage: CacheStorage<'_>, older_than: u64, dry_run: bool) -> Result<(usize, usize), String> {
+    let is_outdated = |entry: &T| entry.get_modified_date() < older_than;
+    match storage {
+        CacheStorage::File(cache_path) => {
+            let entries = read_cache_entries::<T>(cache_path)?;
+            let all_entries = entries.len();
+            let entries: Vec<T> = entries.into_iter().filter(|entry| !is_outdated(entry)).collect();
+            let removed = all_entries - entries.len();
+            // Empty files are removed by caller
+            if removed > 0 && !entries.is_empty() && !dry_run {
+                write_cache_entries(cache_path, &entries)?;
+            }
+            Ok((removed, entries.len()))
+        }
+        // Tables are never removed, so number of left entries is not counted
+        CacheStorage::DatabaseTable(database_path, table_name) => Ok((remove_entries::<T>(database_path, table_name, dry_run, is_outdated)?, 0)),
+    }
+}
+
+fn export_entries<T: DeserializeOwned + Serialize>(storage: CacheStorage<'_>) -> Result<Vec<serde_json::Value>, String> {
+    let entries = match storage {
+        CacheStorage::File(cache_path) => read_cache_entries::<T>(cache_path)?,
+        CacheStorage::DatabaseTable(database_path, table_name) => load_all_entries::<T>(database_path, table_name)?,
+    };
+    entries
+        .iter()
+        .map(|entry| serde_json::to_value(entry).map_err(|e| format!("Cannot convert entry to json: {e}")))
+        .collect()
+}
+
+fn import_entries<T: DeserializeOwned + Serialize + ResultEntry>(storage: CacheStorage<'_>, imported: Vec<serde_json::Value>) -> Result<(usize, usize), String> {
+    let imported: Vec<T> = imported
+        .into_iter()
+        .map(|value| serde_json::from_value(value).map_err(|e| format!("Cannot parse imported entry: {e}")))
+        .collect::<Result<_, _>>()?;
+
+    let cache_path = match storage {
+        CacheStorage::File(cache_path) => cache_path,
+        // Paths inside entries are already converted to volume paths when possible, so they can be used as keys
+        CacheStorage::DatabaseTable(database_path, table_name) => {
+            let entries = imported.into_iter().map(|entry| (entry.get_path().to_string_lossy().to_string(), entry));
+            return insert_missing_entries(database_path, table_name, entries);
+        }
+    };
+
+    let mut entries: BTreeMap<String, T> = BTreeMap::new();
+    if cache_path.exists() {
+        for entry in read_cache_entries::<T>(cache_path)? {
+            entries.insert(entry.get_path().to_string_lossy().to_string(), entry);
+        }
+    }
+
+    let mut added = 0;
+    let mut skipped = 0;
+    for entry in imported {
+        match entries.entry(entry.get_path().to_string_lossy().to_string()) {
+            Entry::Vacant(vacant) => {
+                vacant.insert(entry);
+                added += 1;
+            }
+            Entry::Occupied(_) => skipped += 1,
+        }
+    }
+
+    if added > 0 {
+        let entries: Vec<T> = entries.into_values().collect();
+        write_cache_entries(cache_path, &entries)?;
+    }
+
+    Ok((added, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde::{Deserialize, Serialize};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct TestEntry {
+        path: PathBuf,
+        size: u64,
+        modified_date: u64,
+    }
+
+    impl ResultEntry for TestEntry {
+        fn get_path(&self) -> &Path {
+            &self.path
+        }
+        fn get_modified_date(&self) -> u64 {
+            self.modified_date
+        }
+        fn get_size(&self) -> u64 {
+            self.size
+        }
+    }
+
+    fn entry(path: &str, size: u64) -> TestEntry {
+        TestEntry {
+            path: PathBuf::from(path),
+            size,
+            modified_date: 1000,
+        }
+    }
+
+    #[test]
+    fn test_export_and_import_merges_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let exported_path = temp_dir.path().join("exported.bin");
+        let local_path = temp_dir.path().join("local.bin");
+        let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+        options
+            .serialize_into(fs::File::create(&exported_path).unwrap(), &vec![entry("/a", 1), entry("/b", 2)])
+            .unwrap();
+        options.serialize_into(fs::File::create(&local_path).unwrap(), &vec![entry("/b", 20)]).unwrap();
+        assert_eq!(read_entries_count(&exported_path), Some(2));
+
+        let exported = export_entries::<TestEntry>(CacheStorage::File(&exported_path)).unwrap();
+        assert_eq!(import_entries::<TestEntry>(CacheStorage::File(&local_path), exported.clone()).unwrap(), (1, 1));
+        assert_eq!(read_cache_entries::<TestEntry>(&local_path).unwrap(), vec![entry("/a", 1), entry("/b", 20)]);
+
+        let new_path = temp_dir.path().join("new.bin");
+        assert_eq!(import_entries::<TestEntry>(CacheStorage::File(&new_path), exported).unwrap(), (2, 0));
+        assert_eq!(read_entries_count(&new_path), Some(2));
+    }
+
+    #[test]
+    fn test_prune_removes_entries_of_old_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("cache.bin");
+        let new_entry = TestEntry {
+            modified_date: 3000,
+            ..entry("/b", 2)
+        };
+        write_cache_entries(&cache_path, &[entry("/a", 1), new_entry.clone()]).unwrap();
+
+        assert_eq!(prune_entries::<TestEntry>(CacheStorage::File(&cache_path), 2000, true).unwrap(), (1, 1));
+        assert_eq!(read_entries_count(&cache_path), Some(2));
+
+        assert_eq!(prune_entries::<TestEntry>(CacheStorage::File(&cache_path), 2000, false).unwrap(), (1, 1));
+        assert_eq!(read_cache_entries::<TestEntry>(&cache_path).unwrap(), vec![new_entry]);
+
+        // File is not rewritten when all entries are removed, caller removes it
+        assert_eq!(prune_entries::<TestEntry>(CacheStorage::File(&cache_path), 4000, false).unwrap(), (1, 0));
+        assert_eq!(read_entries_count(&cache_path), Some(1));
+    }
+
+    #[test]
+    fn test_import_export_and_prune_database_table() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join(CACHE_DATABASE_FILE);
+        let storage = CacheStorage::DatabaseTable(&database_path, "cache_table.bin");
+        let new_entry = TestEntry {
+            modified_date: 3000,
+            ..entry("/b", 2)
+        };
+        let exported: Vec<_> = [entry("/a", 1), new_entry.clone()].iter().map(|entry| serde_json::to_value(entry).unwrap()).collect();
+
+        assert_eq!(import_entries::<TestEntry>(storage, exported.clone()).unwrap(), (2, 0));
+        assert_eq!(import_entries::<TestEntry>(storage, exported.clone()).unwrap(), (0, 2));
+        assert_eq!(export_entries::<TestEntry>(storage).unwrap(), exported);
+
+        assert_eq!(prune_entries::<TestEntry>(storage, 2000, true).unwrap().0, 1);
+        assert_eq!(prune_entries::<TestEntry>(storage, 2000, false).unwrap().0, 1);
+        assert_eq!(load_all_entries::<TestEntry>(&database_path, "cache_table.bin").unwrap(), vec![new_entry]);
+    }
+
+    #[test]
+    fn test_is_cache_file() {
+        assert!(is_cache_file("cache_broken_files_120.bin", false));
+        assert!(!is_cache_file("cache_broken_files_120.json", false));
+        assert!(is_cache_file("cache_broken_files_120.json", true));
+        assert!(!is_cache_file("cleaning_timestamps.json", true));
+        assert!(!is_cache_file(CACHE_DATABASE_FILE, true));
+    }
+}
@@ -0,0 +1,310 @@
+use std::borrow::Cow;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Serializer};
+
+use crate::common::traits::CacheEntry;
+
+// Cache entries are saved with path in format `volume:<volume id>:<path inside volume>` when file is on volume with known id,
+// so cache can be still used after mounting volume in different folder.
+// Entries saved with absolute paths(e.g. by older versions) are still loaded and converted when cache is saved again(bincode) or when they are loaded(database).
+const VOLUME_PATH_PREFIX: &str = "volume:";
+// File with custom id of volume, placed in folder where volume is mounted, used when volume doesn't have UUID(e.g. network shares)
+#[cfg_attr(not(target_os = "linux"), expect(dead_code))]
+const VOLUME_ID_FILE: &str = ".czkawka_volume_id";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Volume {
+    id: String,
+    mount_point: PathBuf,
+    // Folder of volume visible in mount point - different from "/" e.g. for bind mounts or btrfs subvolumes
+    root: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ResolvedPath {
+    NotVolumePath,
+    Mounted(PathBuf),
+    NotMounted,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct VolumeResolver {
+    // Sorted from the longest mount point, so nested mounts are found before their parents
+    volumes: Vec<Volume>,
+}
+
+impl VolumeResolver {
+    pub(crate) fn new() -> Self {
+        Self::with_volumes(current_volumes())
+    }
+
+    fn with_volumes(mut volumes: Vec<Volume>) -> Self {
+        volumes.sort_by_key(|volume| std::cmp::Reverse(volume.mount_point.as_os_str().len()));
+        Self { volumes }
+    }
+
+    pub(crate) fn to_volume_path(&self, path: &Path) -> Option<PathBuf> {
+        let volume = self.volumes.iter().find(|volume| path.starts_with(&volume.mount_point))?;
+        let relative_path = path.strip_prefix(&volume.mount_point).ok()?;
+        let volume_path = volume.root.join(relative_path);
+        Some(PathBuf::from(format!("{VOLUME_PATH_PREFIX}{}:{}", volume.id, volume_path.to_str()?)))
+    }
+
+    pub(crate) fn resolve(&self, path: &Path) -> ResolvedPath {
+        let Some((id, volume_path)) = path.to_str().and_then(|path| path.strip_prefix(VOLUME_PATH_PREFIX)).and_then(|path| path.split_once(':')) else {
+            return ResolvedPath::NotVolumePath;
+        };
+        let volume_path = Path::new(volume_path);
+        for volume in self.volumes.iter().filter(|volume| volume.id == id) {
+            if let Ok(relative_path) = volume_path.strip_prefix(&volume.root) {
+                return ResolvedPath::Mounted(volume.mount_point.join(relative_path));
+            }
+        }
+        ResolvedPath::NotMounted
+    }
+
+    /// Key under which entry of file is saved in cache
+    pub(crate) fn cache_key(&self, path: &Path) -> String {
+        match self.to_volume_path(path) {
+            Some(volume_path) => volume_path.to_string_lossy().into_owned(),
+            None => path.to_string_lossy().into_owned(),
+        }
+    }
+
+    pub(crate) fn to_cache_entry<'a, T: CacheEntry + Clone>(&self, entry: &'a T) -> Cow<'a, T> {
+        match self.to_volume_path(entry.get_path()) {
+            Some(volume_path) => {
+                let mut entry = entry.clone();
+                entry.set_path(volume_path);
+                Cow::Owned(entry)
+            }
+            None => Cow::Borrowed(entry),
+        }
+    }
+
+    /// Checks if file of cache entry exists, entries from not mounted volumes cannot be checked, so are treated as existing
+    pub(crate) fn may_exist(&self, path: &Path) -> bool {
+        match self.resolve(path) {
+            ResolvedPath::NotVolumePath => path.exists(),
+            ResolvedPath::Mounted(path) => path.exists(),
+            ResolvedPath::NotMounted => true,
+        }
+    }
+
+    /// Changes path of loaded entry to current location of file, entries from not mounted volumes are left unchanged
+    pub(crate) fn resolve_entry<T: CacheEntry>(&self, entry: &mut T) {
+        if let ResolvedPath::Mounted(path) = self.resolve(entry.get_path()) {
+            entry.set_path(path);
+        }
+    }
+}
+
+/// Serializes entries with volume paths, without cloning whole cache at once
+pub(crate) struct VolumePathEntries<'a, T> {
+    pub(crate) entries: &'a [&'a T],
+    pub(crate) volume_resolver: &'a VolumeResolver,
+}
+
+impl<T: CacheEntry + Clone + Serialize> Serialize for VolumePathEntries<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.entries.iter().map(|entry| self.volume_resolver.to_cache_entry(*entry)))
+    }
+}
+
+fn is_valid_volume_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(not(target_os = "linux"))]
+fn current_volumes() -> Vec<Volume> {
+    Vec::new()
+}
+
+#[cfg(target_os = "linux")]
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "proc",
+    "sysfs",
+    "cgroup",
+    "cgroup2",
+    "devtmpfs",
+    "devpts",
+    "securityfs",
+    "debugfs",
+    "tracefs",
+    "pstore",
+    "bpf",
+    "mqueue",
+    "hugetlbfs",
+    "configfs",
+    "fusectl",
+    "autofs",
+    "binfmt_misc",
+    "efivarfs",
+    "nsfs",
+];
+
+#[cfg(target_os = "linux")]
+fn current_volumes() -> Vec<Volume> {
+    let Ok(mount_info) = fs::read_to_string("/proc/self/mountinfo") else {
+        return Vec::new();
+    };
+    let device_uuids = device_uuids();
+
+    // Later mounts hide earlier ones mounted in the same folder
+    let mut volumes: HashMap<PathBuf, Volume> = HashMap::new();
+    for line in mount_info.lines() {
+        let Some(mount) = parse_mount_info_line(line) else {
+            continue;
+        };
+        volumes.remove(&mount.mount_point);
+        if PSEUDO_FILESYSTEMS.contains(&mount.fs_type.as_str()) {
+            continue;
+        }
+
+        let custom_id = fs::read_to_string(mount.mount_point.join(VOLUME_ID_FILE))
+            .ok()
+            .map(|id| id.trim().to_string())
+            .filter(|id| is_valid_volume_id(id));
+        let volume = if let Some(id) = custom_id {
+            Volume {
+                id,
+                mount_point: mount.mount_point,
+                root: PathBuf::from("/"),
+            }
+        } else if let Some(id) = fs::canonicalize(&mount.source).ok().and_then(|device| device_uuids.get(&device).cloned()) {
+            Volume {
+                id,
+                mount_point: mount.mount_point,
+                root: mount.root,
+            }
+        } else {
+            continue;
+        };
+        volumes.insert(volume.mount_point.clone(), volume);
+    }
+    volumes.into_values().collect()
+}
+
+#[cfg(target_os = "linux")]
+fn device_uuids() -> HashMap<PathBuf, String> {
+    let Ok(read_dir) = fs::read_dir("/dev/disk/by-uuid") else {
+        return HashMap::new();
+    };
+    read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let uuid = entry.file_name().into_string().ok().filter(|uuid| is_valid_volume_id(uuid))?;
+            let device = fs::canonicalize(entry.path()).ok()?;
+            Some((device, uuid))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, PartialEq, Eq)]
+struct MountInfo {
+    root: PathBuf,
+    mount_point: PathBuf,
+    fs_type: String,
+    source: String,
+}
+
+// Line format - `36 35 98:0 /root /mount/point rw,noatime master:1 - ext4 /dev/sda1 rw`
+#[cfg(target_os = "linux")]
+fn parse_mount_info_line(line: &str) -> Option<MountInfo> {
+    let (mount_fields, fs_fields) = line.split_once(" - ")?;
+    let mut mount_fields = mount_fields.split(' ');
+    let root = unescape_mount_field(mount_fields.nth(3)?);
+    let mount_point = unescape_mount_field(mount_fields.next()?);
+    let mut fs_fields = fs_fields.split(' ');
+    let fs_type = fs_fields.next()?.to_string();
+    let source = unescape_mount_field(fs_fields.next()?);
+    Some(MountInfo {
+        root: PathBuf::from(root),
+        mount_point: PathBuf::from(mount_point),
+        fs_type,
+        source,
+    })
+}
+
+// Spaces and other special characters are escaped as octal numbers e.g. `\040`
+#[cfg(target_os = "linux")]
+fn unescape_mount_field(field: &str) -> String {
+    let mut result = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some((before, after)) = rest.split_once('\\') {
+        result.push_str(before);
+        if let Some(character) = after.get(..3).and_then(|escaped| u8::from_str_radix(escaped, 8).ok()) {
+            result.push(char::from(character));
+            rest = after.get(3..).unwrap_or_default();
+        } else {
+            result.push('\\');
+            rest = after;
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(id: &str, mount_point: &str, root: &str) -> Volume {
+        Volume {
+            id: id.to_string(),
+            mount_point: PathBuf::from(mount_point),
+            root: PathBuf::from(root),
+        }
+    }
+
+    #[test]
+    fn test_volume_paths_after_remount() {
+        let resolver = VolumeResolver::with_volumes(vec![volume("ROOT-UUID", "/", "/"), volume("PHOTOS-UUID", "/media/user/Photos", "/")]);
+        let volume_path = resolver.to_volume_path(Path::new("/media/user/Photos/2024/a.jpg")).unwrap();
+        assert_eq!(volume_path, PathBuf::from("volume:PHOTOS-UUID:/2024/a.jpg"));
+        assert_eq!(resolver.cache_key(Path::new("/home/a.txt")), "volume:ROOT-UUID:/home/a.txt");
+
+        let remounted = VolumeResolver::with_volumes(vec![volume("ROOT-UUID", "/", "/"), volume("PHOTOS-UUID", "/mnt/photos", "/")]);
+        assert_eq!(remounted.resolve(&volume_path), ResolvedPath::Mounted(PathBuf::from("/mnt/photos/2024/a.jpg")));
+
+        let unplugged = VolumeResolver::with_volumes(vec![volume("ROOT-UUID", "/", "/")]);
+        assert_eq!(unplugged.resolve(&volume_path), ResolvedPath::NotMounted);
+        assert_eq!(unplugged.resolve(Path::new("/home/a.txt")), ResolvedPath::NotVolumePath);
+        assert_eq!(VolumeResolver::default().to_volume_path(Path::new("/home/a.txt")), None);
+    }
+
+    #[test]
+    fn test_volume_paths_with_subvolume_root() {
+        let resolver = VolumeResolver::with_volumes(vec![volume("DATA-UUID", "/home", "/@home")]);
+        let volume_path = resolver.to_volume_path(Path::new("/home/user/a.txt")).unwrap();
+        assert_eq!(volume_path, PathBuf::from("volume:DATA-UUID:/@home/user/a.txt"));
+
+        let other_subvolume = VolumeResolver::with_volumes(vec![volume("DATA-UUID", "/", "/@")]);
+        assert_eq!(other_subvolume.resolve(&volume_path), ResolvedPath::NotMounted);
+        let whole_volume = VolumeResolver::with_volumes(vec![volume("DATA-UUID", "/mnt/data", "/")]);
+        assert_eq!(whole_volume.resolve(&volume_path), ResolvedPath::Mounted(PathBuf::from("/mnt/data/@home/user/a.txt")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_mount_info_line() {
+        let mount = parse_mount_info_line(r"36 35 8:17 / /media/user/My\040Photos rw,nosuid shared:1 - ext4 /dev/sdb1 rw").unwrap();
+        assert_eq!(
+            mount,
+            MountInfo {
+                root: PathBuf::from("/"),
+                mount_point: PathBuf::from("/media/user/My Photos"),
+                fs_type: "ext4".to_string(),
+                source: "/dev/sdb1".to_string(),
+            }
+        );
+        assert_eq!(parse_mount_info_line("invalid line"), None);
+        assert_eq!(unescape_mount_field(r"a\134b\04"), r"a\b\04");
+    }
+}
@@ -0,0 +1,169 @@
+use std::fs::Metadata;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_IN_HOUR: u64 = 60 * 60;
+const SECONDS_IN_DAY: u64 = 24 * SECONDS_IN_HOUR;
+
+/// Range of unix timestamps(in seconds), both limits are inclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub after: Option<u64>,
+    pub before: Option<u64>,
+}
+
+impl DateRange {
+    pub fn new(after: Option<u64>, before: Option<u64>) -> Self {
+        Self { after, before }
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.after.is_some() || self.before.is_some()
+    }
+
+    pub fn contains(&self, timestamp: u64) -> bool {
+        self.after.is_none_or(|after| timestamp >= after) && self.before.is_none_or(|before| timestamp <= before)
+    }
+}
+
+/// Limits of modification, creation and access time of collected files.
+/// Creation and access time are not available on every platform/filesystem, in that case files are not filtered by them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateFilter {
+    pub modified: DateRange,
+    pub created: DateRange,
+    pub accessed: DateRange,
+}
+
+impl DateFilter {
+    pub fn is_set(&self) -> bool {
+        self.modified.is_set() || self.created.is_set() || self.accessed.is_set()
+    }
+
+    pub(crate) fn matches(&self, metadata: &Metadata, modified_date: u64) -> bool {
+        let created_date = if self.created.is_set() { timestamp(metadata.created()) } else { None };
+        let accessed_date = if self.accessed.is_set() { timestamp(metadata.accessed()) } else { None };
+        self.matches_dates(modified_date, created_date, accessed_date)
+    }
+
+    pub(crate) fn matches_dates(&self, modified_date: u64, created_date: Option<u64>, accessed_date: Option<u64>) -> bool {
+        self.modified.contains(modified_date)
+            && created_date.is_none_or(|created| self.created.contains(created))
+            && accessed_date.is_none_or(|accessed| self.accessed.contains(accessed))
+    }
+}
+
+pub(crate) fn timestamp(time: io::Result<SystemTime>) -> Option<u64> {
+    time.ok()?.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+/// Parses limit of date range to unix timestamp.
+/// Accepts absolute UTC date `2024-05-31` or `2024-05-31 14:30` and relative time ago with unit -
+/// `h`(hours), `d`(days), `w`(weeks), `m`(months, 30 days) or `y`(years, 365 days), e.g. `180d` means 180 days ago.
+pub fn parse_date_limit(value: &str) -> Result<u64, String> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    parse_date_limit_at(value, now)
+}
+
+fn parse_date_limit_at(value: &str, now: u64) -> Result<u64, String> {
+    let value = value.trim();
+    if value.contains('-') {
+        return parse_absolute_date(value).ok_or_else(|| format!("Invalid date \"{value}\", expected format YYYY-MM-DD or YYYY-MM-DD HH:MM"));
+    }
+
+    let unit_start = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(unit_start);
+    let Ok(number) = number.parse::<u64>() else {
+        return Err(format!("Invalid relative date \"{value}\", expected number with unit e.g. 30d"));
+    };
+    let unit_seconds = match unit.trim() {
+        "h" => SECONDS_IN_HOUR,
+        "d" => SECONDS_IN_DAY,
+        "w" => 7 * SECONDS_IN_DAY,
+        "m" => 30 * SECONDS_IN_DAY,
+        "y" => 365 * SECONDS_IN_DAY,
+        _ => return Err(format!("Invalid unit in relative date \"{value}\", allowed units are h, d, w, m and y")),
+    };
+    Ok(now.saturating_sub(number.saturating_mul(unit_seconds)))
+}
+
+fn parse_absolute_date(value: &str) -> Option<u64> {
+    let (date, time) = match value.split_once([' ', 'T']) {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (value, None),
+    };
+
+    let mut date_parts = date.split('-');
+    let year = date_parts.next()?.parse::<u64>().ok()?;
+    let month = date_parts.next()?.parse::<u64>().ok()?;
+    let day = date_parts.next()?.parse::<u64>().ok()?;
+    if date_parts.next().is_some() || year < 1970 || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let mut seconds = days_since_epoch(year, month, day) * SECONDS_IN_DAY;
+    if let Some(time) = time {
+        let (hour, minute) = time.split_once(':')?;
+        let (hour, minute) = (hour.parse::<u64>().ok()?, minute.parse::<u64>().ok()?);
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        seconds += hour * SECONDS_IN_HOUR + minute * 60;
+    }
+    Some(seconds)
+}
+
+fn is_leap_year(year: u64) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn days_since_epoch(year: u64, month: u64, day: u64) -> u64 {
+    let days_in_previous_years: u64 = (1970..year).map(|y| if is_leap_year(y) { 366 } else { 365 }).sum();
+    let days_in_previous_months: u64 = (1..month).map(|m| days_in_month(year, m)).sum();
+    days_in_previous_years + days_in_previous_months + day - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date_limit() {
+        assert_eq!(parse_date_limit_at("1970-01-01", 0), Ok(0));
+        assert_eq!(parse_date_limit_at("2000-03-01", 0), Ok(951_868_800));
+        assert_eq!(parse_date_limit_at("2024-02-29 12:30", 0), Ok(1_709_209_800));
+
+        let now = 1_000_000_000;
+        assert_eq!(parse_date_limit_at("12h", now), Ok(now - 12 * 3600));
+        assert_eq!(parse_date_limit_at(" 180d ", now), Ok(now - 180 * 86400));
+        assert_eq!(parse_date_limit_at("2w", now), Ok(now - 14 * 86400));
+        assert_eq!(parse_date_limit_at("1y", now), Ok(now - 365 * 86400));
+        assert_eq!(parse_date_limit_at("100000y", now), Ok(0));
+
+        for invalid in ["", "d", "10", "10x", "2023-02-29", "2024-13-01", "1969-12-31", "2024-01-01 24:00", "2024-01"] {
+            assert_eq!(parse_date_limit_at(invalid, now).ok(), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_date_range_contains() {
+        assert!(DateRange::default().contains(5));
+        assert!(!DateRange::default().is_set());
+
+        let range = DateRange::new(Some(10), Some(20));
+        assert!(range.is_set());
+        assert!(range.contains(10) && range.contains(20));
+        assert!(!range.contains(9) && !range.contains(21));
+        assert!(DateRange::new(None, Some(20)).contains(0));
+        assert!(DateRange::new(Some(10), None).contains(u64::MAX));
+    }
+}
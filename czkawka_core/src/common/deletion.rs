@@ -1,5 +1,6 @@
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crossbeam_channel::Sender;
@@ -7,12 +8,18 @@ use humansize::{BINARY, format_size};
 use log::info;
 use rayon::prelude::*;
 
+use crate::common::journal::{Journal, JournalAction, JournalEntry};
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::common::quarantine::Quarantine;
+use crate::common::select_rules::SelectionRule;
 use crate::common::tool_data::{CommonToolData, DeleteMethod};
 use crate::common::traits::ResultEntry;
-use crate::common::{make_hard_link, remove_folder_if_contains_only_empty_folders, remove_single_file};
+use crate::common::{
+    check_if_folder_contains_only_empty_folders, get_relative_path, make_file_symlink, make_hard_link, make_reflink, remove_folder_if_contains_only_empty_folders,
+    remove_single_file,
+};
 use crate::helpers::delayed_sender::DelayedSender;
 use crate::helpers::messages::Messages;
 
@@ -20,6 +27,8 @@ use crate::helpers::messages::Messages;
 pub struct DeleteResult {
     deleted_files: usize,
     hardlinked_files: usize,
+    reflinked_files: usize,
+    symlinked_files: usize,
     gained_bytes: u64,
     failed_to_delete_files: usize,
     errors: Vec<String>,
@@ -38,21 +47,67 @@ pub enum DeleteItemType<T: ResultEntry + Sized + Send + Sync> {
     DeletingFiles(Vec<T>),
     DeletingFolders(Vec<T>),
     HardlinkingFiles(Vec<(T, Vec<T>)>),
+    ReflinkingFiles(Vec<(T, Vec<T>)>),
+    SymlinkingFiles(Vec<(T, Vec<T>)>),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum LinkType {
+    Hard,
+    Reflink,
+    Symlink,
+}
+
+impl LinkType {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Hard => "hardlink",
+            Self::Reflink => "reflink",
+            Self::Symlink => "symlink",
+        }
+    }
+
+    fn stage(self) -> ToolStage {
+        match self {
+            Self::Hard => ToolStage::HardlinkingFiles,
+            Self::Reflink => ToolStage::ReflinkingFiles,
+            Self::Symlink => ToolStage::SymlinkingFiles,
+        }
+    }
+
+    fn journal_action(self) -> JournalAction {
+        match self {
+            Self::Hard => JournalAction::HardLink,
+            Self::Reflink => JournalAction::Reflink,
+            Self::Symlink => JournalAction::Symlink,
+        }
+    }
 }
 
 impl<T: ResultEntry + Sized + Send + Sync> DeleteItemType<T> {
+    fn link_type(&self) -> Option<LinkType> {
+        match self {
+            Self::DeletingFiles(_) | Self::DeletingFolders(_) => None,
+            Self::HardlinkingFiles(_) => Some(LinkType::Hard),
+            Self::ReflinkingFiles(_) => Some(LinkType::Reflink),
+            Self::SymlinkingFiles(_) => Some(LinkType::Symlink),
+        }
+    }
+
     fn calculate_size_to_delete(&self) -> u64 {
         match &self {
             Self::DeletingFiles(items) | Self::DeletingFolders(items) => items.iter().map(|item| item.get_size()).sum(),
             // Sum the linked files, not the originals, to match what progress accounts.
-            Self::HardlinkingFiles(items) => items.iter().flat_map(|(_original, files)| files.iter().map(ResultEntry::get_size)).sum(),
+            Self::HardlinkingFiles(items) | Self::ReflinkingFiles(items) | Self::SymlinkingFiles(items) => {
+                items.iter().flat_map(|(_original, files)| files.iter().map(ResultEntry::get_size)).sum()
+            }
         }
     }
 
     fn calculate_entries_to_delete(&self) -> usize {
         match &self {
             Self::DeletingFiles(items) | Self::DeletingFolders(items) => items.len(),
-            Self::HardlinkingFiles(items) => items.iter().map(|(_original, files)| files.len()).sum(),
+            Self::HardlinkingFiles(items) | Self::ReflinkingFiles(items) | Self::SymlinkingFiles(items) => items.iter().map(|(_original, files)| files.len()).sum(),
         }
     }
 }
@@ -79,17 +134,59 @@ pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Size
     progress_sender: Option<&Sender<ProgressData>>,
     files_to_process: Vec<Vec<T>>,
 ) -> WorkContinueStatus {
+    let delete_results = match select_advanced_elements(cd, files_to_process) {
+        Ok(SelectedElements::Linking(res)) => {
+            let delete_item_type = match cd.delete_method {
+                DeleteMethod::Reflink => DeleteItemType::ReflinkingFiles(res),
+                DeleteMethod::Symlink => DeleteItemType::SymlinkingFiles(res),
+                _ => DeleteItemType::HardlinkingFiles(res),
+            };
+            delete_elements(cd, stop_flag, progress_sender, delete_item_type)
+        }
+        Ok(SelectedElements::Deleting(res)) => delete_elements(cd, stop_flag, progress_sender, DeleteItemType::DeletingFiles(res)),
+        Err(e) => {
+            cd.text_messages.errors.push(e);
+            return WorkContinueStatus::Continue;
+        }
+    };
+
+    if check_if_stop_received(stop_flag) {
+        WorkContinueStatus::Stop
+    } else {
+        delete_results.add_to_messages(&mut cd.text_messages);
+        WorkContinueStatus::Continue
+    }
+}
+
+pub(crate) enum SelectedElements<T> {
+    Deleting(Vec<T>),
+    // Original item and items which will be replaced by links to it
+    Linking(Vec<(T, Vec<T>)>),
+}
+
+// Chooses items from each group, which should be deleted or linked according to delete method, without touching them
+pub(crate) fn select_advanced_elements<T: ResultEntry + Clone>(cd: &CommonToolData, files_to_process: Vec<Vec<T>>) -> Result<SelectedElements<T>, String> {
     let delete_method = cd.delete_method;
     let sorting_by_size = matches!(
         delete_method,
         DeleteMethod::AllExceptBiggest | DeleteMethod::AllExceptSmallest | DeleteMethod::OneBiggest | DeleteMethod::OneSmallest
     );
+    // These methods delete items from the start of sorted group, so more preferred paths must be sorted later
+    let keep_last = matches!(
+        delete_method,
+        DeleteMethod::AllExceptNewest | DeleteMethod::AllExceptBiggest | DeleteMethod::OneOldest | DeleteMethod::OneSmallest
+    );
+    let preferred_paths = &cd.preferred_paths;
     let sort_items = |mut input: Vec<T>| -> Vec<T> {
-        input.sort_unstable_by_key(if sorting_by_size { ResultEntry::get_size } else { ResultEntry::get_modified_date });
+        input.sort_by_cached_key(|entry| {
+            let rank = preferred_paths.rank(entry.get_path());
+            let rank = if keep_last { usize::MAX - rank } else { rank };
+            (rank, if sorting_by_size { entry.get_size() } else { entry.get_modified_date() })
+        });
         input
     };
 
-    let delete_results = if delete_method == DeleteMethod::HardLink {
+    if matches!(delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::Symlink) {
         let res = files_to_process
             .into_iter()
             .map(|values| {
@@ -106,7 +203,9 @@ pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Size
                 (original, all_values)
             })
             .collect::<Vec<_>>();
-        delete_elements(cd, stop_flag, progress_sender, DeleteItemType::HardlinkingFiles(res))
+        Ok(SelectedElements::Linking(res))
+    } else if delete_method == DeleteMethod::Rule {
+        select_items_by_rule(cd.selection_rule.as_ref(), cd.use_reference_folders, files_to_process).map(SelectedElements::Deleting)
     } else {
         let res = files_to_process
             .into_iter()
@@ -148,19 +247,40 @@ pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Size
                         all_values.drain(..len - 1);
                         all_values
                     }
-                    DeleteMethod::HardLink | DeleteMethod::None => unreachable!("HardLink and None should be handled before"),
+                    DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::Symlink | DeleteMethod::Rule | DeleteMethod::None => {
+                        unreachable!("HardLink, Reflink, Symlink, Rule and None should be handled before")
+                    }
                 }
             })
             .collect::<Vec<_>>();
-        delete_elements(cd, stop_flag, progress_sender, DeleteItemType::DeletingFiles(res))
-    };
+        Ok(SelectedElements::Deleting(res))
+    }
+}
 
-    if check_if_stop_received(stop_flag) {
-        WorkContinueStatus::Stop
-    } else {
-        delete_results.add_to_messages(&mut cd.text_messages);
-        WorkContinueStatus::Continue
+// Reference item(first in group when reference folders are used) takes part in rule evaluation, but is never deleted
+fn select_items_by_rule<T: ResultEntry>(rule: Option<&SelectionRule>, use_reference_folders: bool, files_to_process: Vec<Vec<T>>) -> Result<Vec<T>, String> {
+    let Some(rule) = rule else {
+        return Err("Selection rule is required to use Rule delete method".to_string());
+    };
+    let mut files_to_delete = Vec::new();
+    for group in files_to_process {
+        let selected = rule
+            .select_group(&group, !use_reference_folders)
+            .map_err(|e| format!("Cannot use selection rule \"{rule}\", reason {e}"))?;
+        files_to_delete.extend(
+            group
+                .into_iter()
+                .zip(selected)
+                .skip(usize::from(use_reference_folders))
+                .filter_map(|(entry, selected)| selected.then_some(entry)),
+        );
     }
+    Ok(files_to_delete)
+}
+
+// Converts (reference, files) pairs into groups accepted by rule selection, where reference is always first item
+pub(crate) fn groups_with_reference_first<'a, T: Clone + 'a>(referenced: impl Iterator<Item = &'a (T, Vec<T>)>) -> Vec<Vec<T>> {
+    referenced.map(|(reference, files)| std::iter::once(reference).chain(files).cloned().collect()).collect()
 }
 
 #[expect(clippy::needless_pass_by_value)]
@@ -172,8 +292,14 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
 ) -> DeleteResult {
     let dry_run = cd.dry_run;
     let move_to_trash = cd.move_to_trash;
-    let is_hardlinking = matches!(delete_item_type, DeleteItemType::HardlinkingFiles(_));
-    let stage = if is_hardlinking { ToolStage::HardlinkingFiles } else { ToolStage::DeletingFiles };
+    let relative_symlinks = cd.relative_symlinks;
+    let link_type = delete_item_type.link_type();
+    let use_quarantine = cd.quarantine_path.is_some();
+    let stage = match link_type {
+        Some(link_type) => link_type.stage(),
+        None if use_quarantine => ToolStage::MovingFiles,
+        None => ToolStage::DeletingFiles,
+    };
     let progress = ProgressData::new(stage, delete_item_type.calculate_entries_to_delete(), delete_item_type.calculate_size_to_delete());
 
     let msg_common = format!(
@@ -181,12 +307,48 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
         progress.entries_to_check,
         format_size(progress.bytes_to_check, BINARY)
     );
-    if is_hardlinking {
-        info!("Hardlinking {msg_common}");
-    } else {
-        info!("Deleting {msg_common}");
+    match link_type {
+        Some(link_type) => info!("Creating {}s for {msg_common}", link_type.name()),
+        None if use_quarantine => info!("Moving to quarantine {msg_common}"),
+        None => info!("Deleting {msg_common}"),
     }
 
+    // Without a working journal nothing is touched, because user explicitly asked to be able to revert changes
+    let journal = match Journal::open_for_tool(cd) {
+        Ok(journal) => journal,
+        Err(e) => {
+            return DeleteResult {
+                failed_to_delete_files: progress.entries_to_check,
+                errors: vec![e],
+                ..Default::default()
+            };
+        }
+    };
+    let quarantine = match cd.quarantine_path.as_deref() {
+        Some(quarantine_path) if !dry_run && link_type.is_none() => match Quarantine::open(quarantine_path) {
+            Ok(quarantine) => Some(quarantine),
+            Err(e) => {
+                return DeleteResult {
+                    failed_to_delete_files: progress.entries_to_check,
+                    errors: vec![e],
+                    ..Default::default()
+                };
+            }
+        },
+        _ => None,
+    };
+    let journal_errors = Mutex::new(Vec::new());
+    // Operation is saved before it starts, so it is visible in journal even when process is killed in the middle
+    let start_in_journal = |action: JournalAction, entry: &T, destination: Option<&Path>| journal.as_ref().map(|journal| journal.start(action, entry, destination)).transpose();
+    let finish_in_journal = |journal_entry: Option<JournalEntry>, completed: bool| {
+        if let (Some(journal), Some(journal_entry)) = (&journal, journal_entry)
+            && let Err(e) = journal.finish(journal_entry, completed)
+        {
+            journal_errors.lock().unwrap_or_else(std::sync::PoisonError::into_inner).push(e);
+        }
+    };
+    let delete_action = if move_to_trash { JournalAction::Trash } else { JournalAction::Delete };
+
     let delayed_sender = progress_sender.map(|e| DelayedSender::new(e.clone(), Duration::from_millis(200)));
 
     let bytes_processed = Arc::new(std::sync::atomic::AtomicU64::new(0));
@@ -214,21 +376,36 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
                     return Some(vec![(e, None, None)]);
                 }
 
-                let delete_res = if matches!(delete_item_type, DeleteItemType::DeletingFiles(_)) {
-                    remove_single_file(e.get_path(), move_to_trash)
-                } else {
+                let is_folder = matches!(delete_item_type, DeleteItemType::DeletingFolders(_));
+                let quarantined_path = quarantine.as_ref().map(|quarantine| quarantine.free_quarantined_path(e.get_path()));
+                let journal_entry = match &quarantined_path {
+                    Some(quarantined_path) => start_in_journal(JournalAction::Move, e, Some(quarantined_path)),
+                    None => start_in_journal(delete_action, e, None),
+                };
+                let journal_entry = match journal_entry {
+                    Ok(journal_entry) => journal_entry,
+                    Err(err) => return Some(vec![(e, None, Some(err))]),
+                };
+
+                let delete_res = if let (Some(quarantine), Some(quarantined_path)) = (&quarantine, &quarantined_path) {
+                    if is_folder {
+                        check_if_folder_contains_only_empty_folders(e.get_path()).and_then(|()| quarantine.move_item(e, quarantined_path))
+                    } else {
+                        quarantine.move_item(e, quarantined_path)
+                    }
+                } else if is_folder {
                     remove_folder_if_contains_only_empty_folders(e.get_path(), move_to_trash)
+                } else {
+                    remove_single_file(e.get_path(), move_to_trash)
                 };
+                finish_in_journal(journal_entry, delete_res.is_ok());
 
-                match delete_res {
-                    Ok(()) => Some(vec![(e, None, None)]),
-                    Err(err) => Some(vec![(e, None, Some(err))]),
-                }
+                Some(vec![(e, None, delete_res.err())])
             })
             .while_some()
             .flatten()
             .collect::<Vec<_>>(),
-        DeleteItemType::HardlinkingFiles(ref items) => items
+        DeleteItemType::HardlinkingFiles(ref items) | DeleteItemType::ReflinkingFiles(ref items) | DeleteItemType::SymlinkingFiles(ref items) => items
             .into_par_iter()
             .map(|(original, files)| {
                 if check_if_stop_received(stop_flag) {
@@ -251,10 +428,22 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
                 let res = files
                     .iter()
                     .map(|file| {
-                        let err = match make_hard_link(original.get_path(), file.get_path()) {
+                        let link_type = link_type.expect("Linking items always have link type");
+                        let journal_entry = match start_in_journal(link_type.journal_action(), file, Some(original.get_path())) {
+                            Ok(journal_entry) => journal_entry,
+                            Err(err) => return (file, Some(original.get_path()), Some(err)),
+                        };
+                        let result = match link_type {
+                            LinkType::Hard => make_hard_link(original.get_path(), file.get_path()),
+                            LinkType::Reflink => make_reflink(original.get_path(), file.get_path()),
+                            LinkType::Symlink => make_file_symlink(symlink_target(original.get_path(), file.get_path(), relative_symlinks), file.get_path()),
+                        };
+                        finish_in_journal(journal_entry, result.is_ok());
+                        let err = match result {
                             Ok(()) => None,
                             Err(err) => Some(format!(
-                                "Failed to hardlink \"{}\" to \"{}\": {err}",
+                                "Failed to {} \"{}\" to \"{}\": {err}",
+                                link_type.name(),
                                 file.get_path().to_string_lossy(),
                                 original.get_path().to_string_lossy(),
                             )),
@@ -270,7 +459,10 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
             .collect::<Vec<_>>(),
     };
 
-    let mut delete_result = DeleteResult::default();
+    let mut delete_result = DeleteResult {
+        errors: journal_errors.into_inner().unwrap_or_else(std::sync::PoisonError::into_inner),
+        ..Default::default()
+    };
 
     for (file_entry, maybe_original, delete_err) in res {
         if let Some(err) = delete_err {
@@ -278,33 +470,45 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
             delete_result.failed_to_delete_files += 1;
         } else {
             if dry_run {
-                if is_hardlinking {
+                if let Some(link_type) = link_type {
                     let original = maybe_original.expect("Should be defined");
                     delete_result.infos.push(format!(
-                        "Would hardlink: \"{}\" to \"{}\"",
+                        "Would {}: \"{}\" to \"{}\"",
+                        link_type.name(),
                         file_entry.get_path().to_string_lossy(),
                         original.to_string_lossy()
                     ));
+                } else if use_quarantine {
+                    delete_result
+                        .infos
+                        .push(format!("Would move to quarantine: \"{}\"", file_entry.get_path().to_string_lossy()));
                 } else {
                     delete_result.infos.push(format!("Would delete: \"{}\"", file_entry.get_path().to_string_lossy()));
                 }
             }
-            if is_hardlinking {
-                delete_result.hardlinked_files += 1;
-            } else {
-                delete_result.deleted_files += 1;
+            match link_type {
+                Some(LinkType::Hard) => delete_result.hardlinked_files += 1,
+                Some(LinkType::Reflink) => delete_result.reflinked_files += 1,
+                Some(LinkType::Symlink) => delete_result.symlinked_files += 1,
+                None => delete_result.deleted_files += 1,
             }
             delete_result.gained_bytes += file_entry.get_size();
         }
     }
 
     if !dry_run {
-        if is_hardlinking {
+        if let Some(link_type) = link_type {
+            let linked_files = match link_type {
+                LinkType::Hard => delete_result.hardlinked_files,
+                LinkType::Reflink => delete_result.reflinked_files,
+                LinkType::Symlink => delete_result.symlinked_files,
+            };
             info!(
-                "{} items hardlinked, {} gained, {} failed to hardlink",
-                delete_result.hardlinked_files,
+                "{linked_files} items replaced with {}, {} gained, {} failed to {}",
+                link_type.name(),
                 format_size(delete_result.gained_bytes, BINARY),
-                delete_result.failed_to_delete_files
+                delete_result.failed_to_delete_files,
+                link_type.name()
             );
         } else {
             info!(
@@ -319,6 +523,18 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
     delete_result
 }
 
+// Relative target is calculated from folder containing symlink, so whole tree may be moved without breaking links
+// Falls back to absolute path when relative one cannot be created(e.g. on different Windows drives)
+fn symlink_target(original: &Path, symlink: &Path, relative: bool) -> PathBuf {
+    if relative
+        && let Some(symlink_dir) = symlink.parent()
+        && let Some(relative) = get_relative_path(symlink_dir, original)
+    {
+        return relative;
+    }
+    original.to_path_buf()
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -365,6 +581,8 @@ mod tests {
         let delete_result = DeleteResult {
             deleted_files: 5,
             hardlinked_files: 0,
+            reflinked_files: 0,
+            symlinked_files: 0,
             gained_bytes: 1024,
             failed_to_delete_files: 2,
             errors: vec!["Error 1".to_string(), "Error 2".to_string()],
@@ -483,6 +701,199 @@ mod tests {
         assert!(!file2.exists(), "File 2 should be deleted");
     }
 
+    #[test]
+    fn test_delete_elements_records_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        let file1 = temp_dir.path().join("file1.txt");
+        let file2 = temp_dir.path().join("file2.txt");
+        let missing = temp_dir.path().join("missing.txt");
+        fs::write(&file1, "test content 1").unwrap();
+        fs::write(&file2, "test content 1").unwrap();
+        let journal_path = temp_dir.path().join("journal.jsonl");
+
+        let mut tool = MockTool::new();
+        tool.set_journal_path(Some(journal_path.clone()));
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let files = vec![
+            FileEntry {
+                path: file1.clone(),
+                size: 14,
+                modified_date: 1,
+            },
+            FileEntry {
+                path: missing,
+                size: 14,
+                modified_date: 1,
+            },
+        ];
+        let delete_result = tool.delete_elements(&stop_flag, None, DeleteItemType::DeletingFiles(files));
+        assert_eq!(delete_result.deleted_files, 1);
+
+        let original = FileEntry {
+            path: temp_dir.path().join("original.txt"),
+            size: 14,
+            modified_date: 1,
+        };
+        fs::write(&original.path, "test content 1").unwrap();
+        let linked = FileEntry {
+            path: file2.clone(),
+            size: 14,
+            modified_date: 2,
+        };
+        let hardlink_result = tool.delete_elements(&stop_flag, None, DeleteItemType::HardlinkingFiles(vec![(original.clone(), vec![linked])]));
+        assert_eq!(hardlink_result.hardlinked_files, 1);
+
+        // Only successful actions are stored
+        let entries = crate::common::journal::load_journal(&journal_path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, JournalAction::Delete);
+        assert_eq!(entries[0].path, file1);
+        assert_eq!(entries[1].action, JournalAction::HardLink);
+        assert_eq!(entries[1].path, file2);
+        assert_eq!(entries[1].destination, Some(original.path));
+    }
+
+    #[test]
+    fn test_delete_advanced_elements_reflink() {
+        let temp_dir = TempDir::new().unwrap();
+        let file1 = temp_dir.path().join("file1.txt");
+        let file2 = temp_dir.path().join("file2.txt");
+        fs::write(&file1, "same").unwrap();
+        fs::write(&file2, "same").unwrap();
+
+        let files_group = vec![vec![
+            FileEntry {
+                path: file1.clone(),
+                size: 4,
+                modified_date: 1,
+            },
+            FileEntry {
+                path: file2.clone(),
+                size: 4,
+                modified_date: 2,
+            },
+        ]];
+
+        let mut tool = MockTool::new();
+        tool.common_data.delete_method = DeleteMethod::Reflink;
+        tool.common_data.dry_run = true;
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let status = tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, files_group.clone());
+        assert_eq!(status, WorkContinueStatus::Continue);
+        assert_eq!(tool.common_data.text_messages.messages.len(), 1);
+        assert!(tool.common_data.text_messages.messages[0].starts_with("Would reflink"));
+
+        // Without reflink support in temp dir, file must stay untouched and error should be reported
+        tool.common_data.dry_run = false;
+        tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, files_group);
+        assert_eq!(fs::read_to_string(&file2).unwrap(), "same");
+        assert!(file1.exists());
+        if !tool.common_data.text_messages.errors.is_empty() {
+            assert!(tool.common_data.text_messages.errors[0].starts_with("Failed to reflink"));
+        }
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_delete_advanced_elements_symlink() {
+        let temp_dir = TempDir::new().unwrap();
+        let sub_dir = temp_dir.path().join("sub");
+        fs::create_dir(&sub_dir).unwrap();
+        let kept = temp_dir.path().join("kept.txt");
+        let absolute = temp_dir.path().join("absolute.txt");
+        let relative = sub_dir.join("relative.txt");
+        for file in [&kept, &absolute, &relative] {
+            fs::write(file, "same").unwrap();
+        }
+        let entry = |path: &PathBuf, modified_date| FileEntry {
+            path: path.clone(),
+            size: 4,
+            modified_date,
+        };
+
+        let mut tool = MockTool::new();
+        tool.common_data.delete_method = DeleteMethod::Symlink;
+        tool.common_data.dry_run = true;
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, vec![vec![entry(&kept, 1), entry(&absolute, 2)]]);
+        assert!(tool.common_data.text_messages.messages[0].starts_with("Would symlink"));
+        assert!(!fs::symlink_metadata(&absolute).unwrap().file_type().is_symlink());
+
+        tool.common_data.dry_run = false;
+        tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, vec![vec![entry(&kept, 1), entry(&absolute, 2)]]);
+        tool.set_relative_symlinks(true);
+        tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, vec![vec![entry(&kept, 1), entry(&relative, 2)]]);
+
+        assert!(tool.common_data.text_messages.errors.is_empty(), "{:?}", tool.common_data.text_messages.errors);
+        assert_eq!(fs::read_link(&absolute).unwrap(), kept);
+        assert_eq!(fs::read_link(&relative).unwrap(), PathBuf::from("../kept.txt"));
+        assert_eq!(fs::read_to_string(&relative).unwrap(), "same");
+        assert!(!fs::symlink_metadata(&kept).unwrap().file_type().is_symlink());
+    }
+
+    #[test]
+    fn test_delete_elements_to_quarantine() {
+        let temp_dir = TempDir::new().unwrap();
+        let file1 = temp_dir.path().join("data").join("file1.txt");
+        let empty_folder = temp_dir.path().join("data").join("empty");
+        let quarantine_path = temp_dir.path().join("quarantine");
+        fs::create_dir_all(&empty_folder).unwrap();
+        fs::write(&file1, "test content").unwrap();
+
+        let mut tool = MockTool::new();
+        tool.set_quarantine_path(Some(quarantine_path.clone()));
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let file_entry = FileEntry {
+            path: file1.clone(),
+            size: 12,
+            modified_date: 1,
+        };
+        let delete_result = tool.delete_elements(&stop_flag, None, DeleteItemType::DeletingFiles(vec![file_entry]));
+        assert_eq!(delete_result.deleted_files, 1, "{:?}", delete_result.errors);
+
+        let folder_entry = FileEntry {
+            path: empty_folder.clone(),
+            size: 0,
+            modified_date: 1,
+        };
+        let delete_result = tool.delete_elements(&stop_flag, None, DeleteItemType::DeletingFolders(vec![folder_entry]));
+        assert_eq!(delete_result.deleted_files, 1, "{:?}", delete_result.errors);
+
+        assert!(!file1.exists());
+        assert!(!empty_folder.exists());
+        assert!(crate::common::quarantine::mirrored_path(&quarantine_path, &empty_folder).is_dir());
+
+        let restore_result = crate::common::quarantine::restore_quarantine(&quarantine_path, false);
+        assert_eq!(restore_result.restored_entries, 2, "{:?}", restore_result.messages);
+        assert_eq!(fs::read_to_string(&file1).unwrap(), "test content");
+        assert!(empty_folder.is_dir());
+    }
+
+    #[test]
+    fn test_delete_elements_journal_not_used_in_dry_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let file1 = temp_dir.path().join("file1.txt");
+        fs::write(&file1, "test content").unwrap();
+        let journal_path = temp_dir.path().join("journal.jsonl");
+
+        let mut tool = MockTool::new();
+        tool.set_journal_path(Some(journal_path.clone()));
+        tool.common_data.dry_run = true;
+
+        let files = vec![FileEntry {
+            path: file1,
+            size: 12,
+            modified_date: 1,
+        }];
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let delete_result = tool.delete_elements(&stop_flag, None, DeleteItemType::DeletingFiles(files));
+
+        assert_eq!(delete_result.deleted_files, 1);
+        assert!(!journal_path.exists(), "Journal should not be created in dry run");
+    }
+
     #[test]
     fn test_delete_elements_with_stop_flag() {
         let temp_dir = TempDir::new().unwrap();
@@ -654,6 +1065,98 @@ mod tests {
         assert!(!file3.exists(), "Newest file should be deleted");
     }
 
+    #[test]
+    fn test_delete_advanced_elements_preferred_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let originals = temp_dir.path().join("originals");
+        let backup = temp_dir.path().join("backup");
+        fs::create_dir(&originals).unwrap();
+        fs::create_dir(&backup).unwrap();
+        let file1 = temp_dir.path().join("file1.txt");
+        let file2 = backup.join("file2.txt");
+        let file3 = originals.join("file3.txt");
+        let file4 = backup.join("file4.txt");
+
+        let entry = |path: &PathBuf, modified_date: u64| FileEntry {
+            path: path.clone(),
+            size: 1,
+            modified_date,
+        };
+        let files_group = vec![vec![entry(&file1, 1), entry(&file2, 2), entry(&file3, 3), entry(&file4, 4)]];
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        for delete_method in [DeleteMethod::AllExceptNewest, DeleteMethod::AllExceptOldest] {
+            for file in [&file1, &file2, &file3, &file4] {
+                fs::write(file, "a").unwrap();
+            }
+            let mut tool = MockTool::new();
+            tool.common_data.delete_method = delete_method;
+            tool.set_preferred_paths(vec![originals.to_string_lossy().to_string(), backup.to_string_lossy().to_string()]);
+            tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, files_group.clone());
+
+            assert!(file3.exists(), "File from the most preferred path should be kept with {delete_method:?}");
+            assert!(!file1.exists() && !file2.exists() && !file4.exists());
+        }
+
+        // Without originals, date breaks the tie between files in backup folder
+        let files_group = vec![vec![entry(&file1, 1), entry(&file2, 2), entry(&file4, 4)]];
+        for (delete_method, deleted, kept) in [(DeleteMethod::OneOldest, &file1, &file2), (DeleteMethod::OneNewest, &file1, &file4)] {
+            for file in [&file1, &file2, &file4] {
+                fs::write(file, "a").unwrap();
+            }
+            let mut tool = MockTool::new();
+            tool.common_data.delete_method = delete_method;
+            tool.set_preferred_paths(vec![backup.to_string_lossy().to_string()]);
+            tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, files_group.clone());
+
+            assert!(!deleted.exists(), "File outside preferred path should be deleted with {delete_method:?}");
+            assert!(kept.exists());
+        }
+    }
+
+    #[test]
+    fn test_delete_advanced_elements_rule() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = ["raw_a.txt", "a.txt", "b.txt"].map(|name| temp_dir.path().join(name));
+        let files_group = vec![
+            files
+                .iter()
+                .map(|path| FileEntry {
+                    path: path.clone(),
+                    size: 1,
+                    modified_date: 1,
+                })
+                .collect::<Vec<_>>(),
+        ];
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let mut tool = MockTool::new();
+        tool.common_data.delete_method = DeleteMethod::Rule;
+        tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, files_group.clone());
+        assert!(tool.common_data.text_messages.errors[0].contains("Selection rule is required"));
+
+        for (rule, use_reference_folders, expected_existing) in [
+            ("keep where name ~ \"raw_*\"", false, [true, false, false]),
+            // Whole group would be deleted, so nothing is removed
+            ("keep where name == \"c.txt\"", false, [true, true, true]),
+            // Reference file is never deleted
+            ("delete where size == 1", true, [true, false, false]),
+        ] {
+            for file in &files {
+                fs::write(file, "a").unwrap();
+            }
+            let mut tool = MockTool::new();
+            tool.common_data.delete_method = DeleteMethod::Rule;
+            tool.common_data.use_reference_folders = use_reference_folders;
+            tool.set_selection_rule(Some(SelectionRule::parse(rule).unwrap()));
+            let status = tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, files_group.clone());
+
+            assert_eq!(status, WorkContinueStatus::Continue);
+            assert!(tool.common_data.text_messages.errors.is_empty());
+            assert_eq!(files.iter().map(|e| e.exists()).collect::<Vec<_>>(), expected_existing, "Rule {rule}");
+        }
+    }
+
     #[test]
     fn test_delete_advanced_elements_one_oldest() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::fs::DirEntry;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bincode::Options;
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::common::config_cache_path::open_cache_folder;
+use crate::common::dir_traversal::common_get_entry_data;
+use crate::flc;
+
+const DIR_SNAPSHOT_FILE: &str = "dir_snapshot_v2.bin";
+const DIR_SNAPSHOT_TMP_FILE: &str = "dir_snapshot_v2.tmp";
+const MEMORY_LIMIT: u64 = 8 * 1024 * 1024 * 1024;
+// Folders modified so recently may still change within the same timestamp, so they are not saved
+const MINIMAL_SNAPSHOT_AGE_SECONDS: u64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum SnapshotEntryType {
+    File,
+    Dir,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SnapshotEntry {
+    pub(crate) name: String,
+    pub(crate) entry_type: SnapshotEntryType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SnapshotDir {
+    // Modification time of folder in nanoseconds, changes when any entry is added, removed or renamed
+    modified: u128,
+    pub(crate) entries: Vec<SnapshotEntry>,
+}
+
+/// Persistent list of entries of already scanned folders.
+///
+/// Folder with unchanged modification time is taken from snapshot instead of reading it again.
+/// Modifying content of file doesn't change modification time of folder, so metadata of files from snapshot is still read every time.
+/// Scan of unchanged tree still reads metadata of every folder and file, only reading content of folders is skipped,
+/// which helps mostly on network and FUSE filesystems, where listing folders is slow.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct DirSnapshot {
+    dirs: HashMap<String, SnapshotDir>,
+}
+
+pub(crate) fn folder_snapshot_key(folder: &Path) -> Option<(String, u128)> {
+    let key = folder.to_str()?.to_string();
+    let modified = fs::metadata(folder).ok()?.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    Some((key, modified))
+}
+
+impl SnapshotDir {
+    // Returns None, when folder cannot be safely saved in snapshot - e.g. contains non UTF-8 names
+    pub(crate) fn from_read_dir(current_folder: &Path, modified: u128, read_dir: &[Result<DirEntry, std::io::Error>], warnings: &mut Vec<String>) -> Option<Self> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        if now.saturating_sub(modified) < u128::from(MINIMAL_SNAPSHOT_AGE_SECONDS) * 1_000_000_000 {
+            return None;
+        }
+
+        let mut entries = Vec::with_capacity(read_dir.len());
+        for entry in read_dir {
+            let Some(entry_data) = common_get_entry_data(entry, warnings, current_folder) else {
+                continue;
+            };
+            let Ok(file_type) = entry_data.file_type() else { continue };
+            let name = entry_data.file_name().into_string().ok()?;
+
+            // Metadata of files is read later, when entries are processed
+            let entry_type = if file_type.is_file() {
+                SnapshotEntryType::File
+            } else if file_type.is_dir() {
+                SnapshotEntryType::Dir
+            } else {
+                SnapshotEntryType::Other
+            };
+            entries.push(SnapshotEntry { name, entry_type });
+        }
+        Some(Self { modified, entries })
+    }
+}
+
+impl DirSnapshot {
+    pub(crate) fn load(warnings: &mut Vec<String>) -> Self {
+        let Some(((Some(file_handler), snapshot_file), _)) = open_cache_folder(DIR_SNAPSHOT_FILE, false, false, warnings) else {
+            return Self::default();
+        };
+        let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+        match options.deserialize_from::<_, Self>(BufReader::new(file_handler)) {
+            Ok(snapshot) => {
+                debug!("Loaded directory snapshot with {} folders", snapshot.dirs.len());
+                snapshot
+            }
+            Err(e) => {
+                warnings.push(flc!("core_failed_to_load_data_from_cache", file = snapshot_file.to_string_lossy(), reason = e.to_string()));
+                Self::default()
+            }
+        }
+    }
+
+    // Snapshot is written to temporary file first, so interrupted save doesn't leave broken snapshot
+    pub(crate) fn save(&self, warnings: &mut Vec<String>) {
+        let Some(((Some(file_handler), tmp_file), _)) = open_cache_folder(DIR_SNAPSHOT_TMP_FILE, true, false, warnings) else {
+            return;
+        };
+        let snapshot_file = tmp_file.with_file_name(DIR_SNAPSHOT_FILE);
+        let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+        let mut writer = BufWriter::new(file_handler);
+        let result = options
+            .serialize_into(&mut writer, self)
+            .map_err(|e| e.to_string())
+            .and_then(|()| writer.flush().and_then(|()| fs::rename(&tmp_file, &snapshot_file)).map_err(|e: io::Error| e.to_string()));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&tmp_file);
+            warnings.push(flc!("core_failed_to_write_data_to_cache", file = snapshot_file.to_string_lossy(), reason = e));
+            return;
+        }
+        debug!("Saved directory snapshot with {} folders", self.dirs.len());
+    }
+
+    pub(crate) fn get_entries(&self, key: &str, modified: u128) -> Option<&[SnapshotEntry]> {
+        self.dirs.get(key).filter(|dir| dir.modified == modified).map(|dir| dir.entries.as_slice())
+    }
+
+    /// Replaces content of scanned root folders with visited and updated folders, so removed or excluded folders don't stay in snapshot.
+    pub(crate) fn update(&mut self, root_dirs: &[PathBuf], visited: &HashSet<String>, updated: Vec<(String, SnapshotDir)>) {
+        self.dirs
+            .retain(|key, _| visited.contains(key) || !root_dirs.iter().any(|root_dir| Path::new(key).starts_with(root_dir)));
+        self.dirs.extend(updated);
+    }
+
+    /// Removes folders containing changed paths, returns number of removed folders.
+    pub(crate) fn remove_folders_of_paths(&mut self, changed_paths: &[PathBuf]) -> usize {
+        let before = self.dirs.len();
+        for path in changed_paths {
+            for folder in [Some(path.as_path()), path.parent()].into_iter().flatten() {
+                if let Some(key) = folder.to_str() {
+                    self.dirs.remove(key);
+                }
+            }
+        }
+        before - self.dirs.len()
+    }
+}
+
+/// Forgets saved entries of folders with changed files, so they are read again during next scan.
+///
+/// Needed when file is modified in place, because this doesn't change modification time of its folder.
+pub fn invalidate_dir_snapshot(changed_paths: &[PathBuf]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if changed_paths.is_empty() {
+        return warnings;
+    }
+    let mut snapshot = DirSnapshot::load(&mut warnings);
+    if snapshot.remove_folders_of_paths(changed_paths) > 0 {
+        snapshot.save(&mut warnings);
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::dir_traversal::common_read_dir;
+
+    #[test]
+    fn test_snapshot_dir_and_update() {
+        let dir = tempfile::tempdir().expect("Cannot create temp dir");
+        fs::create_dir(dir.path().join("sub")).expect("Cannot create dir");
+        fs::write(dir.path().join("file.txt"), "abc").expect("Cannot write file");
+        let mut warnings = Vec::new();
+        let read_dir = common_read_dir(dir.path(), &mut warnings).expect("Cannot read dir");
+
+        let (key, modified) = folder_snapshot_key(dir.path()).expect("Cannot get key");
+        // Just modified folder could still change, so it is not saved
+        assert!(SnapshotDir::from_read_dir(dir.path(), modified, &read_dir, &mut warnings).is_none());
+
+        let old_modified = modified - 60 * 1_000_000_000;
+        let mut snapshot_dir = SnapshotDir::from_read_dir(dir.path(), old_modified, &read_dir, &mut warnings).expect("Cannot create snapshot");
+        snapshot_dir.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(snapshot_dir.entries.len(), 2);
+        assert_eq!(
+            (snapshot_dir.entries[0].name.as_str(), snapshot_dir.entries[0].entry_type),
+            ("file.txt", SnapshotEntryType::File)
+        );
+        assert_eq!(snapshot_dir.entries[1].entry_type, SnapshotEntryType::Dir);
+        assert!(warnings.is_empty());
+
+        let mut snapshot = DirSnapshot::default();
+        snapshot.dirs.insert("/other/folder".to_string(), snapshot_dir.clone());
+        snapshot.dirs.insert(format!("{key}/removed"), snapshot_dir.clone());
+        snapshot.update(&[dir.path().to_path_buf()], &HashSet::new(), vec![(key.clone(), snapshot_dir)]);
+
+        assert!(snapshot.get_entries(&key, old_modified).is_some());
+        assert!(snapshot.get_entries(&key, old_modified + 1).is_none());
+        assert!(snapshot.get_entries(&format!("{key}/removed"), old_modified).is_none());
+        assert!(snapshot.get_entries("/other/folder", old_modified).is_some());
+
+        assert_eq!(snapshot.remove_folders_of_paths(&[dir.path().join("file.txt")]), 1);
+        assert!(snapshot.get_entries(&key, old_modified).is_none());
+        assert!(snapshot.get_entries("/other/folder", old_modified).is_some());
+    }
+}
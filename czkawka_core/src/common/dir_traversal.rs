@@ -1,4 +1,5 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsStr;
 use std::fs;
 use std::fs::{DirEntry, FileType, Metadata};
 #[cfg(target_family = "unix")]
@@ -13,9 +14,11 @@ use fun_time::fun_time;
 use log::debug;
 use rayon::prelude::*;
 
+use crate::common::date_filter::{DateFilter, timestamp};
+use crate::common::dir_snapshot::{DirSnapshot, SnapshotDir, SnapshotEntry, SnapshotEntryType, folder_snapshot_key};
 use crate::common::directories::Directories;
 use crate::common::extensions::Extensions;
-use crate::common::items::ExcludedItems;
+use crate::common::items::{ExcludedItems, GITIGNORE_FILE_NAME, IGNORE_FILE_NAME};
 use crate::common::model::{CheckingMethod, FileEntry, ToolType};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
@@ -28,6 +31,13 @@ pub enum Collect {
     Files,
 }
 
+/// Files found by single traversal, which are later filtered separately by every tool.
+#[derive(Debug, Default)]
+pub(crate) struct CollectedFiles {
+    pub(crate) files: Vec<FileEntry>,
+    pub(crate) warnings: Vec<String>,
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 enum EntryType {
     File,
@@ -44,13 +54,17 @@ pub struct DirTraversalBuilder<'b, F> {
     progress_sender: Option<&'b Sender<ProgressData>>,
     minimal_file_size: Option<u64>,
     maximal_file_size: Option<u64>,
+    date_filter: DateFilter,
     collect: Collect,
     recursive_search: bool,
     directories: Option<Directories>,
     excluded_items: Option<ExcludedItems>,
+    respect_gitignore: bool,
+    use_dir_snapshot: bool,
     extensions: Option<Extensions>,
     tool_type: ToolType,
     checking_method: CheckingMethod,
+    collected_files: Option<Arc<CollectedFiles>>,
 }
 
 #[derive(Debug)]
@@ -63,12 +77,16 @@ pub struct DirTraversal<'b, F> {
     recursive_search: bool,
     directories: Directories,
     excluded_items: ExcludedItems,
+    respect_gitignore: bool,
+    use_dir_snapshot: bool,
     extensions: Extensions,
     minimal_file_size: u64,
     maximal_file_size: u64,
+    date_filter: DateFilter,
     tool_type: ToolType,
     checking_method: CheckingMethod,
     collect: Collect,
+    collected_files: Option<Arc<CollectedFiles>>,
 }
 
 impl Default for DirTraversalBuilder<'_, ()> {
@@ -87,13 +105,17 @@ impl DirTraversalBuilder<'_, ()> {
             progress_sender: None,
             minimal_file_size: None,
             maximal_file_size: None,
+            date_filter: DateFilter::default(),
             collect: Collect::Files,
             recursive_search: false,
             directories: None,
             extensions: None,
             excluded_items: None,
+            respect_gitignore: false,
+            use_dir_snapshot: false,
             tool_type: ToolType::None,
             checking_method: CheckingMethod::None,
+            collected_files: None,
         }
     }
 }
@@ -104,11 +126,15 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
         self.root_files = common_tool_data.directories.included_files.clone();
         self.extensions = Some(common_tool_data.extensions.clone());
         self.excluded_items = Some(common_tool_data.excluded_items.clone());
+        self.respect_gitignore = common_tool_data.respect_gitignore;
+        self.use_dir_snapshot = common_tool_data.use_dir_snapshot;
         self.recursive_search = common_tool_data.recursive_search;
         self.minimal_file_size = Some(common_tool_data.minimal_file_size);
         self.maximal_file_size = Some(common_tool_data.maximal_file_size);
+        self.date_filter = common_tool_data.date_filter;
         self.tool_type = common_tool_data.tool_type;
         self.directories = Some(common_tool_data.directories.clone());
+        self.collected_files = common_tool_data.collected_files.clone();
         self
     }
 
@@ -132,6 +158,11 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
         self
     }
 
+    pub(crate) fn extensions(mut self, extensions: Extensions) -> Self {
+        self.extensions = Some(extensions);
+        self
+    }
+
     pub(crate) fn collect(mut self, collect: Collect) -> Self {
         self.collect = collect;
         self
@@ -157,12 +188,16 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
             directories: self.directories,
             extensions: self.extensions,
             excluded_items: self.excluded_items,
+            respect_gitignore: self.respect_gitignore,
+            use_dir_snapshot: self.use_dir_snapshot,
             recursive_search: self.recursive_search,
             maximal_file_size: self.maximal_file_size,
             minimal_file_size: self.minimal_file_size,
+            date_filter: self.date_filter,
             collect: self.collect,
             tool_type: self.tool_type,
             checking_method: self.checking_method,
+            collected_files: self.collected_files,
         }
     }
 
@@ -175,13 +210,17 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
             progress_sender: self.progress_sender,
             minimal_file_size: self.minimal_file_size.unwrap_or(0),
             maximal_file_size: self.maximal_file_size.unwrap_or(u64::MAX),
+            date_filter: self.date_filter,
             collect: self.collect,
             directories: self.directories.expect("could not build"),
             excluded_items: self.excluded_items.expect("could not build"),
+            respect_gitignore: self.respect_gitignore,
+            use_dir_snapshot: self.use_dir_snapshot,
             extensions: self.extensions.unwrap_or_default(),
             recursive_search: self.recursive_search,
             tool_type: self.tool_type,
             checking_method: self.checking_method,
+            collected_files: self.collected_files,
         }
     }
 }
@@ -215,11 +254,16 @@ where
     pub(crate) fn run(self) -> DirTraversalResult<T> {
         assert_ne!(self.tool_type, ToolType::None, "Tool type cannot be None");
 
+        if let Some(collected_files) = &self.collected_files
+            && self.collect == Collect::Files
+        {
+            return self.group_collected_files(collected_files);
+        }
+
         let mut all_warnings = Vec::new();
         let mut grouped_file_entries: BTreeMap<T, Vec<FileEntry>> = BTreeMap::new();
 
         // Add root folders and files for finding
-        let mut folders_to_check: Vec<PathBuf> = self.root_dirs.clone();
         let mut files_to_check: Vec<PathBuf> = self.root_files.clone();
 
         let collect_stage = if self.tool_type == ToolType::EmptyFolders {
@@ -233,10 +277,13 @@ where
             collect,
             directories,
             excluded_items,
+            respect_gitignore,
+            use_dir_snapshot,
             extensions,
             recursive_search,
             minimal_file_size,
             maximal_file_size,
+            date_filter,
             stop_flag,
             ..
         } = self;
@@ -261,6 +308,7 @@ where
                         &directories,
                         minimal_file_size,
                         maximal_file_size,
+                        &date_filter,
                     );
                 }
                 (EntryType::File, Collect::InvalidSymlinks) => {
@@ -281,6 +329,14 @@ where
             grouped_file_entries.entry(key).or_default().push(fe);
         }
 
+        // Every folder carries excluded items extended by ignore files found in its parent folders
+        let excluded_items = Arc::new(excluded_items);
+        let mut folders_to_check: Vec<(PathBuf, Arc<ExcludedItems>)> = self.root_dirs.iter().map(|dir| (dir.clone(), excluded_items.clone())).collect();
+
+        let mut dir_snapshot = (use_dir_snapshot && collect == Collect::Files).then(|| DirSnapshot::load(&mut all_warnings));
+        let mut visited_snapshot_dirs = HashSet::new();
+        let mut updated_snapshot_dirs = Vec::new();
+
         // Folder traversal
         while !folders_to_check.is_empty() {
             if check_if_stop_received(&stop_flag) {
@@ -291,15 +347,70 @@ where
             let segments: Vec<_> = folders_to_check
                 .into_par_iter()
                 .with_max_len(2) // Avoiding checking too many folders in batch
-                .map(|current_folder| {
+                .map(|(current_folder, excluded_items)| {
                     let mut dir_result = Vec::new();
                     let mut warnings = Vec::new();
                     let mut fe_result = Vec::new();
 
+                    // Unchanged folder is taken from snapshot, otherwise it is read and its new state is saved
+                    let snapshot_key = dir_snapshot.as_ref().and_then(|_| folder_snapshot_key(&current_folder));
+                    if let Some(dir_snapshot) = &dir_snapshot
+                        && let Some((key, modified)) = &snapshot_key
+                        && let Some(entries) = dir_snapshot.get_entries(key, *modified)
+                    {
+                        let (excluded_items, counter) = process_snapshot_folder(
+                            &current_folder,
+                            entries,
+                            excluded_items,
+                            respect_gitignore,
+                            &mut dir_result,
+                            &mut fe_result,
+                            &mut warnings,
+                            recursive_search,
+                            &extensions,
+                            &directories,
+                            minimal_file_size,
+                            maximal_file_size,
+                            &date_filter,
+                        );
+                        progress_handler.increase_items(counter);
+                        return Some((dir_result, warnings, fe_result, excluded_items, Some((key.clone(), None))));
+                    }
+
                     let Some(read_dir) = common_read_dir(&current_folder, &mut warnings) else {
-                        return Some((dir_result, warnings, fe_result));
+                        return Some((dir_result, warnings, fe_result, excluded_items, None));
                     };
 
+                    if let Some((key, modified)) = snapshot_key
+                        && let Some(snapshot_dir) = SnapshotDir::from_read_dir(&current_folder, modified, &read_dir, &mut warnings)
+                    {
+                        let (excluded_items, counter) = process_snapshot_folder(
+                            &current_folder,
+                            &snapshot_dir.entries,
+                            excluded_items,
+                            respect_gitignore,
+                            &mut dir_result,
+                            &mut fe_result,
+                            &mut warnings,
+                            recursive_search,
+                            &extensions,
+                            &directories,
+                            minimal_file_size,
+                            maximal_file_size,
+                            &date_filter,
+                        );
+                        progress_handler.increase_items(counter);
+                        return Some((dir_result, warnings, fe_result, excluded_items, Some((key, Some(snapshot_dir)))));
+                    }
+
+                    let excluded_items = apply_ignore_files(
+                        &current_folder,
+                        |name| read_dir.iter().flatten().any(|entry| entry.file_name() == name),
+                        excluded_items,
+                        respect_gitignore,
+                        &mut warnings,
+                    );
+
                     let mut counter = 0;
                     // Check every sub folder/file/link etc.
                     for entry in read_dir {
@@ -314,7 +425,7 @@ where
 
                         match (entry_type(file_type), collect) {
                             (EntryType::Dir, Collect::Files | Collect::InvalidSymlinks) => {
-                                process_dir_in_file_symlink_mode(recursive_search, entry_data, &directories, &mut dir_result, &mut warnings, &excluded_items);
+                                process_dir_in_file_symlink_mode(recursive_search, entry_data.path(), &directories, &mut dir_result, &mut warnings, &excluded_items);
                             }
                             (EntryType::File, Collect::Files) => {
                                 counter += 1;
@@ -327,6 +438,7 @@ where
                                     &excluded_items,
                                     minimal_file_size,
                                     maximal_file_size,
+                                    &date_filter,
                                 );
                             }
                             (EntryType::File, Collect::InvalidSymlinks) => {
@@ -345,17 +457,23 @@ where
                         // Increase counter in batch, because usually it may be slow to add multiple times atomic value
                         progress_handler.increase_items(counter);
                     }
-                    Some((dir_result, warnings, fe_result))
+                    Some((dir_result, warnings, fe_result, excluded_items, None))
                 })
                 .while_some()
                 .collect();
 
-            let required_size = segments.iter().map(|(segment, _, _)| segment.len()).sum::<usize>();
+            let required_size = segments.iter().map(|(segment, _, _, _, _)| segment.len()).sum::<usize>();
             folders_to_check = Vec::with_capacity(required_size);
 
             // Process collected data
-            for (segment, warnings, mut fe_result) in segments {
-                folders_to_check.extend(segment);
+            for (segment, warnings, mut fe_result, excluded_items, snapshot_result) in segments {
+                if let Some((key, snapshot_dir)) = snapshot_result {
+                    if let Some(snapshot_dir) = snapshot_dir {
+                        updated_snapshot_dirs.push((key.clone(), snapshot_dir));
+                    }
+                    visited_snapshot_dirs.insert(key);
+                }
+                folders_to_check.extend(segment.into_iter().map(|dir| (dir, excluded_items.clone())));
                 all_warnings.extend(warnings);
                 fe_result.sort_by_cached_key(|fe| fe.path.to_string_lossy().to_string());
                 for fe in fe_result {
@@ -367,6 +485,16 @@ where
 
         progress_handler.join_thread();
 
+        if let Some(dir_snapshot) = &mut dir_snapshot {
+            debug!(
+                "Directory snapshot - {} folders reused, {} folders updated",
+                visited_snapshot_dirs.len() - updated_snapshot_dirs.len(),
+                updated_snapshot_dirs.len()
+            );
+            dir_snapshot.update(&self.root_dirs, &visited_snapshot_dirs, updated_snapshot_dirs);
+            dir_snapshot.save(&mut all_warnings);
+        }
+
         debug!("Collected {} files", grouped_file_entries.values().map(Vec::len).sum::<usize>());
 
         match collect {
@@ -376,6 +504,179 @@ where
             },
         }
     }
+
+    // Directories, excluded items and date filter were already checked during shared traversal
+    fn group_collected_files(&self, collected_files: &CollectedFiles) -> DirTraversalResult<T> {
+        let mut grouped_file_entries: BTreeMap<T, Vec<FileEntry>> = BTreeMap::new();
+        for fe in &collected_files.files {
+            let Some(file_name) = fe.path.file_name() else { continue };
+            if !self.extensions.check_if_entry_have_valid_extension(file_name) || !(self.minimal_file_size..=self.maximal_file_size).contains(&fe.size) {
+                continue;
+            }
+            grouped_file_entries.entry((self.group_by)(fe)).or_default().push(fe.clone());
+        }
+
+        debug!(
+            "Reused {} of {} collected files",
+            grouped_file_entries.values().map(Vec::len).sum::<usize>(),
+            collected_files.files.len()
+        );
+
+        DirTraversalResult::SuccessFiles {
+            grouped_file_entries,
+            warnings: collected_files.warnings.clone(),
+        }
+    }
+}
+
+// Rules from `.czkawkaignore`(and `.gitignore` if enabled) apply to the folder and all its subfolders
+fn apply_ignore_files(
+    current_folder: &Path,
+    has_entry: impl Fn(&str) -> bool,
+    excluded_items: Arc<ExcludedItems>,
+    respect_gitignore: bool,
+    warnings: &mut Vec<String>,
+) -> Arc<ExcludedItems> {
+    let ignore_file_names: &[&str] = if respect_gitignore {
+        &[GITIGNORE_FILE_NAME, IGNORE_FILE_NAME]
+    } else {
+        &[IGNORE_FILE_NAME]
+    };
+
+    let mut new_excluded_items: Option<ExcludedItems> = None;
+    for ignore_file_name in ignore_file_names {
+        if !has_entry(ignore_file_name) {
+            continue;
+        }
+        let ignore_file = current_folder.join(ignore_file_name);
+        match fs::read_to_string(&ignore_file) {
+            Ok(content) => new_excluded_items
+                .get_or_insert_with(|| (*excluded_items).clone())
+                .add_ignore_file_rules(current_folder, &content),
+            Err(e) => warnings.push(flc!(
+                "core_cannot_read_ignore_file",
+                file = ignore_file.to_string_lossy().to_string(),
+                reason = e.to_string()
+            )),
+        }
+    }
+    new_excluded_items.map_or(excluded_items, Arc::new)
+}
+
+// Works like regular folder processing, but on entries saved in directory snapshot, without reading content of folder
+// Metadata of files is always read, because modifying file in place doesn't change modification time of its folder
+fn process_snapshot_folder(
+    current_folder: &Path,
+    entries: &[SnapshotEntry],
+    excluded_items: Arc<ExcludedItems>,
+    respect_gitignore: bool,
+    dir_result: &mut Vec<PathBuf>,
+    fe_result: &mut Vec<FileEntry>,
+    warnings: &mut Vec<String>,
+    recursive_search: bool,
+    extensions: &Extensions,
+    directories: &Directories,
+    minimal_file_size: u64,
+    maximal_file_size: u64,
+    date_filter: &DateFilter,
+) -> (Arc<ExcludedItems>, usize) {
+    let excluded_items = apply_ignore_files(
+        current_folder,
+        |name| entries.iter().any(|entry| entry.name == name),
+        excluded_items,
+        respect_gitignore,
+        warnings,
+    );
+
+    let mut counter = 0;
+    for entry in entries {
+        match entry.entry_type {
+            SnapshotEntryType::Dir => {
+                process_dir_in_file_symlink_mode(recursive_search, current_folder.join(&entry.name), directories, dir_result, warnings, &excluded_items);
+            }
+            SnapshotEntryType::File => {
+                counter += 1;
+                process_snapshot_file(
+                    current_folder,
+                    entry,
+                    warnings,
+                    fe_result,
+                    extensions,
+                    directories,
+                    &excluded_items,
+                    minimal_file_size,
+                    maximal_file_size,
+                    date_filter,
+                );
+            }
+            SnapshotEntryType::Other => {
+                // nothing to do
+            }
+        }
+    }
+    (excluded_items, counter)
+}
+
+fn process_snapshot_file(
+    current_folder: &Path,
+    entry: &SnapshotEntry,
+    warnings: &mut Vec<String>,
+    fe_result: &mut Vec<FileEntry>,
+    extensions: &Extensions,
+    directories: &Directories,
+    excluded_items: &ExcludedItems,
+    minimal_file_size: u64,
+    maximal_file_size: u64,
+    date_filter: &DateFilter,
+) {
+    if !extensions.check_if_entry_have_valid_extension(OsStr::new(&entry.name)) {
+        return;
+    }
+
+    let current_file_name = current_folder.join(&entry.name);
+    if excluded_items.is_excluded(&current_file_name) {
+        return;
+    }
+
+    if directories.is_excluded_file(&current_file_name) {
+        return;
+    }
+
+    #[cfg(target_family = "unix")]
+    if directories.exclude_other_filesystems() {
+        match directories.is_on_other_filesystems(&current_file_name) {
+            Ok(true) => return,
+            Err(e) => warnings.push(e),
+            _ => (),
+        }
+    }
+
+    // Snapshot contains only names, metadata is read here, so files modified in place are also noticed
+    let metadata = match fs::symlink_metadata(&current_file_name) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            warnings.push(flc!(
+                "core_cannot_read_metadata_file",
+                file = current_file_name.to_string_lossy().to_string(),
+                reason = e.to_string()
+            ));
+            return;
+        }
+    };
+    if !metadata.is_file() {
+        return;
+    }
+    let size = metadata.len();
+    let modified_date = get_modified_time(&metadata, warnings, &current_file_name, false);
+    if !(minimal_file_size..=maximal_file_size).contains(&size) || !date_filter.matches_dates(modified_date, timestamp(metadata.created()), timestamp(metadata.accessed())) {
+        return;
+    }
+
+    fe_result.push(FileEntry {
+        size,
+        modified_date,
+        path: current_file_name,
+    });
 }
 
 fn process_file_in_file_mode(
@@ -387,6 +688,7 @@ fn process_file_in_file_mode(
     excluded_items: &ExcludedItems,
     minimal_file_size: u64,
     maximal_file_size: u64,
+    date_filter: &DateFilter,
 ) {
     if !extensions.check_if_entry_have_valid_extension(&entry_data.file_name()) {
         return;
@@ -418,10 +720,15 @@ fn process_file_in_file_mode(
     };
 
     if (minimal_file_size..=maximal_file_size).contains(&metadata.len()) {
+        let modified_date = get_modified_time(&metadata, warnings, &current_file_name, false);
+        if !date_filter.matches(&metadata, modified_date) {
+            return;
+        }
+
         // Creating new file entry
         let fe: FileEntry = FileEntry {
             size: metadata.len(),
-            modified_date: get_modified_time(&metadata, warnings, &current_file_name, false),
+            modified_date,
             path: current_file_name,
         };
 
@@ -440,6 +747,7 @@ fn process_file_in_file_mode_path_check(
     directories: &Directories,
     minimal_file_size: u64,
     maximal_file_size: u64,
+    date_filter: &DateFilter,
 ) {
     let Some(file_name) = path.file_name() else {
         return;
@@ -460,10 +768,15 @@ fn process_file_in_file_mode_path_check(
     }
 
     if (minimal_file_size..=maximal_file_size).contains(&metadata.len()) {
+        let modified_date = get_modified_time(metadata, warnings, path, false);
+        if !date_filter.matches(metadata, modified_date) {
+            return;
+        }
+
         // Creating new file entry
         let fe: FileEntry = FileEntry {
             size: metadata.len(),
-            modified_date: get_modified_time(metadata, warnings, path, false),
+            modified_date,
             path: path.to_path_buf(),
         };
 
@@ -474,7 +787,7 @@ fn process_file_in_file_mode_path_check(
 #[cfg_attr(target_family = "windows", expect(clippy::needless_pass_by_ref_mut))]
 fn process_dir_in_file_symlink_mode(
     recursive_search: bool,
-    entry_data: &DirEntry,
+    dir_path: PathBuf,
     directories: &Directories,
     dir_result: &mut Vec<PathBuf>,
     warnings: &mut Vec<String>,
@@ -484,7 +797,6 @@ fn process_dir_in_file_symlink_mode(
         return;
     }
 
-    let dir_path = entry_data.path();
     if directories.is_excluded_dir(&dir_path) {
         return;
     }
@@ -687,6 +999,8 @@ mod tests {
     use indexmap::IndexSet;
 
     use super::*;
+    use crate::common::cache::tests::setup_cache_path;
+    use crate::common::date_filter::DateRange;
     use crate::common::tool_data::*;
 
     impl CommonData for CommonToolData {
@@ -943,6 +1257,100 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_traversal_with_ignore_files() -> io::Result<()> {
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        let (global_file, other_file, _other_dir) = create_temp_structure(&dir_path)?;
+        fs::write(dir_path.join(".gitignore"), "other_file/\n")?;
+        fs::write(dir_path.join("other_file").join(IGNORE_FILE_NAME), "*.txt\n!other_file.txt\n")?;
+        fs::write(dir_path.join("other_file").join("ignored.txt"), "ignored")?;
+
+        let collect_paths = |common_data: &CommonToolData| {
+            let mut paths: Vec<_> = run_traversal(common_data).into_iter().map(|e| normalize_path(&e.path)).collect();
+            paths.sort();
+            paths
+        };
+
+        let mut common_data = CommonToolData::new(ToolType::SimilarImages);
+        common_data.directories.set_included_paths([dir_path.clone()].to_vec());
+        common_data.set_minimal_file_size(0);
+        let paths = collect_paths(&common_data);
+        assert_eq!(
+            paths,
+            vec![
+                dir_path.join(".gitignore"),
+                global_file.clone(),
+                dir_path.join("other_file").join(IGNORE_FILE_NAME),
+                other_file
+            ]
+        );
+
+        common_data.respect_gitignore = true;
+        let paths = collect_paths(&common_data);
+        assert_eq!(paths, vec![dir_path.join(".gitignore"), global_file]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_traversal_with_date_filter() -> io::Result<()> {
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        let (global_file, other_file, _other_dir) = create_temp_structure(&dir_path)?;
+        File::options()
+            .write(true)
+            .open(&other_file)?
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000_000))?;
+
+        let mut common_data = CommonToolData::new(ToolType::SimilarImages);
+        common_data.directories.set_included_paths([dir_path].to_vec());
+        common_data.set_minimal_file_size(0);
+
+        common_data.date_filter.modified = DateRange::new(None, Some(1_000_000_000));
+        let paths: Vec<_> = run_traversal(&common_data).into_iter().map(|e| normalize_path(&e.path)).collect();
+        assert_eq!(paths, vec![global_file]);
+
+        common_data.date_filter.modified = DateRange::new(Some(1_000_000_000), None);
+        let paths: Vec<_> = run_traversal(&common_data).into_iter().map(|e| normalize_path(&e.path)).collect();
+        assert_eq!(paths, vec![other_file]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_traversal_with_dir_snapshot() -> io::Result<()> {
+        setup_cache_path();
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        let (global_file, other_file, other_dir) = create_temp_structure(&dir_path)?;
+        // Only folders unchanged for some time are saved in snapshot
+        for folder in [&dir_path, &other_dir] {
+            File::open(folder)?.set_modified(*NOW)?;
+        }
+
+        let mut common_data = CommonToolData::new(ToolType::SimilarImages);
+        common_data.directories.set_included_paths([dir_path].to_vec());
+        common_data.set_minimal_file_size(0);
+        common_data.use_dir_snapshot = true;
+
+        let sizes = |entries: Vec<FileEntry>| entries.into_iter().map(|e| (normalize_path(&e.path), e.size)).collect::<Vec<_>>();
+        assert_eq!(sizes(run_traversal(&common_data)), vec![(global_file.clone(), 11), (other_file.clone(), 10)]);
+        let cache_folder = crate::common::config_cache_path::get_config_cache_path().expect("Cache path is set").cache_folder;
+        assert!(cache_folder.join("dir_snapshot_v2.bin").exists());
+        assert!(!cache_folder.join("dir_snapshot_v2.tmp").exists());
+
+        // Content change doesn't modify folder, so entries are taken from snapshot, but size is read again
+        fs::write(&global_file, b"changed global_file")?;
+        // New file changes modification time of folder, so it is read again
+        let new_file = other_dir.join("new_file.txt");
+        fs::write(&new_file, b"new")?;
+        assert_eq!(sizes(run_traversal(&common_data)), vec![(global_file.clone(), 19), (new_file, 3), (other_file, 10)]);
+
+        common_data.use_dir_snapshot = false;
+        assert_eq!(sizes(run_traversal(&common_data))[0], (global_file, 19));
+        Ok(())
+    }
+
     #[cfg(target_family = "unix")]
     #[test]
     fn test_traversal_group_by_inode() -> io::Result<()> {
@@ -88,6 +88,17 @@ impl Extensions {
         }
     }
 
+    // Extensions accepted by any of filters, so one traversal may collect files for multiple tools
+    pub(crate) fn union(extensions: &[Self]) -> Self {
+        if extensions.is_empty() || extensions.iter().any(|e| e.allowed_extensions_hashset.is_empty()) {
+            return Self::default();
+        }
+        Self {
+            allowed_extensions_hashset: extensions.iter().flat_map(|e| e.allowed_extensions_hashset.iter().cloned()).collect(),
+            excluded_extensions_hashset: IndexSet::new(),
+        }
+    }
+
     // E.g. when using similar videos, user can provide extensions like "mp4,flv", but if user provide "mp4,jpg" then
     // it will be only "mp4" because "jpg" is not valid extension for videos
     fn intersection_allowed_extensions(&mut self, file_extensions: &[&str]) {
@@ -174,6 +185,27 @@ mod tests {
         assert!(msgs.warnings.iter().any(|w| w.contains("bad ext")));
     }
 
+    #[test]
+    fn test_union() {
+        let mut images = Extensions::new();
+        images.set_allowed_extensions(vec!["jpg".to_string(), "png".to_string()]);
+        let mut documents = Extensions::new();
+        documents.set_allowed_extensions(vec!["pdf".to_string(), "png".to_string()]);
+        let mut not_text = Extensions::new();
+        not_text.set_excluded_extensions(vec!["txt".to_string()]);
+
+        let union = Extensions::union(&[images.clone(), documents]);
+        assert_eq!(union.allowed_extensions_hashset.len(), 3);
+        assert!(union.check_if_entry_have_valid_extension(OsStr::new("a.pdf")));
+        assert!(union.check_if_entry_have_valid_extension(OsStr::new("a.JPG")));
+        assert!(!union.check_if_entry_have_valid_extension(OsStr::new("a.txt")));
+
+        // Filter without allowed extensions may accept any file
+        let union = Extensions::union(&[images, not_text]);
+        assert!(union.check_if_entry_have_valid_extension(OsStr::new("a.txt")));
+        assert!(union.check_if_entry_have_valid_extension(OsStr::new("a")));
+    }
+
     #[test]
     fn test_check_if_entry_have_valid_extension() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1,7 +1,10 @@
+use std::fs::File;
 use std::io::Error;
 use std::path::Path;
 use std::{fs, io};
 
+use filetime::FileTime;
+
 use crate::flc;
 
 const MAX_SYMLINK_HARDLINK_ATTEMPTS: u8 = 5;
@@ -250,6 +253,59 @@ pub fn make_file_symlink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::
     }
 }
 
+// Replaces content of dst with copy-on-write clone of src - data blocks are shared, but files stay independent,
+// so unlike hardlinks, editing one copy will not change the other
+// Content is cloned into existing dst, so it keeps its owner, permissions, extended attributes and other hard links,
+// modification time is restored after cloning and dst is left untouched when cloning fails
+pub fn make_reflink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
+    let src = src.as_ref();
+    let dst = dst.as_ref();
+    let dst_metadata = fs::metadata(dst)?;
+    let src_file = File::open(src)?;
+    let src_metadata = src_file.metadata()?;
+    if src_metadata.len() != dst_metadata.len() {
+        return Err(Error::other("Files have different sizes"));
+    }
+    // Hard links of the same file already share data
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if src_metadata.dev() == dst_metadata.dev() && src_metadata.ino() == dst_metadata.ino() {
+            return Ok(());
+        }
+    }
+
+    // File is not truncated, so its content is kept when filesystem refuses to clone it
+    let dst_file = fs::OpenOptions::new().write(true).open(dst)?;
+    clone_file_content(&src_file, &dst_file).map_err(|e| describe_reflink_error(e, dst))?;
+    filetime::set_file_handle_times(
+        &dst_file,
+        Some(FileTime::from_last_access_time(&dst_metadata)),
+        Some(FileTime::from_last_modification_time(&dst_metadata)),
+    )
+}
+
+// Only FICLONE is used - copy_file_range silently falls back to copying data, which would not save any space
+#[cfg(target_os = "linux")]
+fn clone_file_content(src: &File, dst: &File) -> io::Result<()> {
+    rustix::fs::ioctl_ficlone(dst, src).map_err(io::Error::from)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn clone_file_content(_src: &File, _dst: &File) -> io::Result<()> {
+    Err(Error::new(io::ErrorKind::Unsupported, "Reflinks are not supported on this platform"))
+}
+
+// FICLONE returns EOPNOTSUPP/EINVAL on filesystems without reflink support(ext4, tmpfs) and EXDEV between different filesystems
+fn describe_reflink_error(e: io::Error, dst: &Path) -> io::Error {
+    if matches!(e.kind(), io::ErrorKind::Unsupported | io::ErrorKind::CrossesDevices | io::ErrorKind::InvalidInput) {
+        let message = flc!("core_reflink_unsupported_filesystem", path = dst.to_string_lossy().to_string(), reason = e.to_string());
+        Error::new(io::ErrorKind::Unsupported, message)
+    } else {
+        e
+    }
+}
+
 #[cfg(not(any(target_family = "unix", target_family = "windows")))]
 pub fn make_file_symlink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
     Err(Error::new(io::ErrorKind::Other, "Soft links are not supported on this platform"))
@@ -287,4 +343,68 @@ mod tests {
         assert_eq!(described.kind(), ErrorKind::PermissionDenied);
         assert_eq!(described.to_string(), "Permission denied");
     }
+
+    #[test]
+    fn make_reflink_keeps_destination_metadata_or_leaves_it_untouched() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let src = temp_dir.path().join("src.bin");
+        let dst = temp_dir.path().join("dst.bin");
+        fs::write(&src, "same content").unwrap();
+        fs::write(&dst, "same content").unwrap();
+        filetime::set_file_mtime(&dst, FileTime::from_unix_time(1_000_000, 0)).unwrap();
+
+        // Reflinks depend on filesystem used by temp dir, so both outcomes must be handled correctly
+        match make_reflink(&src, &dst) {
+            Ok(()) => {
+                fs::write(&src, "changed data").unwrap();
+                assert_eq!(fs::read_to_string(&dst).unwrap(), "same content");
+            }
+            Err(e) => assert_eq!(e.kind(), ErrorKind::Unsupported, "{e}"),
+        }
+        assert_eq!(FileTime::from_last_modification_time(&fs::metadata(&dst).unwrap()).unix_seconds(), 1_000_000);
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2, "No additional files should be created");
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn make_reflink_keeps_permissions_and_hard_links_of_destination() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let src = temp_dir.path().join("src.bin");
+        let dst = temp_dir.path().join("dst.bin");
+        let dst_hard_link = temp_dir.path().join("dst_hard_link.bin");
+        fs::write(&src, "same content").unwrap();
+        fs::write(&dst, "same content").unwrap();
+        fs::hard_link(&dst, &dst_hard_link).unwrap();
+        fs::set_permissions(&src, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::set_permissions(&dst, fs::Permissions::from_mode(0o600)).unwrap();
+        let dst_metadata = fs::metadata(&dst).unwrap();
+
+        match make_reflink(&src, &dst) {
+            Ok(()) => assert_eq!(fs::read_to_string(&dst).unwrap(), "same content"),
+            Err(e) => assert_eq!(e.kind(), ErrorKind::Unsupported, "{e}"),
+        }
+        let metadata = fs::metadata(&dst).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        assert_eq!((metadata.uid(), metadata.gid()), (dst_metadata.uid(), dst_metadata.gid()));
+        assert_eq!(metadata.ino(), dst_metadata.ino());
+        assert_eq!(metadata.nlink(), 2);
+        assert_eq!(fs::metadata(&dst_hard_link).unwrap().ino(), dst_metadata.ino());
+
+        // Hard links of the same file are left as they are
+        make_reflink(&dst, &dst_hard_link).unwrap();
+    }
+
+    #[test]
+    fn make_reflink_rejects_files_with_different_size() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let src = temp_dir.path().join("src.bin");
+        let dst = temp_dir.path().join("dst.bin");
+        fs::write(&src, "abc").unwrap();
+        fs::write(&dst, "abcd").unwrap();
+
+        assert_eq!(make_reflink(&src, &dst).unwrap_err().kind(), ErrorKind::Other);
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "abcd");
+    }
 }
@@ -0,0 +1,281 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::common::tool_data::CommonData;
+
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Waits for changes in folders checked by tool.
+///
+/// On Linux changes are reported by inotify, so only folders allowed by included/excluded directories and excluded items are watched.
+/// On other systems, or when inotify cannot be used, folders are considered changed after every poll interval.
+pub struct FsWatcher {
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify_watcher::InotifyWatcher>,
+    poll_interval: Duration,
+}
+
+impl FsWatcher {
+    /// Should be created before search, so files changed while search is running are reported too.
+    /// Directories are optimized the same way as during search, problems with them are reported by search.
+    #[cfg_attr(not(target_os = "linux"), expect(unused_variables))]
+    pub fn new<T: CommonData>(tool: &T, poll_interval: Duration) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            let cd = tool.get_cd();
+            let mut directories = cd.directories.clone();
+            let _ = directories.optimize_directories(cd.recursive_search, false);
+            let inotify = match inotify_watcher::InotifyWatcher::new(directories, cd.excluded_items.clone(), cd.recursive_search) {
+                Ok(inotify) => Some(inotify),
+                Err(e) => {
+                    log::error!("{e}, falling back to rescanning every {} seconds", poll_interval.as_secs());
+                    None
+                }
+            };
+            Self { inotify, poll_interval }
+        }
+        #[cfg(not(target_os = "linux"))]
+        Self { poll_interval }
+    }
+
+    /// Returns false when changed paths are unknown, so every folder may contain changes, not only reported ones.
+    pub fn tracks_changes(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        return self.inotify.is_some();
+        #[cfg(not(target_os = "linux"))]
+        false
+    }
+
+    /// Blocks until something changes in watched folders and then nothing changes for `debounce` time.
+    /// Returns changed files and folders (empty when changes cannot be tracked), or `None` when stop flag was set.
+    #[cfg_attr(not(target_os = "linux"), expect(unused_variables))]
+    pub fn wait_for_changes(&mut self, stop_flag: &AtomicBool, debounce: Duration) -> Option<Vec<PathBuf>> {
+        #[cfg(target_os = "linux")]
+        if self.inotify.is_some() {
+            return self.wait_for_inotify_changes(stop_flag, debounce);
+        }
+
+        let start = Instant::now();
+        while start.elapsed() < self.poll_interval {
+            if stop_flag.load(Ordering::Relaxed) {
+                return None;
+            }
+            sleep(CHECK_INTERVAL);
+        }
+        Some(Vec::new())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn wait_for_inotify_changes(&mut self, stop_flag: &AtomicBool, debounce: Duration) -> Option<Vec<PathBuf>> {
+        let mut changed_paths = Vec::new();
+        let mut last_change: Option<Instant> = None;
+        loop {
+            if stop_flag.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Some(inotify) = &mut self.inotify else {
+                return Some(changed_paths);
+            };
+            match inotify.read_changes() {
+                Ok(paths) => {
+                    if !paths.is_empty() {
+                        changed_paths.extend(paths);
+                        last_change = Some(Instant::now());
+                    }
+                }
+                Err(e) => {
+                    log::error!("{e}, falling back to rescanning every {} seconds", self.poll_interval.as_secs());
+                    self.inotify = None;
+                    return Some(changed_paths);
+                }
+            }
+            if last_change.is_some_and(|last_change| last_change.elapsed() >= debounce) {
+                return Some(changed_paths);
+            }
+            sleep(CHECK_INTERVAL);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod inotify_watcher {
+    use std::collections::HashMap;
+    use std::ffi::OsStr;
+    use std::fs;
+    use std::mem::MaybeUninit;
+    use std::os::fd::OwnedFd;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+
+    use log::{debug, warn};
+    use rustix::fs::inotify::{self, CreateFlags, ReadFlags, WatchFlags};
+    use rustix::io::Errno;
+
+    use crate::common::directories::Directories;
+    use crate::common::items::ExcludedItems;
+
+    const EVENT_BUFFER_SIZE: usize = 16 * 1024;
+
+    pub(super) struct InotifyWatcher {
+        fd: OwnedFd,
+        watched_folders: HashMap<i32, PathBuf>,
+        directories: Directories,
+        excluded_items: ExcludedItems,
+        recursive_search: bool,
+        buffer: Vec<MaybeUninit<u8>>,
+    }
+
+    impl InotifyWatcher {
+        pub(super) fn new(directories: Directories, excluded_items: ExcludedItems, recursive_search: bool) -> Result<Self, String> {
+            let fd = inotify::init(CreateFlags::NONBLOCK | CreateFlags::CLOEXEC).map_err(|e| format!("Cannot initialize inotify - {e}"))?;
+            let mut watcher = Self {
+                fd,
+                watched_folders: HashMap::new(),
+                directories,
+                excluded_items,
+                recursive_search,
+                buffer: vec![MaybeUninit::uninit(); EVENT_BUFFER_SIZE],
+            };
+            for folder in watcher.directories.included_directories.clone() {
+                watcher.watch_folder(&folder)?;
+            }
+            debug!("Watching {} folders for changes", watcher.watched_folders.len());
+            Ok(watcher)
+        }
+
+        /// Returns watched folders, folder itself and its subfolders when search is recursive.
+        fn watch_folder(&mut self, folder: &Path) -> Result<Vec<PathBuf>, String> {
+            let mut watched_folders = Vec::new();
+            let mut folders_to_check = vec![folder.to_path_buf()];
+            while let Some(folder) = folders_to_check.pop() {
+                if self.directories.is_excluded_item_in_dir(&folder) || self.excluded_items.is_excluded(&folder) {
+                    continue;
+                }
+                let flags = WatchFlags::CLOSE_WRITE | WatchFlags::CREATE | WatchFlags::DELETE | WatchFlags::MOVED_FROM | WatchFlags::MOVED_TO | WatchFlags::ONLYDIR;
+                match inotify::add_watch(&self.fd, &folder, flags) {
+                    Ok(wd) => {
+                        self.watched_folders.insert(wd, folder.clone());
+                        watched_folders.push(folder.clone());
+                    }
+                    // Limit is shared by all folders, so watching next ones would fail too
+                    Err(Errno::NOSPC) => {
+                        return Err(format!(
+                            "Reached limit of inotify watches when watching \"{}\", it can be increased with fs.inotify.max_user_watches",
+                            folder.to_string_lossy()
+                        ));
+                    }
+                    Err(e) => {
+                        warn!("Cannot watch folder \"{}\" - {e}", folder.to_string_lossy());
+                        continue;
+                    }
+                }
+
+                if !self.recursive_search {
+                    continue;
+                }
+                let Ok(read_dir) = fs::read_dir(&folder) else {
+                    continue;
+                };
+                // Symlinks are not followed, same as during scan
+                folders_to_check.extend(read_dir.flatten().filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir())).map(|entry| entry.path()));
+            }
+            Ok(watched_folders)
+        }
+
+        /// Returns paths changed since previous call, empty when nothing changed.
+        pub(super) fn read_changes(&mut self) -> Result<Vec<PathBuf>, String> {
+            let mut events = Vec::new();
+            let mut reader = inotify::Reader::new(&self.fd, &mut self.buffer);
+            loop {
+                match reader.next() {
+                    Ok(event) => events.push((event.wd(), event.events(), event.file_name().map(|name| PathBuf::from(OsStr::from_bytes(name.to_bytes()))))),
+                    Err(Errno::AGAIN) => break,
+                    Err(e) => return Err(format!("Cannot read inotify events - {e}")),
+                }
+            }
+
+            let mut changed_paths = Vec::new();
+            for (wd, flags, file_name) in events {
+                if flags.contains(ReadFlags::QUEUE_OVERFLOW) {
+                    // Some events were lost, so everything needs to be checked again
+                    changed_paths.extend(self.directories.included_directories.iter().cloned());
+                    continue;
+                }
+                if flags.contains(ReadFlags::IGNORED) {
+                    self.watched_folders.remove(&wd);
+                    continue;
+                }
+                let Some(folder) = self.watched_folders.get(&wd) else {
+                    continue;
+                };
+                let path = match file_name {
+                    Some(file_name) => folder.join(file_name),
+                    None => folder.clone(),
+                };
+
+                if flags.contains(ReadFlags::ISDIR) {
+                    if self.recursive_search && flags.intersects(ReadFlags::CREATE | ReadFlags::MOVED_TO) {
+                        // Subfolders and files could be created before folder was watched, so all watched folders are reported as changed
+                        changed_paths.extend(self.watch_folder(&path)?);
+                        continue;
+                    }
+                } else if flags.contains(ReadFlags::CREATE) || self.excluded_items.is_excluded(&path) {
+                    // Created file is still being written, it will be reported when closed
+                    continue;
+                }
+                changed_paths.push(path);
+            }
+            Ok(changed_paths)
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::common::model::{CheckingMethod, HashType};
+    use crate::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
+
+    #[test]
+    fn test_watcher_reports_changes_in_new_folders() {
+        let dir = tempfile::tempdir().expect("Cannot create temp dir");
+        let mut tool = DuplicateFinder::new(DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true));
+        tool.set_included_paths(vec![dir.path().to_path_buf()]);
+
+        let mut watcher = FsWatcher::new(&tool, Duration::from_secs(60));
+        assert!(watcher.tracks_changes());
+        let stop_flag = AtomicBool::new(false);
+        let debounce = Duration::from_millis(200);
+
+        fs::create_dir(dir.path().join("sub")).expect("Cannot create dir");
+        let changed = watcher.wait_for_changes(&stop_flag, debounce).expect("Watcher stopped");
+        assert_eq!(changed, vec![dir.path().join("sub")]);
+
+        fs::write(dir.path().join("sub").join("file.txt"), "abc").expect("Cannot write file");
+        let changed = watcher.wait_for_changes(&stop_flag, debounce).expect("Watcher stopped");
+        assert_eq!(changed, vec![dir.path().join("sub").join("file.txt")]);
+
+        stop_flag.store(true, Ordering::Relaxed);
+        assert!(watcher.wait_for_changes(&stop_flag, debounce).is_none());
+    }
+
+    #[test]
+    fn test_watcher_reports_subfolders_created_before_being_watched() {
+        let dir = tempfile::tempdir().expect("Cannot create temp dir");
+        let mut tool = DuplicateFinder::new(DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true));
+        tool.set_included_paths(vec![dir.path().to_path_buf()]);
+
+        // Events are read only after whole folder tree is created, like after long search
+        let mut watcher = FsWatcher::new(&tool, Duration::from_secs(60));
+        fs::create_dir_all(dir.path().join("sub").join("nested")).expect("Cannot create dir");
+        fs::write(dir.path().join("sub").join("nested").join("file.txt"), "abc").expect("Cannot write file");
+
+        let mut changed = watcher.wait_for_changes(&AtomicBool::new(false), Duration::from_millis(200)).expect("Watcher stopped");
+        changed.sort();
+        assert_eq!(changed, vec![dir.path().join("sub"), dir.path().join("sub").join("nested")]);
+    }
+}
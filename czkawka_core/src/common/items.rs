@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use regex::{Regex, RegexBuilder};
+
 use crate::common::regex_check;
 use crate::flc;
 use crate::helpers::messages::Messages;
@@ -25,10 +27,21 @@ pub const TRASH_EXCLUDED_ITEMS: &str = "*/Trash/*,*/.Trash-*/*";
 #[cfg(not(target_family = "unix"))]
 pub const TRASH_EXCLUDED_ITEMS: &str = "*:\\$RECYCLE.BIN\\*";
 
+/// Items excluded from scan.
+///
+/// Supported expressions:
+/// - `*/temp/*` - wildcard, `*` matches any number of characters, including path separators
+/// - `**/build/**` - glob, used when expression contains `**`; `**` matches any number of folders, `*` and `?` match only inside single path component
+/// - `regex:.*/cache_[0-9]+/.*` - regular expression, which must match whole path
+/// - `!*/app/build/*` - negation, item matched by it is not excluded, even if earlier expression excluded it
+///
+/// The last matching expression decides, so negation should be placed after expressions which it overrides, like in `.gitignore`.
+/// Rules from ignore files found during scan are checked before expressions set by user, so user expressions, including negations, decide last.
 #[derive(Debug, Clone, Default)]
 pub struct ExcludedItems {
     expressions: Vec<String>,
-    connected_expressions: Vec<SingleExcludedItem>,
+    connected_expressions: Vec<ExcludedRule>,
+    ignore_file_rules: Vec<ExcludedRule>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -38,6 +51,30 @@ pub struct SingleExcludedItem {
     pub unique_extensions_splits: Vec<String>,
 }
 
+#[derive(Debug, Clone)]
+struct ExcludedRule {
+    pattern: ExcludedPattern,
+    negated: bool,
+}
+
+#[derive(Debug, Clone)]
+enum ExcludedPattern {
+    Wildcard(SingleExcludedItem),
+    Regex(Regex),
+}
+
+impl ExcludedRule {
+    fn matches(&self, path: &str) -> bool {
+        match &self.pattern {
+            ExcludedPattern::Wildcard(item) => regex_check(item, path),
+            ExcludedPattern::Regex(regex) => regex.is_match(path),
+        }
+    }
+}
+
+pub const IGNORE_FILE_NAME: &str = ".czkawkaignore";
+pub const GITIGNORE_FILE_NAME: &str = ".gitignore";
+
 impl ExcludedItems {
     pub fn new() -> Self {
         Default::default()
@@ -56,7 +93,7 @@ impl ExcludedItems {
         }
 
         let expressions: Vec<String> = excluded_items;
-        let mut checked_expressions: Vec<String> = Vec::new();
+        let mut checked_expressions: Vec<(String, ExcludedRule)> = Vec::new();
 
         for expression in expressions {
             let expression: String = expression.trim().to_string();
@@ -65,9 +102,6 @@ impl ExcludedItems {
                 continue;
             }
 
-            #[cfg(target_family = "windows")]
-            let expression = expression.replace("/", "\\");
-
             if expression == "DEFAULT" {
                 push_preset_items(&mut checked_expressions, DEFAULT_EXCLUDED_ITEMS);
                 continue;
@@ -76,7 +110,32 @@ impl ExcludedItems {
                 push_preset_items(&mut checked_expressions, TRASH_EXCLUDED_ITEMS);
                 continue;
             }
-            if !expression.contains('*') {
+
+            let (negated, pattern) = match expression.strip_prefix('!') {
+                Some(pattern) => (true, pattern.trim()),
+                None => (false, expression.as_str()),
+            };
+
+            // Regular expression is used as is, so it is not modified on Windows like wildcards
+            if let Some(regex) = pattern.strip_prefix("regex:") {
+                match build_regex(regex) {
+                    Ok(regex) => checked_expressions.push((
+                        expression.clone(),
+                        ExcludedRule {
+                            pattern: ExcludedPattern::Regex(regex),
+                            negated,
+                        },
+                    )),
+                    Err(e) => warnings.push(flc!("core_excluded_items_invalid_regex", expression = expression.clone(), reason = e.to_string())),
+                }
+                continue;
+            }
+
+            let pattern = pattern.to_string();
+            #[cfg(target_family = "windows")]
+            let pattern = pattern.replace("/", "\\");
+
+            if !pattern.contains('*') {
                 warnings.push(flc!("core_excluded_items_wildcard_required", expression = expression.clone()));
                 continue;
             }
@@ -84,15 +143,26 @@ impl ExcludedItems {
             // On Windows the scanned path is lowercased before matching (see `normalize_windows_path`),
             // so the expression must be lowercased too, otherwise patterns with uppercase letters never match.
             #[cfg(target_family = "windows")]
-            let expression = expression.to_ascii_lowercase();
-
-            checked_expressions.push(expression);
+            let pattern = pattern.to_ascii_lowercase();
+
+            let pattern = if pattern.contains("**") {
+                let regex = format!("^{}$", glob_to_regex(&pattern));
+                match build_regex(&regex) {
+                    Ok(regex) => ExcludedPattern::Regex(regex),
+                    Err(e) => {
+                        warnings.push(flc!("core_excluded_items_invalid_regex", expression = expression.clone(), reason = e.to_string()));
+                        continue;
+                    }
+                }
+            } else {
+                ExcludedPattern::Wildcard(new_excluded_item(&pattern))
+            };
+            checked_expressions.push((expression.clone(), ExcludedRule { pattern, negated }));
         }
 
-        for checked_expression in &checked_expressions {
-            let item = new_excluded_item(checked_expression);
-            self.expressions.push(item.expression.clone());
-            self.connected_expressions.push(item);
+        for (expression, rule) in checked_expressions {
+            self.expressions.push(expression);
+            self.connected_expressions.push(rule);
         }
         Messages {
             critical: None,
@@ -102,11 +172,56 @@ impl ExcludedItems {
         }
     }
 
+    /// Adds rules from `.gitignore`-like file placed in `folder`.
+    /// Patterns with `/` are relative to `folder`, others match file or folder name at any depth below it.
+    pub(crate) fn add_ignore_file_rules(&mut self, folder: &Path, content: &str) {
+        #[cfg(target_family = "windows")]
+        let folder = crate::common::normalize_windows_path(folder);
+
+        let separator = std::path::MAIN_SEPARATOR;
+        let escaped_separator = regex::escape(&separator.to_string());
+        let base = regex::escape(folder.to_string_lossy().trim_end_matches(separator));
+
+        for line in content.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (negated, pattern) = match line.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            // Type of item is unknown here, so `folder/` excludes also file with same name, which is rarely a problem
+            let pattern = pattern.strip_suffix('/').unwrap_or(pattern);
+            let anchored = pattern.contains('/');
+            let pattern = pattern.trim_start_matches('/');
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let pattern = pattern.to_string();
+            #[cfg(target_family = "windows")]
+            let pattern = pattern.replace("/", "\\").to_ascii_lowercase();
+
+            let prefix = if anchored { String::new() } else { format!("(?:.*{escaped_separator})?") };
+            // Everything inside matched folder is excluded too, so like in git, files inside cannot be included again
+            let suffix = format!("(?:{escaped_separator}.*)?");
+            let regex = format!("^{base}{escaped_separator}{prefix}{}{suffix}$", glob_to_regex(&pattern));
+
+            if let Ok(regex) = build_regex(&regex) {
+                self.ignore_file_rules.push(ExcludedRule {
+                    pattern: ExcludedPattern::Regex(regex),
+                    negated,
+                });
+            }
+        }
+    }
+
     pub(crate) fn get_excluded_items(&self) -> &Vec<String> {
         &self.expressions
     }
     pub(crate) fn is_excluded(&self, path: &Path) -> bool {
-        if self.connected_expressions.is_empty() {
+        if self.connected_expressions.is_empty() && self.ignore_file_rules.is_empty() {
             return false;
         }
         #[cfg(target_family = "windows")]
@@ -114,25 +229,61 @@ impl ExcludedItems {
 
         let path_str = path.to_string_lossy();
 
-        for expression in &self.connected_expressions {
-            if regex_check(expression, &path_str) {
-                return true;
+        // Last matching rule decides, if item is excluded
+        self.ignore_file_rules
+            .iter()
+            .chain(&self.connected_expressions)
+            .rev()
+            .find(|rule| rule.matches(&path_str))
+            .is_some_and(|rule| !rule.negated)
+    }
+}
+
+fn build_regex(regex: &str) -> Result<Regex, regex::Error> {
+    // Paths are lowercased on Windows, so regex needs to ignore case
+    RegexBuilder::new(regex).case_insensitive(cfg!(target_family = "windows")).build()
+}
+
+// `**` matches any number of folders, `*` and `?` match characters only inside single path component
+fn glob_to_regex(glob: &str) -> String {
+    let separator = std::path::MAIN_SEPARATOR;
+    let escaped_separator = regex::escape(&separator.to_string());
+    let mut regex = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&separator) {
+                    chars.next();
+                    regex.push_str(&format!("(?:.*{escaped_separator})?"));
+                } else {
+                    regex.push_str(".*");
+                }
             }
+            '*' => regex.push_str(&format!("[^{escaped_separator}]*")),
+            '?' => regex.push_str(&format!("[^{escaped_separator}]")),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
         }
-        false
     }
+    regex
 }
 
-fn push_preset_items(checked_expressions: &mut Vec<String>, preset: &str) {
+fn push_preset_items(checked_expressions: &mut Vec<(String, ExcludedRule)>, preset: &str) {
     for item in preset.split(',') {
         let item = item.trim();
         if !item.is_empty() {
             #[cfg(not(target_family = "windows"))]
-            checked_expressions.push(item.to_string());
+            let item = item.to_string();
             // On Windows, scanned paths are lowercased before matching, so patterns
             // must be lowercased too - including those from the preset sets.
             #[cfg(target_family = "windows")]
-            checked_expressions.push(item.to_ascii_lowercase());
+            let item = item.to_ascii_lowercase();
+            let rule = ExcludedRule {
+                pattern: ExcludedPattern::Wildcard(new_excluded_item(&item)),
+                negated: false,
+            };
+            checked_expressions.push((item, rule));
         }
     }
 }
@@ -227,6 +378,59 @@ mod tests {
         assert!(!items.is_excluded(Path::new("C:\\root\\file.txt")));
     }
 
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_is_excluded_regex_glob_and_negation() {
+        let mut items = ExcludedItems::new();
+        let msgs = items.set_excluded_items(vec![
+            "*/build/*".to_string(),
+            "!*/app/build/*".to_string(),
+            "**/target/**/*.o".to_string(),
+            "regex:.*/cache_[0-9]+/.*".to_string(),
+            "regex:(unclosed".to_string(),
+        ]);
+        assert_eq!(msgs.warnings.len(), 1);
+        assert_eq!(items.get_excluded_items().len(), 4);
+
+        assert!(items.is_excluded(Path::new("/repo/lib/build/out.bin")));
+        assert!(!items.is_excluded(Path::new("/repo/app/build/out.bin")));
+        assert!(items.is_excluded(Path::new("/repo/target/debug/deps/main.o")));
+        assert!(items.is_excluded(Path::new("/target/main.o")));
+        assert!(!items.is_excluded(Path::new("/repo/target/debug/main.rs")));
+        assert!(items.is_excluded(Path::new("/home/cache_12/file")));
+        assert!(!items.is_excluded(Path::new("/home/cache_x/file")));
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_ignore_file_rules() {
+        let mut items = ExcludedItems::new_from(vec!["*.tmp".to_string()]);
+        items.add_ignore_file_rules(Path::new("/repo"), "# comment\n\n*.log\n!keep.log\n/target\nnode_modules/\ndocs/*.pdf\n!*.tmp\n");
+
+        assert!(items.is_excluded(Path::new("/repo/a/b/debug.log")));
+        assert!(!items.is_excluded(Path::new("/repo/a/keep.log")));
+        assert!(!items.is_excluded(Path::new("/other/debug.log")));
+        assert!(items.is_excluded(Path::new("/repo/target")));
+        assert!(items.is_excluded(Path::new("/repo/target/debug/app")));
+        assert!(!items.is_excluded(Path::new("/repo/src/target")));
+        assert!(items.is_excluded(Path::new("/repo/web/node_modules/lib.js")));
+        assert!(items.is_excluded(Path::new("/repo/web/node_modules")));
+        assert!(items.is_excluded(Path::new("/repo/docs/manual.pdf")));
+        assert!(!items.is_excluded(Path::new("/repo/src/docs/manual.pdf")));
+        // Ignore files cannot override items excluded by user
+        assert!(items.is_excluded(Path::new("/repo/file.tmp")));
+
+        // User negation can include again items excluded by ignore files
+        let mut items = ExcludedItems::new_from(vec!["!*/important.log".to_string()]);
+        items.add_ignore_file_rules(
+            Path::new("/repo"),
+            "*.log
+",
+        );
+        assert!(items.is_excluded(Path::new("/repo/debug.log")));
+        assert!(!items.is_excluded(Path::new("/repo/a/important.log")));
+    }
+
     #[test]
     fn test_new_excluded_item() {
         let item = new_excluded_item("  */test/*.txt  ");
@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{fs, io};
+
+use filetime::FileTime;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::common::date_filter::timestamp;
+use crate::common::model::FileEntry;
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+use crate::helpers::messages::Messages;
+
+// Bump when the entry layout changes, entries with unknown version are refused by undo.
+pub const JOURNAL_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalAction {
+    Delete,
+    Trash,
+    HardLink,
+    Reflink,
+    Symlink,
+    Move,
+    Rename,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalStatus {
+    // Saved before operation, stays only when process was killed during operation
+    Started,
+    Completed,
+    Failed,
+}
+
+/// Single destructive operation done on a file.
+/// `destination` is the hardlink/reflink/symlink target for link actions and the new location for move/rename.
+/// Every operation is saved twice with the same `id` - before it starts and after it ends, with its result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub version: u32,
+    pub id: u64,
+    pub status: JournalStatus,
+    pub timestamp: u64,
+    pub action: JournalAction,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_date: u64,
+    pub hash: Option<String>,
+    pub destination: Option<PathBuf>,
+}
+
+impl JournalEntry {
+    pub fn new<T: ResultEntry>(action: JournalAction, entry: &T, destination: Option<&Path>) -> Self {
+        Self {
+            version: JOURNAL_VERSION,
+            id: rand::random::<u64>(),
+            status: JournalStatus::Started,
+            timestamp: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs(),
+            action,
+            path: entry.get_path().to_path_buf(),
+            size: entry.get_size(),
+            modified_date: entry.get_modified_date(),
+            hash: entry.get_hash().map(str::to_string),
+            destination: destination.map(Path::to_path_buf),
+        }
+    }
+}
+
+/// Append-only journal file, one json entry per line.
+/// Entry is synced to disk by `start` before file is changed, so operation interrupted by killed process is still visible in journal.
+/// Files modified in place, e.g. by exif remover or video optimizer, are not recorded, because their previous content is not kept and cannot be restored.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Journal {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(|e| format!("Cannot create folder for journal \"{}\", reason {e}", path.to_string_lossy()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Cannot open journal \"{}\", reason {e}", path.to_string_lossy()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    // Journal is not needed when nothing is changed on disk
+    pub(crate) fn open_for_tool(cd: &CommonToolData) -> Result<Option<Self>, String> {
+        match cd.journal_path.as_deref() {
+            Some(path) if !cd.dry_run => Self::open(path).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Saves operation which is about to start, returned entry must be passed to `finish` when operation ends.
+    /// When this fails, operation should not be started, because it couldn't be reverted.
+    pub fn start<T: ResultEntry>(&self, action: JournalAction, entry: &T, destination: Option<&Path>) -> Result<JournalEntry, String> {
+        let entry = JournalEntry::new(action, entry, destination);
+        self.record_entry(&entry)?;
+        Ok(entry)
+    }
+
+    /// Same as `start`, but size and modification date are read from file, for files which are not available as scan results.
+    pub fn start_for_path(&self, action: JournalAction, path: &Path, destination: Option<&Path>) -> Result<JournalEntry, String> {
+        let metadata = fs::symlink_metadata(path).ok();
+        let entry = FileEntry {
+            path: path.to_path_buf(),
+            size: metadata.as_ref().map_or(0, fs::Metadata::len),
+            modified_date: metadata.and_then(|metadata| timestamp(metadata.modified())).unwrap_or_default(),
+        };
+        self.start(action, &entry, destination)
+    }
+
+    pub fn finish(&self, mut entry: JournalEntry, completed: bool) -> Result<(), String> {
+        entry.status = if completed { JournalStatus::Completed } else { JournalStatus::Failed };
+        self.record_entry(&entry)
+    }
+
+    // Saves already completed operation
+    pub fn record<T: ResultEntry>(&self, action: JournalAction, entry: &T, destination: Option<&Path>) -> Result<(), String> {
+        let mut entry = JournalEntry::new(action, entry, destination);
+        entry.status = JournalStatus::Completed;
+        self.record_entry(&entry)
+    }
+
+    pub fn record_entry(&self, entry: &JournalEntry) -> Result<(), String> {
+        let mut line = serde_json::to_string(entry).map_err(|e| format!("Cannot serialize journal entry, reason {e}"))?;
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        file.write_all(line.as_bytes()).and_then(|()| file.sync_data()).map_err(|e| {
+            format!(
+                "Cannot write entry for \"{}\" to journal \"{}\", reason {e}",
+                entry.path.to_string_lossy(),
+                self.path.to_string_lossy()
+            )
+        })
+    }
+}
+
+/// Loads operations in order in which they were started, with their last status.
+/// Failed operations didn't change anything, so they are skipped.
+pub fn load_journal(path: &Path) -> Result<Vec<JournalEntry>, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open journal \"{}\", reason {e}", path.to_string_lossy()))?;
+    let mut entries: Vec<JournalEntry> = Vec::new();
+    let mut entry_indexes: HashMap<u64, usize> = HashMap::new();
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Cannot read journal \"{}\", reason {e}", path.to_string_lossy()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid_entry = |e: serde_json::Error| format!("Invalid entry in line {} of journal \"{}\", reason {e}", idx + 1, path.to_string_lossy());
+        // Version is checked before parsing whole entry, because entries with other version may have different fields
+        let value: serde_json::Value = serde_json::from_str(&line).map_err(invalid_entry)?;
+        let version = value.get("version").and_then(serde_json::Value::as_u64).unwrap_or_default();
+        if version != u64::from(JOURNAL_VERSION) {
+            return Err(format!(
+                "Unsupported journal entry version {version} in line {} of journal \"{}\" (supported version: {JOURNAL_VERSION})",
+                idx + 1,
+                path.to_string_lossy()
+            ));
+        }
+        let entry: JournalEntry = serde_json::from_value(value).map_err(invalid_entry)?;
+        match entry_indexes.get(&entry.id).and_then(|idx| entries.get_mut(*idx)) {
+            Some(started_entry) => started_entry.status = entry.status,
+            None => {
+                entry_indexes.insert(entry.id, entries.len());
+                entries.push(entry);
+            }
+        }
+    }
+    entries.retain(|entry| entry.status != JournalStatus::Failed);
+    Ok(entries)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UndoResult {
+    pub reverted_entries: usize,
+    pub not_revertible_entries: usize,
+    // Entries of operations which left file with unchanged and independent content
+    pub not_needed_entries: usize,
+    pub failed_entries: usize,
+    pub messages: Messages,
+}
+
+/// Reverts journal entries from newest to oldest.
+/// Deleted files cannot be restored and trashed files must be restored from the OS trash, so such entries are only reported.
+/// Reflinked files don't need to be restored, so their entries are only reported too.
+pub fn undo_journal(path: &Path, dry_run: bool) -> UndoResult {
+    let mut undo_result = UndoResult::default();
+
+    let entries = match load_journal(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            undo_result.messages.critical = Some(e);
+            return undo_result;
+        }
+    };
+
+    info!("Reverting {} journal entries from \"{}\", dry_run: {dry_run}", entries.len(), path.to_string_lossy());
+
+    for entry in entries.iter().rev() {
+        let entry_path = entry.path.to_string_lossy();
+        match entry.action {
+            JournalAction::Delete => {
+                undo_result.not_revertible_entries += 1;
+                undo_result.messages.warnings.push(format!("Cannot restore permanently deleted \"{entry_path}\""));
+                continue;
+            }
+            JournalAction::Trash => {
+                undo_result.not_revertible_entries += 1;
+                undo_result.messages.warnings.push(format!("\"{entry_path}\" was moved to trash, restore it from there"));
+                continue;
+            }
+            // Reflinked file has the same content as before and shares disk blocks only until one of files is modified
+            JournalAction::Reflink => {
+                undo_result.not_needed_entries += 1;
+                undo_result
+                    .messages
+                    .messages
+                    .push(format!("\"{entry_path}\" was reflinked, so it is already independent file and doesn't need to be restored"));
+                continue;
+            }
+            _ => {}
+        }
+
+        if dry_run {
+            undo_result.reverted_entries += 1;
+            undo_result.messages.messages.push(format!("Would restore: \"{entry_path}\""));
+            continue;
+        }
+
+        match undo_entry(entry) {
+            Ok(()) => {
+                undo_result.reverted_entries += 1;
+            }
+            // Process was killed during operation, so most likely it was not done and there is nothing to revert
+            Err(e) if entry.status == JournalStatus::Started => {
+                undo_result.not_revertible_entries += 1;
+                undo_result.messages.warnings.push(format!("Operation on \"{entry_path}\" was interrupted - {e}"));
+            }
+            Err(e) => {
+                undo_result.failed_entries += 1;
+                undo_result.messages.errors.push(e);
+            }
+        }
+    }
+
+    if !dry_run {
+        info!(
+            "{} journal entries reverted, {} not revertible, {} failed",
+            undo_result.reverted_entries, undo_result.not_revertible_entries, undo_result.failed_entries
+        );
+    }
+
+    undo_result
+}
+
+fn undo_entry(entry: &JournalEntry) -> Result<(), String> {
+    let entry_path = entry.path.to_string_lossy();
+    match entry.action {
+        JournalAction::HardLink | JournalAction::Symlink => {
+            let Ok(metadata) = fs::symlink_metadata(&entry.path) else {
+                return Err(format!("Cannot restore \"{entry_path}\", file no longer exists"));
+            };
+            if entry.action == JournalAction::Symlink && !metadata.file_type().is_symlink() {
+                return Err(format!("Cannot restore \"{entry_path}\", it is no longer a symlink"));
+            }
+            // Symlink metadata would return size of link itself, so size must be checked on the target
+            let current_size = fs::metadata(&entry.path).map_or(u64::MAX, |m| m.len());
+            if current_size != entry.size {
+                return Err(format!(
+                    "Cannot restore \"{entry_path}\", linked content has size {current_size} instead of expected {}",
+                    entry.size
+                ));
+            }
+            replace_with_independent_copy(&entry.path, entry.modified_date).map_err(|e| format!("Failed to restore \"{entry_path}\", reason {e}"))
+        }
+        JournalAction::Move | JournalAction::Rename => {
+            let Some(destination) = &entry.destination else {
+                return Err(format!("Cannot restore \"{entry_path}\", journal entry has no destination"));
+            };
+            if fs::symlink_metadata(&entry.path).is_ok() {
+                return Err(format!("Cannot restore \"{entry_path}\", file already exists"));
+            }
+            if !destination.exists() {
+                return Err(format!("Cannot restore \"{entry_path}\", \"{}\" no longer exists", destination.to_string_lossy()));
+            }
+            move_back(destination, &entry.path).map_err(|e| format!("Failed to move \"{}\" back to \"{entry_path}\", reason {e}", destination.to_string_lossy()))
+        }
+        JournalAction::Delete | JournalAction::Trash | JournalAction::Reflink => {
+            unreachable!("Not revertible actions and actions which don't need reverting should be handled before")
+        }
+    }
+}
+
+// Copies content into a temporary file next to the link and swaps it in, so the path stops sharing data with other files
+fn replace_with_independent_copy(path: &Path, modified_date: u64) -> io::Result<()> {
+    let dir = path.parent().ok_or_else(|| io::Error::other("No parent"))?;
+    let temp = dir.join(format!("{}.czkawka_tmp", rand::random::<u128>()));
+    fs::copy(path, &temp)?;
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    filetime::set_file_mtime(path, FileTime::from_unix_time(modified_date as i64, 0))
+}
+
+pub(crate) fn move_back(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    // Rename fails across filesystems, e.g. when files were moved to another drive
+    fs::copy(from, to)?;
+    if let Err(e) = fs::remove_file(from) {
+        error!("Restored \"{}\", but cannot remove \"{}\", reason {e}", to.to_string_lossy(), from.to_string_lossy());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::common::make_hard_link;
+
+    fn file_entry(path: &Path) -> FileEntry {
+        let metadata = fs::metadata(path).unwrap();
+        FileEntry {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            modified_date: 1_000_000,
+        }
+    }
+
+    #[test]
+    fn test_journal_record_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal_path = temp_dir.path().join("sub").join("journal.jsonl");
+        let file = temp_dir.path().join("a.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let journal = Journal::open(&journal_path).unwrap();
+        journal.record(JournalAction::Delete, &file_entry(&file), None).unwrap();
+        journal.record(JournalAction::Move, &file_entry(&file), Some(Path::new("/tmp/b.txt"))).unwrap();
+        drop(journal);
+
+        // Reopening must append instead of truncating
+        let journal = Journal::open(&journal_path).unwrap();
+        journal.record(JournalAction::Trash, &file_entry(&file), None).unwrap();
+
+        let entries = load_journal(&journal_path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].action, JournalAction::Delete);
+        assert_eq!(entries[0].size, 3);
+        assert_eq!(entries[1].destination, Some(PathBuf::from("/tmp/b.txt")));
+        assert_eq!(entries[2].action, JournalAction::Trash);
+        assert!(entries.iter().all(|e| e.version == JOURNAL_VERSION));
+    }
+
+    #[test]
+    fn test_journal_start_and_finish() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal_path = temp_dir.path().join("journal.jsonl");
+        let file = temp_dir.path().join("a.txt");
+        fs::write(&file, "abc").unwrap();
+        let destination = temp_dir.path().join("b.txt");
+
+        let journal = Journal::open(&journal_path).unwrap();
+        let completed = journal.start(JournalAction::Delete, &file_entry(&file), None).unwrap();
+        let failed = journal.start(JournalAction::Trash, &file_entry(&file), None).unwrap();
+        let _interrupted = journal.start(JournalAction::Move, &file_entry(&file), Some(&destination)).unwrap();
+        journal.finish(failed, false).unwrap();
+        journal.finish(completed, true).unwrap();
+
+        let entries = load_journal(&journal_path).unwrap();
+        assert_eq!(
+            entries.iter().map(|e| (e.action, e.status)).collect::<Vec<_>>(),
+            vec![(JournalAction::Delete, JournalStatus::Completed), (JournalAction::Move, JournalStatus::Started)]
+        );
+
+        // Interrupted move was not done, so it is only reported
+        let result = undo_journal(&journal_path, false);
+        assert_eq!(result.not_revertible_entries, 2);
+        assert_eq!(result.failed_entries, 0);
+        assert!(result.messages.errors.is_empty());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_journal_start_for_path_reads_file_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("a.txt");
+        fs::write(&file, "abc").unwrap();
+        filetime::set_file_mtime(&file, FileTime::from_unix_time(1_000_000, 0)).unwrap();
+
+        let journal = Journal::open(&temp_dir.path().join("journal.jsonl")).unwrap();
+        let entry = journal.start_for_path(JournalAction::Rename, &file, Some(&temp_dir.path().join("b.txt"))).unwrap();
+        assert_eq!((entry.size, entry.modified_date, entry.status), (3, 1_000_000, JournalStatus::Started));
+    }
+
+    #[test]
+    fn test_load_journal_rejects_unknown_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal_path = temp_dir.path().join("journal.jsonl");
+        let mut entry = JournalEntry::new(JournalAction::Delete, &FileEntry::default(), None);
+        entry.version = JOURNAL_VERSION + 1;
+        fs::write(&journal_path, serde_json::to_string(&entry).unwrap()).unwrap();
+
+        let err = load_journal(&journal_path).unwrap_err();
+        assert!(err.contains("Unsupported journal entry version"), "{err}");
+    }
+
+    #[test]
+    fn test_undo_move_and_rename() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("dir").join("a.txt");
+        let moved = temp_dir.path().join("quarantine").join("a.txt");
+        fs::create_dir_all(moved.parent().unwrap()).unwrap();
+        fs::write(&moved, "abc").unwrap();
+
+        let renamed_original = temp_dir.path().join("B.TXT");
+        let renamed = temp_dir.path().join("b.txt");
+        fs::write(&renamed, "def").unwrap();
+
+        let journal_path = temp_dir.path().join("journal.jsonl");
+        let journal = Journal::open(&journal_path).unwrap();
+        journal
+            .record(
+                JournalAction::Move,
+                &FileEntry {
+                    path: original.clone(),
+                    size: 3,
+                    modified_date: 0,
+                },
+                Some(&moved),
+            )
+            .unwrap();
+        journal
+            .record(
+                JournalAction::Rename,
+                &FileEntry {
+                    path: renamed_original.clone(),
+                    size: 3,
+                    modified_date: 0,
+                },
+                Some(&renamed),
+            )
+            .unwrap();
+
+        let dry_result = undo_journal(&journal_path, true);
+        assert_eq!(dry_result.reverted_entries, 2);
+        assert!(moved.exists());
+
+        let result = undo_journal(&journal_path, false);
+        assert_eq!(result.reverted_entries, 2, "{:?}", result.messages);
+        assert_eq!(result.failed_entries, 0);
+        assert_eq!(fs::read_to_string(&original).unwrap(), "abc");
+        assert_eq!(fs::read_to_string(&renamed_original).unwrap(), "def");
+        assert!(!moved.exists());
+    }
+
+    #[test]
+    fn test_undo_hardlink_restores_independent_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("original.txt");
+        let duplicate = temp_dir.path().join("duplicate.txt");
+        fs::write(&original, "content").unwrap();
+        fs::write(&duplicate, "content").unwrap();
+
+        let journal_path = temp_dir.path().join("journal.jsonl");
+        let journal = Journal::open(&journal_path).unwrap();
+        let entry = file_entry(&duplicate);
+        make_hard_link(&original, &duplicate).unwrap();
+        journal.record(JournalAction::HardLink, &entry, Some(&original)).unwrap();
+
+        let result = undo_journal(&journal_path, false);
+        assert_eq!(result.reverted_entries, 1, "{:?}", result.messages);
+
+        fs::write(&original, "changed").unwrap();
+        assert_eq!(fs::read_to_string(&duplicate).unwrap(), "content");
+        let modified = FileTime::from_last_modification_time(&fs::metadata(&duplicate).unwrap());
+        assert_eq!(modified.unix_seconds(), 1_000_000);
+    }
+
+    #[test]
+    fn test_undo_reports_not_revertible_and_failed_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal_path = temp_dir.path().join("journal.jsonl");
+        let missing = FileEntry {
+            path: temp_dir.path().join("missing.txt"),
+            size: 1,
+            modified_date: 0,
+        };
+        let journal = Journal::open(&journal_path).unwrap();
+        journal.record(JournalAction::Delete, &missing, None).unwrap();
+        journal.record(JournalAction::Trash, &missing, None).unwrap();
+        journal.record(JournalAction::HardLink, &missing, Some(Path::new("/nonexistent"))).unwrap();
+        journal.record(JournalAction::Reflink, &missing, Some(Path::new("/nonexistent"))).unwrap();
+
+        let result = undo_journal(&journal_path, false);
+        assert_eq!(result.not_revertible_entries, 2);
+        assert_eq!(result.not_needed_entries, 1);
+        assert_eq!(result.failed_entries, 1);
+        assert_eq!(result.reverted_entries, 0);
+        assert_eq!(result.messages.errors.len(), 1);
+    }
+}
@@ -3,17 +3,25 @@ pub mod basic_gui_cli;
 pub mod cache;
 pub mod config_cache_path;
 pub mod consts;
+pub mod date_filter;
+pub mod dir_snapshot;
 pub mod dir_traversal;
 pub mod directories;
 pub mod extensions;
 pub mod ffmpeg_utils;
+pub mod fs_watcher;
 pub mod image;
 pub mod items;
+pub mod journal;
 pub mod logger;
 pub mod model;
+pub mod multi_scan;
+pub mod preferred_paths;
 pub mod process_utils;
 pub mod progress_data;
 pub mod progress_stop_handler;
+pub mod quarantine;
+pub mod select_rules;
 pub mod tool_data;
 pub mod traits;
 pub mod video_utils;
@@ -321,6 +329,38 @@ mod test {
         Ok(())
     }
 
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_get_lexical_relative_path() {
+        assert_eq!(get_lexical_relative_path(Path::new("/home/a"), Path::new("/home/a/b.txt")), Some(PathBuf::from("b.txt")));
+        assert_eq!(
+            get_lexical_relative_path(Path::new("/home/a/x"), Path::new("/home/b/c.txt")),
+            Some(PathBuf::from("../../b/c.txt"))
+        );
+        assert_eq!(get_lexical_relative_path(Path::new("/"), Path::new("/c.txt")), Some(PathBuf::from("c.txt")));
+        assert_eq!(get_lexical_relative_path(Path::new("/home/a/../b"), Path::new("/home/c.txt")), None);
+        assert_eq!(get_lexical_relative_path(Path::new("relative"), Path::new("/home/c.txt")), None);
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_get_relative_path_through_symlinked_folder() {
+        let dir = tempdir().expect("Cannot create temporary directory");
+        let real_dir = dir.path().join("a").join("b").join("c");
+        fs::create_dir_all(&real_dir).unwrap();
+        fs::create_dir(dir.path().join("other")).unwrap();
+        let original = dir.path().join("other").join("file.txt");
+        fs::write(&original, "original").unwrap();
+        let linked_dir = dir.path().join("link");
+        std::os::unix::fs::symlink(&real_dir, &linked_dir).unwrap();
+
+        let relative = get_relative_path(&linked_dir, &original).unwrap();
+        assert_eq!(relative, PathBuf::from("../../../other/file.txt"));
+        assert_eq!(fs::read_to_string(linked_dir.join(relative)).unwrap(), "original");
+
+        assert_eq!(get_relative_path(&linked_dir, &dir.path().join("missing.txt")), None);
+    }
+
     #[test]
     fn test_remove_folder_if_contains_only_empty_folders() {
         let dir = tempdir().expect("Cannot create temporary directory");
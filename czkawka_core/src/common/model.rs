@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
+use sha2::Digest;
 use xxhash_rust::xxh3::Xxh3;
 
 use crate::common::traits::ResultEntry;
@@ -9,6 +10,7 @@ use crate::tools::duplicate::MyHasher;
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
 pub enum ToolType {
     Duplicate,
+    DuplicateFolders,
     EmptyFolders,
     EmptyFiles,
     InvalidSymlinks,
@@ -65,12 +67,16 @@ impl ResultEntry for FileEntry {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Copy, Default)]
+#[derive(PartialEq, Eq, Clone, Debug, Copy, Default, Serialize, Deserialize)]
 pub enum HashType {
     #[default]
     Blake3,
     Crc32,
     Xxh3,
+    // Cryptographic digests, slower, but produce the same hashes as `sha256sum`, `sha1sum` and `md5sum`
+    Sha256,
+    Sha1,
+    Md5,
 }
 
 impl HashType {
@@ -79,6 +85,9 @@ impl HashType {
             Self::Blake3 => Box::new(blake3::Hasher::new()),
             Self::Crc32 => Box::new(crc32fast::Hasher::new()),
             Self::Xxh3 => Box::new(Xxh3::new()),
+            Self::Sha256 => Box::new(sha2::Sha256::new()),
+            Self::Sha1 => Box::new(sha1::Sha1::new()),
+            Self::Md5 => Box::new(md5::Md5::new()),
         }
     }
 }
@@ -121,6 +130,21 @@ mod tests {
         assert!(std::mem::size_of_val(&xxh3_hasher) > 0);
     }
 
+    #[test]
+    fn test_cryptographic_hashes_match_checksum_tools() {
+        let expected = [
+            (HashType::Sha256, "a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e"),
+            (HashType::Sha1, "0a4d55a8d778e5022fab701977c5d840bbc486d0"),
+            (HashType::Md5, "b10a8db164e0754105b7a99be72e3fe5"),
+        ];
+        for (hash_type, expected_hash) in expected {
+            let mut hasher = hash_type.hasher();
+            hasher.update(b"Hello ");
+            hasher.update(b"World");
+            assert_eq!(hasher.finalize(), expected_hash, "{hash_type:?}");
+        }
+    }
+
     #[test]
     fn test_checking_method_default() {
         assert_eq!(CheckingMethod::default(), CheckingMethod::None);
@@ -0,0 +1,339 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::{debug, info};
+
+use crate::common::date_filter::DateFilter;
+use crate::common::dir_traversal::{CollectedFiles, DirTraversalBuilder, DirTraversalResult};
+use crate::common::extensions::Extensions;
+use crate::common::progress_data::ProgressData;
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::common::traits::Search;
+use crate::tools::big_file::BigFile;
+use crate::tools::broken_files::BrokenFiles;
+use crate::tools::duplicate::DuplicateFinder;
+use crate::tools::empty_files::EmptyFiles;
+use crate::tools::temporary::Temporary;
+
+/// Tool which may get its files from traversal shared with other tools.
+pub enum MultiScanTool {
+    Duplicates(Box<DuplicateFinder>),
+    EmptyFiles(EmptyFiles),
+    BigFile(BigFile),
+    BrokenFiles(BrokenFiles),
+    Temporary(Temporary),
+}
+
+impl MultiScanTool {
+    pub fn get_cd(&self) -> &CommonToolData {
+        match self {
+            Self::Duplicates(tool) => tool.get_cd(),
+            Self::EmptyFiles(tool) => tool.get_cd(),
+            Self::BigFile(tool) => tool.get_cd(),
+            Self::BrokenFiles(tool) => tool.get_cd(),
+            Self::Temporary(tool) => tool.get_cd(),
+        }
+    }
+
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        match self {
+            Self::Duplicates(tool) => tool.get_cd_mut(),
+            Self::EmptyFiles(tool) => tool.get_cd_mut(),
+            Self::BigFile(tool) => tool.get_cd_mut(),
+            Self::BrokenFiles(tool) => tool.get_cd_mut(),
+            Self::Temporary(tool) => tool.get_cd_mut(),
+        }
+    }
+
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        match self {
+            Self::Duplicates(tool) => tool.search(stop_flag, progress_sender),
+            Self::EmptyFiles(tool) => tool.search(stop_flag, progress_sender),
+            Self::BigFile(tool) => tool.search(stop_flag, progress_sender),
+            Self::BrokenFiles(tool) => tool.search(stop_flag, progress_sender),
+            Self::Temporary(tool) => tool.search(stop_flag, progress_sender),
+        }
+    }
+
+    // Extensions used later by tool, None when tool will not be able to start search
+    fn traversal_extensions(&self) -> Option<Extensions> {
+        let mut extensions = self.get_cd().extensions.clone();
+        let result = match self {
+            Self::Duplicates(_) | Self::EmptyFiles(_) | Self::BigFile(_) => extensions.set_and_validate_extensions(None),
+            Self::BrokenFiles(tool) => extensions.set_and_validate_extensions(Some(&tool.checked_extensions())),
+            // Temporary files are matched by name suffix, not by extension
+            Self::Temporary(_) => return Some(Extensions::default()),
+        };
+        result.ok().map(|()| extensions)
+    }
+
+    fn traversal_settings(&self) -> TraversalSettings {
+        let common_data = self.get_cd();
+        let mut settings = TraversalSettings {
+            included_directories: common_data.directories.original_included_paths.clone(),
+            excluded_directories: common_data.directories.original_excluded_paths.clone(),
+            exclude_other_filesystems: common_data.directories.exclude_other_filesystems,
+            excluded_items: common_data.excluded_items.get_excluded_items().clone(),
+            recursive_search: common_data.recursive_search,
+            respect_gitignore: common_data.respect_gitignore,
+            date_filter: common_data.date_filter,
+            use_dir_snapshot: common_data.use_dir_snapshot,
+            hide_hard_links: common_data.hide_hard_links,
+        };
+        // Temporary files tool never used these filters
+        if let Self::Temporary(_) = self {
+            settings.respect_gitignore = false;
+            settings.date_filter = DateFilter::default();
+        }
+        settings
+    }
+}
+
+// Tools can share traversal only when it would visit exactly the same files for each of them
+#[derive(PartialEq, Eq)]
+struct TraversalSettings {
+    included_directories: Vec<PathBuf>,
+    excluded_directories: Vec<PathBuf>,
+    exclude_other_filesystems: Option<bool>,
+    excluded_items: Vec<String>,
+    recursive_search: bool,
+    respect_gitignore: bool,
+    date_filter: DateFilter,
+    use_dir_snapshot: bool,
+    hide_hard_links: bool,
+}
+
+/// Runs several tools, but walks through directories only once.
+///
+/// Files are collected with union of extension filters of all tools, then every tool filters them by itself
+/// and continues its search as usual, so each of them keeps its own results and messages.
+/// Tools which search in other directories or with other excluded items than the first tool, collect files on their own.
+pub struct MultiScan {
+    tools: Vec<MultiScanTool>,
+}
+
+impl MultiScan {
+    pub fn new(tools: Vec<MultiScanTool>) -> Self {
+        Self { tools }
+    }
+
+    pub fn get_tools(&self) -> &[MultiScanTool] {
+        &self.tools
+    }
+
+    pub fn into_tools(self) -> Vec<MultiScanTool> {
+        self.tools
+    }
+
+    // Indexes of tools which may use shared traversal
+    fn sharing_tools(&self) -> Vec<(usize, Extensions)> {
+        let Some(first_settings) = self.tools.first().map(MultiScanTool::traversal_settings) else {
+            return Vec::new();
+        };
+        self.tools
+            .iter()
+            .enumerate()
+            .filter(|(_, tool)| tool.traversal_settings() == first_settings)
+            .filter_map(|(idx, tool)| tool.traversal_extensions().map(|extensions| (idx, extensions)))
+            .collect()
+    }
+
+    #[fun_time(message = "collect_shared_files", level = "debug")]
+    fn collect_shared_files(&self, sharing_tools: &[(usize, Extensions)], stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> Option<CollectedFiles> {
+        let (first_idx, _) = sharing_tools.first()?;
+        let first_tool = self.tools.get(*first_idx)?;
+        let settings = first_tool.traversal_settings();
+        let mut common_data = first_tool.get_cd().clone();
+        common_data.respect_gitignore = settings.respect_gitignore;
+        common_data.date_filter = settings.date_filter;
+        common_data.collected_files = None;
+        if common_data.directories.optimize_directories(common_data.recursive_search, false).is_err() {
+            // Every tool will report the same problem by itself
+            return Some(CollectedFiles::default());
+        }
+
+        let extensions = sharing_tools.iter().map(|(_, extensions)| extensions.clone()).collect::<Vec<_>>();
+        let result = DirTraversalBuilder::new()
+            .common_data(&common_data)
+            .extensions(Extensions::union(&extensions))
+            .minimal_file_size(0)
+            .maximal_file_size(u64::MAX)
+            .group_by(|_fe| ())
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => Some(CollectedFiles {
+                files: grouped_file_entries.into_values().flatten().collect(),
+                warnings,
+            }),
+            DirTraversalResult::Stopped => None,
+        }
+    }
+}
+
+impl Search for MultiScan {
+    #[fun_time(message = "multi_scan", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let sharing_tools = self.sharing_tools();
+        let collected_files = if sharing_tools.len() > 1 {
+            let Some(collected_files) = self.collect_shared_files(&sharing_tools, stop_flag, progress_sender) else {
+                for tool in &mut self.tools {
+                    tool.get_cd_mut().stopped_search = true;
+                }
+                return;
+            };
+            debug!("Collected {} files shared by {} tools", collected_files.files.len(), sharing_tools.len());
+            Some(Arc::new(collected_files))
+        } else {
+            None
+        };
+
+        for (idx, tool) in self.tools.iter_mut().enumerate() {
+            if check_if_stop_received(stop_flag) {
+                tool.get_cd_mut().stopped_search = true;
+                continue;
+            }
+            if sharing_tools.iter().any(|(sharing_idx, _)| *sharing_idx == idx) {
+                tool.get_cd_mut().collected_files.clone_from(&collected_files);
+            } else {
+                info!("Tool {:?} uses different search settings, so it collects files by itself", tool.get_cd().tool_type);
+            }
+            tool.search(stop_flag, progress_sender);
+            tool.get_cd_mut().collected_files = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::common::model::{CheckingMethod, HashType};
+    use crate::tools::big_file::{BigFileParameters, SearchMode};
+    use crate::tools::broken_files::{BrokenFilesParameters, CheckedTypes};
+    use crate::tools::duplicate::DuplicateFinderParameters;
+    use crate::tools::empty_files::EmptyFilesParameters;
+    use crate::tools::temporary::TemporaryParameters;
+
+    fn create_tools(path: &std::path::Path) -> Vec<MultiScanTool> {
+        let mut duplicates = DuplicateFinder::new(DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, false));
+        let mut empty_files = EmptyFiles::new(EmptyFilesParameters::default());
+        let mut big_files = BigFile::new(BigFileParameters::new(1, SearchMode::BiggestFiles));
+        let mut broken_files = BrokenFiles::new(BrokenFilesParameters::new(CheckedTypes::PDF));
+        let mut temporary = Temporary::new(TemporaryParameters::default());
+        duplicates.set_included_paths(vec![path.to_path_buf()]);
+        duplicates.set_use_cache(false);
+        empty_files.set_included_paths(vec![path.to_path_buf()]);
+        big_files.set_included_paths(vec![path.to_path_buf()]);
+        broken_files.set_included_paths(vec![path.to_path_buf()]);
+        broken_files.set_use_cache(false);
+        temporary.set_included_paths(vec![path.to_path_buf()]);
+
+        vec![
+            MultiScanTool::Duplicates(Box::new(duplicates)),
+            MultiScanTool::EmptyFiles(empty_files),
+            MultiScanTool::BigFile(big_files),
+            MultiScanTool::BrokenFiles(broken_files),
+            MultiScanTool::Temporary(temporary),
+        ]
+    }
+
+    fn found_files(tool: &MultiScanTool) -> Vec<String> {
+        let mut files: Vec<String> = match tool {
+            MultiScanTool::Duplicates(tool) => tool
+                .get_files_sorted_by_hash()
+                .values()
+                .flatten()
+                .flatten()
+                .map(|e| e.path.to_string_lossy().to_string())
+                .collect(),
+            MultiScanTool::EmptyFiles(tool) => tool.get_empty_files().iter().map(|e| e.path.to_string_lossy().to_string()).collect(),
+            MultiScanTool::BigFile(tool) => tool.get_big_files().iter().map(|e| e.path.to_string_lossy().to_string()).collect(),
+            MultiScanTool::BrokenFiles(tool) => tool.get_broken_files().iter().map(|e| e.path.to_string_lossy().to_string()).collect(),
+            MultiScanTool::Temporary(tool) => tool.get_temporary_files().iter().map(|e| e.path.to_string_lossy().to_string()).collect(),
+        };
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_multi_scan_same_results_as_separate_tools() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        fs::create_dir(path.join("sub")).unwrap();
+        fs::write(path.join("empty.txt"), b"").unwrap();
+        fs::write(path.join("a.bin"), vec![b'A'; 100]).unwrap();
+        fs::write(path.join("sub").join("b.bin"), vec![b'A'; 100]).unwrap();
+        fs::write(path.join("big.dat"), vec![b'B'; 1000]).unwrap();
+        fs::write(path.join("sub").join("broken.pdf"), b"not a pdf").unwrap();
+        fs::write(path.join("file.tmp"), b"temporary").unwrap();
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let mut separate_tools = create_tools(path);
+        for tool in &mut separate_tools {
+            tool.search(&stop_flag, None);
+        }
+
+        let mut multi_scan = MultiScan::new(create_tools(path));
+        assert_eq!(multi_scan.sharing_tools().len(), 5);
+        multi_scan.search(&stop_flag, None);
+
+        let expected = [vec!["a.bin", "sub/b.bin"], vec!["empty.txt"], vec!["big.dat"], vec!["sub/broken.pdf"], vec!["file.tmp"]];
+        for ((separate_tool, shared_tool), expected) in separate_tools.iter().zip(multi_scan.get_tools()).zip(expected) {
+            let expected: Vec<String> = expected.iter().map(|name| path.join(name).to_string_lossy().to_string()).collect();
+            assert_eq!(found_files(separate_tool), expected);
+            assert_eq!(found_files(shared_tool), expected);
+            assert!(!shared_tool.get_cd().stopped_search);
+            assert!(shared_tool.get_cd().collected_files.is_none());
+        }
+    }
+
+    #[test]
+    fn test_multi_scan_different_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        fs::write(path.join("empty.txt"), b"").unwrap();
+        fs::write(path.join("file.tmp"), b"temporary").unwrap();
+
+        let mut tools = create_tools(path);
+        if let Some(MultiScanTool::Temporary(temporary)) = tools.last_mut() {
+            temporary.set_excluded_items(vec!["*.tmp".to_string()]);
+        }
+        let mut multi_scan = MultiScan::new(tools);
+        assert_eq!(multi_scan.sharing_tools().iter().map(|(idx, _)| *idx).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        multi_scan.search(&stop_flag, None);
+        assert_eq!(found_files(&multi_scan.get_tools()[1]), vec![path.join("empty.txt").to_string_lossy().to_string()]);
+        assert!(found_files(&multi_scan.get_tools()[4]).is_empty());
+
+        let mut tools = create_tools(path);
+        if let Some(MultiScanTool::EmptyFiles(empty_files)) = tools.get_mut(1) {
+            empty_files.set_hide_hard_links(true);
+        }
+        if let Some(MultiScanTool::BigFile(big_files)) = tools.get_mut(2) {
+            big_files.set_use_dir_snapshot(true);
+        }
+        let multi_scan = MultiScan::new(tools);
+        assert_eq!(multi_scan.sharing_tools().iter().map(|(idx, _)| *idx).collect::<Vec<_>>(), vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn test_multi_scan_stopped() {
+        let temp_dir = TempDir::new().unwrap();
+        let stop_flag = Arc::new(AtomicBool::new(true));
+        let mut multi_scan = MultiScan::new(create_tools(temp_dir.path()));
+        multi_scan.search(&stop_flag, None);
+        assert!(multi_scan.get_tools().iter().all(|tool| tool.get_cd().stopped_search));
+    }
+}
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::ffi::OsString;
-use std::path::{Path, PathBuf};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 
 use crate::common::items::SingleExcludedItem;
 
@@ -19,6 +20,39 @@ pub fn split_path_compare(path_a: &Path, path_b: &Path) -> Ordering {
     }
 }
 
+// Both paths must exist, they are canonicalized first, because ".." in relative path is resolved from real location of folder,
+// not from path containing symlinks. None is returned when they have different roots, e.g. different Windows drives
+pub fn get_relative_path(from_dir: &Path, to: &Path) -> Option<PathBuf> {
+    let from_dir = fs::canonicalize(from_dir).ok()?;
+    let to = fs::canonicalize(to).ok()?;
+    get_lexical_relative_path(&from_dir, &to)
+}
+
+// Both paths should be absolute and without symlinks
+pub(crate) fn get_lexical_relative_path(from_dir: &Path, to: &Path) -> Option<PathBuf> {
+    let mut from_components = from_dir.components().peekable();
+    let mut to_components = to.components().peekable();
+    if from_components.peek() != to_components.peek() {
+        return None;
+    }
+    while from_components.peek().is_some() && from_components.peek() == to_components.peek() {
+        from_components.next();
+        to_components.next();
+    }
+
+    let mut relative = PathBuf::new();
+    for component in from_components {
+        match component {
+            Component::Normal(_) => relative.push(".."),
+            Component::CurDir => {}
+            // Cannot go back from "..", prefix or root
+            _ => return None,
+        }
+    }
+    relative.extend(to_components);
+    Some(relative)
+}
+
 #[expect(clippy::string_slice)]
 #[expect(clippy::indexing_slicing)]
 pub fn regex_check(expression_item: &SingleExcludedItem, directory_name: &str) -> bool {
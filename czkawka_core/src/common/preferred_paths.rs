@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use crate::common::items::{SingleExcludedItem, new_excluded_item};
+use crate::common::regex_check;
+
+/// Ordered list of locations, which should be kept when choosing between duplicates.
+/// Earlier entries have higher priority, e.g. `["/photos/originals", "/backup"]` keeps files from originals before backup.
+/// Entry containing `*` is matched as wildcard like excluded items, otherwise as path prefix.
+#[derive(Debug, Clone, Default)]
+pub struct PreferredPaths {
+    items: Vec<PreferredPath>,
+}
+
+#[derive(Debug, Clone)]
+enum PreferredPath {
+    Prefix(PathBuf),
+    Wildcard(SingleExcludedItem),
+}
+
+impl PreferredPaths {
+    pub fn new(preferred_paths: Vec<String>) -> Self {
+        let items = preferred_paths
+            .into_iter()
+            .filter_map(|item| {
+                let item = item.trim().to_string();
+                if item.is_empty() {
+                    return None;
+                }
+                #[cfg(target_family = "windows")]
+                let item = item.replace("/", "\\").to_ascii_lowercase();
+
+                if item.contains('*') {
+                    Some(PreferredPath::Wildcard(new_excluded_item(&item)))
+                } else {
+                    Some(PreferredPath::Prefix(PathBuf::from(item)))
+                }
+            })
+            .collect();
+        Self { items }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Lower value means more preferred path, not matched paths get the lowest priority.
+    pub fn rank(&self, path: &Path) -> usize {
+        if self.items.is_empty() {
+            return 0;
+        }
+        #[cfg(target_family = "windows")]
+        let path = crate::common::normalize_windows_path(path);
+        #[cfg(target_family = "windows")]
+        let path = path.as_path();
+
+        let path_str = path.to_string_lossy();
+        self.items
+            .iter()
+            .position(|item| match item {
+                PreferredPath::Prefix(prefix) => path.starts_with(prefix),
+                PreferredPath::Wildcard(expression) => regex_check(expression, &path_str),
+            })
+            .unwrap_or(self.items.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_preferred_paths_rank() {
+        let preferred = PreferredPaths::new(vec!["/photos/originals".to_string(), " ".to_string(), "*/backup/*".to_string()]);
+        assert!(!preferred.is_empty());
+        assert_eq!(preferred.rank(Path::new("/photos/originals/a.jpg")), 0);
+        assert_eq!(preferred.rank(Path::new("/mnt/backup/originals/a.jpg")), 1);
+        assert_eq!(preferred.rank(Path::new("/photos/originals_old/a.jpg")), 2);
+        assert_eq!(preferred.rank(Path::new("/home/a.jpg")), 2);
+
+        let empty = PreferredPaths::new(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.rank(Path::new("/home/a.jpg")), 0);
+    }
+}
@@ -1,15 +1,16 @@
 use humansize::{BINARY, format_size};
+use serde::Serialize;
 
 use crate::common::model::CheckingMethod;
 use crate::flc;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum CacheLoadPhase {
     Loading,
     FilteringOutdated,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum DuplicateStage {
     HidingHardLinks,
     LoadingPreHashCache(CacheLoadPhase),
@@ -20,13 +21,13 @@ pub enum DuplicateStage {
     SavingHashCache,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum SameMusicMode {
     AudioTags,
     AudioContent,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum SameMusicStage {
     LoadingTagsCache(CacheLoadPhase),
     ReadingTags,
@@ -38,20 +39,20 @@ pub enum SameMusicStage {
     ComparingFingerprints,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum SimilarImagesStage {
     HidingHardLinks,
     CalculatingHashes,
     ComparingHashes,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum SimilarVideosMode {
     VisualHash,
     AudioContent,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum SimilarVideosStage {
     HidingHardLinks,
     CalculatingHashes,
@@ -63,20 +64,20 @@ pub enum SimilarVideosStage {
     CreatingAudioThumbnails,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum ExifRemoverStage {
     LoadingCache(CacheLoadPhase),
     ExtractingTags,
     SavingCache,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum VideoOptimizerStage {
     ProcessingVideos,
     CreatingThumbnails,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum ToolStage {
     CollectingFiles(CheckingMethod),
     CollectingFolders,
@@ -85,6 +86,7 @@ pub enum ToolStage {
     RenamingFiles,
     MovingFiles,
     HardlinkingFiles,
+    ReflinkingFiles,
     SymlinkingFiles,
     OptimizingVideos,
     CleaningExif,
@@ -106,7 +108,14 @@ impl ToolStage {
     pub fn is_special_non_tool_stage(self) -> bool {
         matches!(
             self,
-            Self::DeletingFiles | Self::RenamingFiles | Self::MovingFiles | Self::HardlinkingFiles | Self::SymlinkingFiles | Self::OptimizingVideos | Self::CleaningExif
+            Self::DeletingFiles
+                | Self::RenamingFiles
+                | Self::MovingFiles
+                | Self::HardlinkingFiles
+                | Self::ReflinkingFiles
+                | Self::SymlinkingFiles
+                | Self::OptimizingVideos
+                | Self::CleaningExif
         )
     }
 
@@ -179,6 +188,7 @@ impl ToolStage {
             | Self::RenamingFiles
             | Self::MovingFiles
             | Self::HardlinkingFiles
+            | Self::ReflinkingFiles
             | Self::SymlinkingFiles
             | Self::OptimizingVideos
             | Self::CleaningExif => 0,
@@ -248,6 +258,7 @@ impl ToolStage {
             | Self::RenamingFiles
             | Self::MovingFiles
             | Self::HardlinkingFiles
+            | Self::ReflinkingFiles
             | Self::SymlinkingFiles
             | Self::OptimizingVideos
             | Self::CleaningExif => 0,
@@ -420,6 +431,8 @@ impl ProgressData {
             ToolStage::MovingFiles => flc!("stage_moving_no_size_files", items_stats = items_stats),
             ToolStage::HardlinkingFiles if has_size => flc!("stage_hardlinking_files", items_stats = items_stats, size_stats = size_stats),
             ToolStage::HardlinkingFiles => flc!("stage_hardlinking_no_size_files", items_stats = items_stats),
+            ToolStage::ReflinkingFiles if has_size => flc!("stage_reflinking_files", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::ReflinkingFiles => flc!("stage_reflinking_no_size_files", items_stats = items_stats),
             ToolStage::SymlinkingFiles if has_size => flc!("stage_symlinking_files", items_stats = items_stats, size_stats = size_stats),
             ToolStage::SymlinkingFiles => flc!("stage_symlinking_no_size_files", items_stats = items_stats),
             ToolStage::OptimizingVideos if has_size => flc!("stage_optimizing_videos", items_stats = items_stats, size_stats = size_stats),
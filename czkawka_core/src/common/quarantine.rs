@@ -0,0 +1,314 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use filetime::FileTime;
+use log::info;
+
+use crate::common::journal::{Journal, JournalAction, JournalEntry, JournalStatus, load_journal, move_back};
+use crate::common::traits::ResultEntry;
+use crate::helpers::messages::Messages;
+
+// Manifest uses journal format - each quarantined file is stored as Move entry with quarantined path as destination
+pub const QUARANTINE_MANIFEST_FILE: &str = "czkawka_quarantine_manifest.jsonl";
+
+/// Folder to which files are moved instead of being deleted.
+/// Original directory structure is mirrored inside, e.g. `/home/user/a.txt` lands in `<root>/home/user/a.txt`.
+#[derive(Debug)]
+pub struct Quarantine {
+    root: PathBuf,
+    manifest: Journal,
+}
+
+impl Quarantine {
+    pub fn open(root: &Path) -> Result<Self, String> {
+        fs::create_dir_all(root).map_err(|e| format!("Cannot create quarantine folder \"{}\", reason {e}", root.to_string_lossy()))?;
+        let manifest = Journal::open(&root.join(QUARANTINE_MANIFEST_FILE))?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // Moves item to path returned by `free_quarantined_path`
+    pub fn move_item<T: ResultEntry>(&self, entry: &T, quarantined_path: &Path) -> Result<(), String> {
+        let path = entry.get_path();
+        if let Some(parent) = quarantined_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Cannot create folder \"{}\" in quarantine, reason {e}", parent.to_string_lossy()))?;
+        }
+        // Without manifest entry file could be only restored manually, so it is saved before moving
+        let manifest_entry = self.manifest.start(JournalAction::Move, entry, Some(quarantined_path))?;
+        let result = move_item(path, quarantined_path).map_err(|e| format!("Cannot move \"{}\" to quarantine, reason {e}", path.to_string_lossy()));
+        let manifest_result = self.manifest.finish(manifest_entry, result.is_ok());
+        result.and(manifest_result)
+    }
+
+    // Same file may be quarantined multiple times, e.g. when it was recreated after previous cleaning
+    pub fn free_quarantined_path(&self, path: &Path) -> PathBuf {
+        let mirrored = mirrored_path(&self.root, path);
+        if fs::symlink_metadata(&mirrored).is_err() {
+            return mirrored;
+        }
+        let file_name = mirrored.file_name().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let mut idx = 1;
+        loop {
+            let candidate = mirrored.with_file_name(format!("{file_name}.{idx}"));
+            if fs::symlink_metadata(&candidate).is_err() {
+                return candidate;
+            }
+            idx += 1;
+        }
+    }
+}
+
+// Windows prefixes like `C:` are changed into `C` folder, so paths from different drives do not collide
+pub(crate) fn mirrored_path(root: &Path, path: &Path) -> PathBuf {
+    let mut mirrored = root.to_path_buf();
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => {
+                let prefix = prefix.as_os_str().to_string_lossy().replace([':', '\\', '/', '?'], "");
+                if !prefix.is_empty() {
+                    mirrored.push(prefix);
+                }
+            }
+            Component::Normal(part) => mirrored.push(part),
+            // Paths are absolute, so ".." would only allow to escape from quarantine
+            Component::RootDir | Component::CurDir | Component::ParentDir => {}
+        }
+    }
+    mirrored
+}
+
+fn move_item(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    // Quarantine may be located on different drive than scanned files
+    let metadata = fs::symlink_metadata(from)?;
+    if !metadata.is_file() {
+        return fs::rename(from, to);
+    }
+    fs::copy(from, to)?;
+    filetime::set_file_mtime(to, FileTime::from_last_modification_time(&metadata))?;
+    if let Err(e) = fs::remove_file(from) {
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RestoreResult {
+    pub restored_entries: usize,
+    pub failed_entries: usize,
+    pub messages: Messages,
+}
+
+/// Moves every item from quarantine back to its original location.
+/// Restored entries are removed from manifest, so restore may be safely repeated after fixing reported problems.
+pub fn restore_quarantine(root: &Path, dry_run: bool) -> RestoreResult {
+    let mut restore_result = RestoreResult::default();
+    let manifest_path = root.join(QUARANTINE_MANIFEST_FILE);
+
+    let entries = match load_journal(&manifest_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            restore_result.messages.critical = Some(e);
+            return restore_result;
+        }
+    };
+
+    info!("Restoring {} items from quarantine \"{}\", dry_run: {dry_run}", entries.len(), root.to_string_lossy());
+
+    let mut not_restored = Vec::new();
+    for entry in entries.into_iter().rev() {
+        let entry_path = entry.path.to_string_lossy().to_string();
+        let Some(quarantined_path) = entry.destination.clone().filter(|_| entry.action == JournalAction::Move) else {
+            restore_result.failed_entries += 1;
+            restore_result.messages.errors.push(format!("Invalid quarantine manifest entry for \"{entry_path}\""));
+            not_restored.push(entry);
+            continue;
+        };
+
+        // Moving was interrupted before file reached quarantine, so there is nothing to restore
+        if entry.status == JournalStatus::Started && fs::symlink_metadata(&quarantined_path).is_err() && fs::symlink_metadata(&entry.path).is_ok() {
+            continue;
+        }
+
+        if dry_run {
+            restore_result.restored_entries += 1;
+            restore_result
+                .messages
+                .messages
+                .push(format!("Would restore: \"{}\" to \"{entry_path}\"", quarantined_path.to_string_lossy()));
+            continue;
+        }
+
+        let result = if fs::symlink_metadata(&entry.path).is_ok() {
+            Err(format!("Cannot restore \"{entry_path}\", file already exists"))
+        } else if fs::symlink_metadata(&quarantined_path).is_err() {
+            Err(format!("Cannot restore \"{entry_path}\", \"{}\" no longer exists", quarantined_path.to_string_lossy()))
+        } else {
+            move_back(&quarantined_path, &entry.path).map_err(|e| format!("Failed to restore \"{entry_path}\", reason {e}"))
+        };
+        match result {
+            Ok(()) => restore_result.restored_entries += 1,
+            Err(e) => {
+                restore_result.failed_entries += 1;
+                restore_result.messages.errors.push(e);
+                not_restored.push(entry);
+            }
+        }
+    }
+
+    if !dry_run {
+        not_restored.reverse();
+        if let Err(e) = save_manifest(&manifest_path, &not_restored) {
+            restore_result.messages.errors.push(e);
+        }
+        info!(
+            "{} items restored from quarantine, {} failed",
+            restore_result.restored_entries, restore_result.failed_entries
+        );
+    }
+
+    restore_result
+}
+
+fn save_manifest(manifest_path: &Path, entries: &[JournalEntry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return fs::remove_file(manifest_path).map_err(|e| format!("Cannot remove quarantine manifest \"{}\", reason {e}", manifest_path.to_string_lossy()));
+    }
+    let mut content = Vec::new();
+    for entry in entries {
+        let line = serde_json::to_string(entry).map_err(|e| format!("Cannot serialize quarantine manifest entry, reason {e}"))?;
+        let _ = writeln!(content, "{line}");
+    }
+    fs::write(manifest_path, content).map_err(|e| format!("Cannot save quarantine manifest \"{}\", reason {e}", manifest_path.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::common::model::FileEntry;
+
+    fn file_entry(path: &Path) -> FileEntry {
+        FileEntry {
+            path: path.to_path_buf(),
+            size: fs::metadata(path).unwrap().len(),
+            modified_date: 0,
+        }
+    }
+
+    fn quarantine_file(quarantine: &Quarantine, path: &Path) -> PathBuf {
+        let quarantined_path = quarantine.free_quarantined_path(path);
+        quarantine.move_item(&file_entry(path), &quarantined_path).unwrap();
+        quarantined_path
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_mirrored_path() {
+        assert_eq!(mirrored_path(Path::new("/q"), Path::new("/home/a/b.txt")), PathBuf::from("/q/home/a/b.txt"));
+        assert_eq!(mirrored_path(Path::new("/q"), Path::new("/home/../../etc/b.txt")), PathBuf::from("/q/home/etc/b.txt"));
+    }
+
+    #[test]
+    fn test_quarantine_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        let quarantine_dir = temp_dir.path().join("quarantine");
+        let file1 = data_dir.join("a").join("file1.txt");
+        let file2 = data_dir.join("file2.txt");
+        fs::create_dir_all(file1.parent().unwrap()).unwrap();
+        fs::write(&file1, "first").unwrap();
+        fs::write(&file2, "second").unwrap();
+
+        let quarantine = Quarantine::open(&quarantine_dir).unwrap();
+        let quarantined1 = quarantine_file(&quarantine, &file1);
+        let quarantined2 = quarantine_file(&quarantine, &file2);
+        drop(quarantine);
+
+        assert!(!file1.exists());
+        assert_eq!(quarantined1, mirrored_path(&quarantine_dir, &file1));
+        assert_eq!(fs::read_to_string(&quarantined1).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&quarantined2).unwrap(), "second");
+
+        // Restoring is blocked for file recreated in original location, but others should be restored
+        fs::write(&file2, "new").unwrap();
+        let dry_result = restore_quarantine(&quarantine_dir, true);
+        assert_eq!(dry_result.restored_entries, 2);
+        assert!(quarantined1.exists());
+
+        let result = restore_quarantine(&quarantine_dir, false);
+        assert_eq!(result.restored_entries, 1);
+        assert_eq!(result.failed_entries, 1);
+        assert_eq!(fs::read_to_string(&file1).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&file2).unwrap(), "new");
+
+        let remaining = load_journal(&quarantine_dir.join(QUARANTINE_MANIFEST_FILE)).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].path, file2);
+
+        fs::remove_file(&file2).unwrap();
+        let result = restore_quarantine(&quarantine_dir, false);
+        assert_eq!(result.restored_entries, 1);
+        assert_eq!(fs::read_to_string(&file2).unwrap(), "second");
+        assert!(!quarantine_dir.join(QUARANTINE_MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn test_quarantine_same_path_twice() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("data").join("file.txt");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        let quarantine = Quarantine::open(&temp_dir.path().join("quarantine")).unwrap();
+
+        fs::write(&file, "first").unwrap();
+        let first = quarantine_file(&quarantine, &file);
+        fs::write(&file, "second").unwrap();
+        let second = quarantine_file(&quarantine, &file);
+
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "second");
+    }
+
+    #[test]
+    fn test_quarantine_manifest_saved_before_moving() {
+        let temp_dir = TempDir::new().unwrap();
+        let quarantine_dir = temp_dir.path().join("quarantine");
+        let file = temp_dir.path().join("data").join("file.txt");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "abc").unwrap();
+        let entry = file_entry(&file);
+        let quarantine = Quarantine::open(&quarantine_dir).unwrap();
+
+        // Failed move is not restored
+        let missing = FileEntry {
+            path: temp_dir.path().join("data").join("missing.txt"),
+            ..file_entry(&file)
+        };
+        quarantine.move_item(&missing, &quarantine.free_quarantined_path(&missing.path)).unwrap_err();
+        assert!(load_journal(&quarantine_dir.join(QUARANTINE_MANIFEST_FILE)).unwrap().is_empty());
+
+        // Process killed just after saving manifest entry, before moving file
+        let _interrupted = quarantine
+            .manifest
+            .start(JournalAction::Move, &entry, Some(&quarantine.free_quarantined_path(&file)))
+            .unwrap();
+        drop(quarantine);
+
+        let result = restore_quarantine(&quarantine_dir, false);
+        assert_eq!((result.restored_entries, result.failed_entries), (0, 0));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "abc");
+        assert!(!quarantine_dir.join(QUARANTINE_MANIFEST_FILE).exists());
+    }
+}
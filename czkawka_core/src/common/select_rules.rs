@@ -0,0 +1,506 @@
+//! Small language used to choose which items of a group should be deleted.
+//!
+//! Rule has form `keep where <condition>` or `delete where <condition>`, e.g.
+//! `keep where path ~ "/raw/" or (width * height max)`.
+//!
+//! Conditions:
+//! - `a == b`, `a != b`, `a < b`, `a <= b`, `a > b`, `a >= b` - compare numbers or texts
+//! - `text ~ "pattern"` - case-insensitive check if text contains pattern, pattern with `*` is matched as wildcard
+//! - `value max`, `value min` - true for items with the biggest/smallest value in group
+//! - `and`, `or`, `not` and parentheses
+//!
+//! Values may be numbers, quoted texts, fields and arithmetic (`+ - * /`) on numbers.
+//! Fields available for every item are `path`, `name`, `folder`, `extension`, `size`(bytes) and `modified`(unix timestamp),
+//! tools may provide additional ones, e.g. `width` and `height` for similar images.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::common::items::new_excluded_item;
+use crate::common::regex_check;
+use crate::common::traits::ResultEntry;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleValue {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Keep,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub struct SelectionRule {
+    source: String,
+    action: RuleAction,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Or(Box<Self>, Box<Self>),
+    And(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+    Compare(Expr, CompareOp, Expr),
+    Contains(Expr, String),
+    Extreme(Expr, bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Value(RuleValue),
+    Field(String),
+    Arithmetic(Box<Self>, char, Box<Self>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Text(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: &[&str] = &["==", "!=", "<=", ">=", "<", ">", "~", "(", ")", "+", "-", "*", "/"];
+const KEYWORDS: &[&str] = &["keep", "delete", "where", "and", "or", "not", "max", "min"];
+
+impl SelectionRule {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let tokens = tokenize(rule)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        let action = if parser.eat_keyword("keep") {
+            RuleAction::Keep
+        } else if parser.eat_keyword("delete") {
+            RuleAction::Delete
+        } else {
+            return Err("Rule must start with \"keep where\" or \"delete where\"".to_string());
+        };
+        if !parser.eat_keyword("where") {
+            return Err(format!("Expected \"where\" after \"{}\"", if action == RuleAction::Keep { "keep" } else { "delete" }));
+        }
+        let condition = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {} at the end of rule", describe_token(token)));
+        }
+
+        Ok(Self {
+            source: rule.trim().to_string(),
+            action,
+            condition,
+        })
+    }
+
+    pub fn action(&self) -> RuleAction {
+        self.action
+    }
+
+    /// Returns for every item of group, if it should be selected(deleted).
+    /// With `leave_one_in_group`, group in which rule would select every item is left untouched.
+    pub fn select_group<T: ResultEntry>(&self, group: &[T], leave_one_in_group: bool) -> Result<Vec<bool>, String> {
+        let matching = self.condition.evaluate(group)?;
+        let mut selected: Vec<bool> = match self.action {
+            RuleAction::Keep => matching.into_iter().map(|m| !m).collect(),
+            RuleAction::Delete => matching,
+        };
+        if leave_one_in_group && selected.iter().all(|s| *s) {
+            selected.fill(false);
+        }
+        Ok(selected)
+    }
+}
+
+impl FromStr for SelectionRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for SelectionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+fn entry_field<T: ResultEntry>(entry: &T, name: &str) -> Result<RuleValue, String> {
+    let path = entry.get_path();
+    let os_to_text = |value: Option<&std::ffi::OsStr>| RuleValue::Text(value.map(|e| e.to_string_lossy().to_string()).unwrap_or_default());
+    let value = match name {
+        "path" => RuleValue::Text(path.to_string_lossy().to_string()),
+        "name" => os_to_text(path.file_name()),
+        "folder" => os_to_text(path.parent().map(|e| e.as_os_str())),
+        "extension" => os_to_text(path.extension()),
+        "size" => RuleValue::Number(entry.get_size() as f64),
+        "modified" => RuleValue::Number(entry.get_modified_date() as f64),
+        _ => return entry.get_rule_field(name).ok_or_else(|| format!("Unknown field \"{name}\"")),
+    };
+    Ok(value)
+}
+
+impl Condition {
+    fn evaluate<T: ResultEntry>(&self, group: &[T]) -> Result<Vec<bool>, String> {
+        let result = match self {
+            Self::Or(left, right) => left.evaluate(group)?.into_iter().zip(right.evaluate(group)?).map(|(l, r)| l || r).collect(),
+            Self::And(left, right) => left.evaluate(group)?.into_iter().zip(right.evaluate(group)?).map(|(l, r)| l && r).collect(),
+            Self::Not(inner) => inner.evaluate(group)?.into_iter().map(|e| !e).collect(),
+            Self::Compare(left, op, right) => group
+                .iter()
+                .map(|entry| compare(&left.evaluate(entry)?, *op, &right.evaluate(entry)?))
+                .collect::<Result<_, _>>()?,
+            Self::Contains(value, pattern) => {
+                let pattern = pattern.to_lowercase();
+                let wildcard = pattern.contains('*').then(|| new_excluded_item(&pattern));
+                group
+                    .iter()
+                    .map(|entry| {
+                        let RuleValue::Text(text) = value.evaluate(entry)? else {
+                            return Err("Operator ~ can be used only with text".to_string());
+                        };
+                        let text = text.to_lowercase();
+                        Ok(match &wildcard {
+                            Some(wildcard) => regex_check(wildcard, &text),
+                            None => text.contains(&pattern),
+                        })
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            Self::Extreme(value, max) => {
+                let values = group
+                    .iter()
+                    .map(|entry| match value.evaluate(entry)? {
+                        RuleValue::Number(number) => Ok(number),
+                        RuleValue::Text(_) => Err(format!("{} can be used only with numbers", if *max { "max" } else { "min" })),
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                let extreme = values.iter().copied().fold(if *max { f64::MIN } else { f64::MAX }, if *max { f64::max } else { f64::min });
+                values.into_iter().map(|e| e.total_cmp(&extreme).is_eq()).collect()
+            }
+        };
+        Ok(result)
+    }
+}
+
+impl Expr {
+    fn evaluate<T: ResultEntry>(&self, entry: &T) -> Result<RuleValue, String> {
+        match self {
+            Self::Value(value) => Ok(value.clone()),
+            Self::Field(name) => entry_field(entry, name),
+            Self::Arithmetic(left, op, right) => {
+                let (RuleValue::Number(left), RuleValue::Number(right)) = (left.evaluate(entry)?, right.evaluate(entry)?) else {
+                    return Err(format!("Operator {op} can be used only with numbers"));
+                };
+                Ok(RuleValue::Number(match op {
+                    '+' => left + right,
+                    '-' => left - right,
+                    '*' => left * right,
+                    _ => left / right,
+                }))
+            }
+        }
+    }
+}
+
+fn compare(left: &RuleValue, op: CompareOp, right: &RuleValue) -> Result<bool, String> {
+    let ordering = match (left, right) {
+        (RuleValue::Number(left), RuleValue::Number(right)) => left.partial_cmp(right),
+        (RuleValue::Text(left), RuleValue::Text(right)) => Some(left.cmp(right)),
+        _ => return Err("Cannot compare number with text".to_string()),
+    };
+    let Some(ordering) = ordering else {
+        return Ok(op == CompareOp::NotEq);
+    };
+    Ok(match op {
+        CompareOp::Eq => ordering.is_eq(),
+        CompareOp::NotEq => ordering.is_ne(),
+        CompareOp::Lt => ordering.is_lt(),
+        CompareOp::Lte => ordering.is_le(),
+        CompareOp::Gt => ordering.is_gt(),
+        CompareOp::Gte => ordering.is_ge(),
+    })
+}
+
+fn tokenize(rule: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = rule.char_indices().peekable();
+    while let Some(&(idx, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, escaped)) => text.push(escaped),
+                        None => return Err("Unterminated text in rule".to_string()),
+                    },
+                    Some((_, c)) => text.push(c),
+                    None => return Err("Unterminated text in rule".to_string()),
+                }
+            }
+            tokens.push(Token::Text(text));
+        } else if c.is_ascii_digit() {
+            let mut number = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if !c.is_ascii_digit() && c != '.' {
+                    break;
+                }
+                number.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Number(number.parse().map_err(|_| format!("Invalid number \"{number}\""))?));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if !c.is_alphanumeric() && c != '_' {
+                    break;
+                }
+                ident.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Ident(ident.to_lowercase()));
+        } else {
+            let rest = rule.get(idx..).unwrap_or_default();
+            let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) else {
+                return Err(format!("Unexpected character '{c}' in rule"));
+            };
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+    Ok(tokens)
+}
+
+fn describe_token(token: &Token) -> String {
+    match token {
+        Token::Ident(ident) => format!("\"{ident}\""),
+        Token::Number(number) => format!("number {number}"),
+        Token::Text(text) => format!("text \"{text}\""),
+        Token::Symbol(symbol) => format!("\"{symbol}\""),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!("Expected {expected}, found {}", describe_token(token)),
+            None => format!("Expected {expected}, found end of rule"),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_and()?;
+        while self.eat_keyword("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.parse_and()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_not()?;
+        while self.eat_keyword("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.parse_not()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_not(&mut self) -> Result<Condition, String> {
+        if self.eat_keyword("not") {
+            return Ok(Condition::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_condition()
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, String> {
+        // Parenthesis may start both nested condition and arithmetic expression like `(width + height) > 100`
+        let start = self.pos;
+        if self.eat_symbol("(") {
+            if let Ok(condition) = self.parse_or()
+                && self.eat_symbol(")")
+            {
+                return Ok(condition);
+            }
+            self.pos = start;
+        }
+
+        let value = self.parse_sum()?;
+        if self.eat_keyword("max") {
+            return Ok(Condition::Extreme(value, true));
+        }
+        if self.eat_keyword("min") {
+            return Ok(Condition::Extreme(value, false));
+        }
+        if self.eat_symbol("~") {
+            let Some(Token::Text(pattern)) = self.peek().cloned() else {
+                return Err(self.unexpected("quoted pattern after ~"));
+            };
+            self.pos += 1;
+            return Ok(Condition::Contains(value, pattern));
+        }
+        let op = match self.peek() {
+            Some(Token::Symbol("==")) => CompareOp::Eq,
+            Some(Token::Symbol("!=")) => CompareOp::NotEq,
+            Some(Token::Symbol("<")) => CompareOp::Lt,
+            Some(Token::Symbol("<=")) => CompareOp::Lte,
+            Some(Token::Symbol(">")) => CompareOp::Gt,
+            Some(Token::Symbol(">=")) => CompareOp::Gte,
+            _ => return Err(self.unexpected("comparison, ~, max or min")),
+        };
+        self.pos += 1;
+        Ok(Condition::Compare(value, op, self.parse_sum()?))
+    }
+
+    fn parse_sum(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_product()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                '+'
+            } else if self.eat_symbol("-") {
+                '-'
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Arithmetic(Box::new(expr), op, Box::new(self.parse_product()?));
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_value()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                '*'
+            } else if self.eat_symbol("/") {
+                '/'
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Arithmetic(Box::new(expr), op, Box::new(self.parse_value()?));
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Expr, String> {
+        if self.eat_symbol("(") {
+            let expr = self.parse_sum()?;
+            if !self.eat_symbol(")") {
+                return Err(self.unexpected("\")\""));
+            }
+            return Ok(expr);
+        }
+        let expr = match self.peek() {
+            Some(Token::Number(number)) => Expr::Value(RuleValue::Number(*number)),
+            Some(Token::Text(text)) => Expr::Value(RuleValue::Text(text.clone())),
+            Some(Token::Ident(ident)) if !KEYWORDS.contains(&ident.as_str()) => Expr::Field(ident.clone()),
+            _ => return Err(self.unexpected("value")),
+        };
+        self.pos += 1;
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::common::model::FileEntry;
+
+    fn entry(path: &str, size: u64, modified_date: u64) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(path),
+            size,
+            modified_date,
+        }
+    }
+
+    fn group() -> Vec<FileEntry> {
+        vec![entry("/photos/raw/a.CR2", 300, 10), entry("/photos/export/a.jpg", 100, 30), entry("/backup/a.jpg", 100, 20)]
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(SelectionRule::parse("keep path ~ \"/raw/\"").unwrap_err().contains("where"));
+        assert!(SelectionRule::parse("remove where size > 1").unwrap_err().contains("keep where"));
+        assert!(SelectionRule::parse("keep where size >").unwrap_err().contains("end of rule"));
+        assert!(SelectionRule::parse("keep where size").unwrap_err().contains("comparison"));
+        assert!(SelectionRule::parse("keep where path ~ raw").unwrap_err().contains("quoted pattern"));
+        assert!(SelectionRule::parse("keep where name == \"a").unwrap_err().contains("Unterminated"));
+        assert!(SelectionRule::parse("keep where size max)").unwrap_err().contains("end of rule"));
+        assert!(SelectionRule::parse("keep where size & 1").unwrap_err().contains("Unexpected character"));
+    }
+
+    #[test]
+    fn test_select_group() {
+        let group = group();
+
+        let rule: SelectionRule = "keep where path ~ \"/RAW/\" or (size max)".parse().unwrap();
+        assert_eq!(rule.action(), RuleAction::Keep);
+        assert_eq!(rule.select_group(&group, true).unwrap(), vec![false, true, true]);
+
+        let rule = SelectionRule::parse("delete where not (modified max) and (size + 0) * 2 == 200").unwrap();
+        assert_eq!(rule.select_group(&group, true).unwrap(), vec![false, false, true]);
+
+        let rule = SelectionRule::parse("DELETE WHERE extension == \"jpg\" and folder != \"/backup\"").unwrap();
+        assert_eq!(rule.select_group(&group, true).unwrap(), vec![false, true, false]);
+
+        let rule = SelectionRule::parse("keep where name ~ \"*.cr*\"").unwrap();
+        assert_eq!(rule.select_group(&group, true).unwrap(), vec![false, true, true]);
+
+        let rule = SelectionRule::parse("keep where modified min and size min").unwrap();
+        assert_eq!(rule.select_group(&group, true).unwrap(), vec![false, false, false]);
+        assert_eq!(rule.select_group(&group, false).unwrap(), vec![true, true, true]);
+    }
+
+    #[test]
+    fn test_evaluation_errors() {
+        let group = group();
+        let rule = SelectionRule::parse("keep where width max").unwrap();
+        assert!(rule.select_group(&group, true).unwrap_err().contains("Unknown field \"width\""));
+        let rule = SelectionRule::parse("keep where path > 5").unwrap();
+        assert!(rule.select_group(&group, true).unwrap_err().contains("Cannot compare"));
+        let rule = SelectionRule::parse("keep where size ~ \"1\"").unwrap();
+        assert!(rule.select_group(&group, true).unwrap_err().contains("only with text"));
+    }
+}
@@ -25,8 +25,15 @@ pub fn get_all_available_threads() -> usize {
     }
 }
 
+/// Global thread pool can be built only once, so later calls with same number of threads, e.g. before every rescan, do nothing.
 pub fn set_number_of_threads(thread_number: usize) {
-    *NUMBER_OF_THREADS.lock().expect("Cannot fail") = Some(thread_number);
+    {
+        let mut number_of_threads = NUMBER_OF_THREADS.lock().expect("Cannot fail");
+        if *number_of_threads == Some(thread_number) {
+            return;
+        }
+        *number_of_threads = Some(thread_number);
+    }
 
     let additional_message = if thread_number == 0 {
         format!(
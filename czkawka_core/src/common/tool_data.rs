@@ -4,13 +4,18 @@ use std::sync::atomic::AtomicBool;
 
 use crossbeam_channel::Sender;
 
+use crate::common::cache::CacheBackend;
+use crate::common::date_filter::DateFilter;
 use crate::common::deletion;
 pub use crate::common::deletion::{DeleteItemType, DeleteResult};
+use crate::common::dir_traversal::CollectedFiles;
 use crate::common::directories::Directories;
 use crate::common::extensions::Extensions;
 use crate::common::items::ExcludedItems;
 use crate::common::model::{CheckingMethod, ToolType, WorkContinueStatus};
+use crate::common::preferred_paths::PreferredPaths;
 use crate::common::progress_data::ProgressData;
+use crate::common::select_rules::SelectionRule;
 use crate::common::traits::ResultEntry;
 use crate::helpers::messages::Messages;
 
@@ -21,18 +26,29 @@ pub struct CommonToolData {
     pub(crate) directories: Directories,
     pub(crate) extensions: Extensions,
     pub(crate) excluded_items: ExcludedItems,
+    pub(crate) respect_gitignore: bool,
     pub(crate) recursive_search: bool,
     pub(crate) delete_method: DeleteMethod,
     pub(crate) maximal_file_size: u64,
     pub(crate) minimal_file_size: u64,
+    pub(crate) date_filter: DateFilter,
     pub(crate) stopped_search: bool,
     pub(crate) use_cache: bool,
+    pub(crate) use_dir_snapshot: bool,
+    pub(crate) cache_backend: CacheBackend,
     pub(crate) delete_outdated_cache: bool,
     pub(crate) save_also_as_json: bool,
     pub(crate) use_reference_folders: bool,
+    pub(crate) preferred_paths: PreferredPaths,
+    pub(crate) selection_rule: Option<SelectionRule>,
     pub(crate) dry_run: bool,
     pub(crate) move_to_trash: bool,
+    pub(crate) quarantine_path: Option<PathBuf>,
+    pub(crate) relative_symlinks: bool,
     pub(crate) hide_hard_links: bool,
+    pub(crate) journal_path: Option<PathBuf>,
+    // Files already collected by multi scan, used instead of walking directories again
+    pub(crate) collected_files: Option<Arc<CollectedFiles>>,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Copy, Default)]
@@ -45,10 +61,13 @@ pub enum DeleteMethod {
     OneOldest,
     OneNewest,
     HardLink,
+    Symlink,
+    Reflink, // Copy-on-write clone of kept file, only on filesystems supporting it(btrfs, XFS)
     AllExceptBiggest,
     AllExceptSmallest,
     OneBiggest,
     OneSmallest,
+    Rule, // Items chosen by selection rule
 }
 
 impl CommonToolData {
@@ -59,18 +78,28 @@ impl CommonToolData {
             directories: Directories::new(),
             extensions: Extensions::new(),
             excluded_items: ExcludedItems::new(),
+            respect_gitignore: false,
             recursive_search: true,
             delete_method: DeleteMethod::None,
             maximal_file_size: u64::MAX,
             minimal_file_size: 0,
+            date_filter: DateFilter::default(),
             stopped_search: false,
             use_cache: true,
+            use_dir_snapshot: false,
+            cache_backend: CacheBackend::default(),
             delete_outdated_cache: true,
             save_also_as_json: false,
             use_reference_folders: false,
+            preferred_paths: PreferredPaths::default(),
+            selection_rule: None,
             dry_run: false,
             move_to_trash: false,
+            quarantine_path: None,
+            relative_symlinks: false,
             hide_hard_links: false,
+            journal_path: None,
+            collected_files: None,
         }
     }
 }
@@ -116,6 +145,15 @@ pub trait CommonData {
     fn get_use_cache(&self) -> bool {
         self.get_cd().use_cache
     }
+    fn set_use_dir_snapshot(&mut self, use_dir_snapshot: bool) {
+        self.get_cd_mut().use_dir_snapshot = use_dir_snapshot;
+    }
+    fn set_cache_backend(&mut self, cache_backend: CacheBackend) {
+        self.get_cd_mut().cache_backend = cache_backend;
+    }
+    fn get_cache_backend(&self) -> CacheBackend {
+        self.get_cd().cache_backend
+    }
 
     fn set_delete_outdated_cache(&mut self, delete_outdated_cache: bool) {
         self.get_cd_mut().delete_outdated_cache = delete_outdated_cache;
@@ -151,6 +189,13 @@ pub trait CommonData {
         self.get_cd().minimal_file_size
     }
 
+    fn set_date_filter(&mut self, date_filter: DateFilter) {
+        self.get_cd_mut().date_filter = date_filter;
+    }
+    fn get_date_filter(&self) -> DateFilter {
+        self.get_cd().date_filter
+    }
+
     #[cfg(target_family = "unix")]
     fn set_exclude_other_filesystems(&mut self, exclude_other_filesystems: bool) {
         self.get_cd_mut().directories.set_exclude_other_filesystems(exclude_other_filesystems);
@@ -179,6 +224,11 @@ pub trait CommonData {
         self.get_cd().recursive_search
     }
 
+    // `.czkawkaignore` files are always used, `.gitignore` only when enabled
+    fn set_respect_gitignore(&mut self, respect_gitignore: bool) {
+        self.get_cd_mut().respect_gitignore = respect_gitignore;
+    }
+
     fn set_use_reference_folders(&mut self, use_reference_folders: bool) {
         self.get_cd_mut().use_reference_folders = use_reference_folders;
     }
@@ -186,6 +236,15 @@ pub trait CommonData {
         self.get_cd().use_reference_folders
     }
 
+    fn set_preferred_paths(&mut self, preferred_paths: Vec<String>) {
+        self.get_cd_mut().preferred_paths = PreferredPaths::new(preferred_paths);
+    }
+
+    // Used only with DeleteMethod::Rule
+    fn set_selection_rule(&mut self, selection_rule: Option<SelectionRule>) {
+        self.get_cd_mut().selection_rule = selection_rule;
+    }
+
     fn set_delete_method(&mut self, delete_method: DeleteMethod) {
         self.get_cd_mut().delete_method = delete_method;
     }
@@ -201,6 +260,30 @@ pub trait CommonData {
         self.get_cd().move_to_trash
     }
 
+    // When set, items are moved to this folder instead of being deleted, see `quarantine::restore_quarantine`
+    fn set_quarantine_path(&mut self, quarantine_path: Option<PathBuf>) {
+        self.get_cd_mut().quarantine_path = quarantine_path;
+    }
+    fn get_quarantine_path(&self) -> Option<&PathBuf> {
+        self.get_cd().quarantine_path.as_ref()
+    }
+
+    // Used only with DeleteMethod::Symlink - when enabled, symlinks point to kept file with path relative to symlink location
+    fn set_relative_symlinks(&mut self, relative_symlinks: bool) {
+        self.get_cd_mut().relative_symlinks = relative_symlinks;
+    }
+    fn get_relative_symlinks(&self) -> bool {
+        self.get_cd().relative_symlinks
+    }
+
+    // Every delete, trash, link, move and rename done by the tool is appended to this file, so it can be reverted later
+    fn set_journal_path(&mut self, journal_path: Option<PathBuf>) {
+        self.get_cd_mut().journal_path = journal_path;
+    }
+    fn get_journal_path(&self) -> Option<&PathBuf> {
+        self.get_cd().journal_path.as_ref()
+    }
+
     fn set_included_paths(&mut self, included_paths: Vec<PathBuf>) {
         let messages = self.get_cd_mut().directories.set_included_paths(included_paths);
         self.get_cd_mut().text_messages.extend_with_another_messages(messages);
@@ -300,17 +383,26 @@ pub trait CommonData {
         println!("Directories: {:?}", self.get_cd().directories);
         println!("Extensions: {:?}", self.get_cd().extensions);
         println!("Excluded items: {:?}", self.get_cd().excluded_items);
+        println!("Respect gitignore: {}", self.get_cd().respect_gitignore);
         println!("Recursive search: {}", self.get_cd().recursive_search);
         println!("Maximal file size: {}", self.get_cd().maximal_file_size);
         println!("Minimal file size: {}", self.get_cd().minimal_file_size);
+        println!("Date filter: {:?}", self.get_cd().date_filter);
         println!("Stopped search: {}", self.get_cd().stopped_search);
         println!("Use cache: {}", self.get_cd().use_cache);
+        println!("Use directory snapshot: {}", self.get_cd().use_dir_snapshot);
+        println!("Cache backend: {:?}", self.get_cd().cache_backend);
         println!("Delete outdated cache: {}", self.get_cd().delete_outdated_cache);
         println!("Save also as json: {}", self.get_cd().save_also_as_json);
         println!("Delete method: {:?}", self.get_cd().delete_method);
         println!("Use reference folders: {}", self.get_cd().use_reference_folders);
+        println!("Preferred paths: {:?}", self.get_cd().preferred_paths);
+        println!("Selection rule: {:?}", self.get_cd().selection_rule.as_ref().map(ToString::to_string));
         println!("Dry run: {}", self.get_cd().dry_run);
         println!("Hide hard links: {}", self.get_cd().hide_hard_links);
+        println!("Quarantine path: {:?}", self.get_cd().quarantine_path);
+        println!("Relative symlinks: {}", self.get_cd().relative_symlinks);
+        println!("Journal path: {:?}", self.get_cd().journal_path);
 
         println!("---------------DEBUG PRINT MESSAGES---------------");
         println!("Errors size - {}", self.get_cd().text_messages.errors.len());
@@ -1,6 +1,6 @@
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
@@ -10,6 +10,7 @@ use serde::Serialize;
 
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
+use crate::common::select_rules::RuleValue;
 use crate::common::tool_data::CommonData;
 
 pub trait DebugPrint {
@@ -78,29 +79,23 @@ pub trait PrintResults: CommonData {
         self.write_results(writer)
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()>;
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> std::io::Result<()>;
 
-    fn save_results_to_file_as_json_internal<T: Serialize + std::fmt::Debug>(&self, file_name: &str, item_to_serialize: &T, pretty_print: bool) -> std::io::Result<()> {
+    fn write_results_as_json_internal<W: Write, T: Serialize + std::fmt::Debug>(&self, writer: &mut W, item_to_serialize: &T, pretty_print: bool) -> std::io::Result<()> {
         if pretty_print {
-            self.save_results_to_file_as_json_pretty(file_name, item_to_serialize)
+            serde_json::to_writer_pretty(writer, item_to_serialize)?;
         } else {
-            self.save_results_to_file_as_json_compact(file_name, item_to_serialize)
+            serde_json::to_writer(writer, item_to_serialize)?;
         }
-    }
-
-    #[fun_time(message = "save_results_to_file_as_json_pretty", level = "debug")]
-    fn save_results_to_file_as_json_pretty<T: Serialize + std::fmt::Debug>(&self, file_name: &str, item_to_serialize: &T) -> std::io::Result<()> {
-        let file_handler = File::create(file_name)?;
-        let mut writer = BufWriter::new(file_handler);
-        serde_json::to_writer_pretty(&mut writer, item_to_serialize)?;
         Ok(())
     }
 
-    #[fun_time(message = "save_results_to_file_as_json_compact", level = "debug")]
-    fn save_results_to_file_as_json_compact<T: Serialize + std::fmt::Debug>(&self, file_name: &str, item_to_serialize: &T) -> std::io::Result<()> {
+    #[fun_time(message = "save_results_to_file_as_json", level = "debug")]
+    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
         let file_handler = File::create(file_name)?;
         let mut writer = BufWriter::new(file_handler);
-        serde_json::to_writer(&mut writer, item_to_serialize)?;
+        self.write_results_as_json(&mut writer, pretty_print)?;
+        writer.flush()?;
         Ok(())
     }
 
@@ -130,6 +125,19 @@ pub trait ResultEntry {
     fn get_path(&self) -> &Path;
     fn get_modified_date(&self) -> u64;
     fn get_size(&self) -> u64;
+    // Only tools that hash whole files know it, used to store it in the undo journal
+    fn get_hash(&self) -> Option<&str> {
+        None
+    }
+    // Tool specific values available in selection rules, common ones like path or size are handled by rules itself
+    fn get_rule_field(&self, _name: &str) -> Option<RuleValue> {
+        None
+    }
+}
+
+// Entries saved in cache may be loaded with path changed to current location of file
+pub trait CacheEntry: ResultEntry {
+    fn set_path(&mut self, path: PathBuf);
 }
 
 pub trait Search {
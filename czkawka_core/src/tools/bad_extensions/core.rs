@@ -11,6 +11,7 @@ use mime_guess::get_mime_extensions;
 use rayon::prelude::*;
 
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::journal::{Journal, JournalAction};
 use crate::common::model::{FileEntry, ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
@@ -200,6 +201,14 @@ impl BadExtensions {
 
     #[fun_time(message = "fix_bad_extensions", level = "debug")]
     pub fn fix_bad_extensions(&mut self, _fix_params: super::BadExtensionsFixParams, stop_flag: &Arc<AtomicBool>) {
+        let journal = match Journal::open_for_tool(&self.common_data) {
+            Ok(journal) => journal,
+            Err(e) => {
+                self.common_data.text_messages.errors.push(e);
+                return;
+            }
+        };
+
         let warnings: Vec<_> = mem::take(&mut self.bad_extensions_files)
             .into_par_iter()
             .map(|entry| {
@@ -213,8 +222,18 @@ impl BadExtensions {
                     return Some(Some(format!("Cannot rename {:?} to {:?}: target file already exists", entry.path, new_path)));
                 }
 
-                match std::fs::rename(&entry.path, &new_path) {
-                    Ok(()) => Some(None),
+                let journal_entry = match journal.as_ref().map(|journal| journal.start(JournalAction::Rename, &entry, Some(&new_path))).transpose() {
+                    Ok(journal_entry) => journal_entry,
+                    Err(e) => return Some(Some(e)),
+                };
+                let result = std::fs::rename(&entry.path, &new_path);
+                let journal_error = match (&journal, journal_entry) {
+                    (Some(journal), Some(journal_entry)) => journal.finish(journal_entry, result.is_ok()).err(),
+                    _ => None,
+                };
+
+                match result {
+                    Ok(()) => Some(journal_error),
                     Err(e) => Some(Some(format!("Failed to rename {:?} to {:?}: {}", entry.path, new_path, e))),
                 }
             })
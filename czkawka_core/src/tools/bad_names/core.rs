@@ -9,6 +9,7 @@ use log::debug;
 use rayon::prelude::*;
 
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::journal::{Journal, JournalAction};
 use crate::common::model::{ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
@@ -103,6 +104,14 @@ impl BadNames {
 
     #[fun_time(message = "fix_bad_names", level = "debug")]
     pub fn fix_bad_names(&mut self, _fix_params: NameFixerParams, stop_flag: &Arc<AtomicBool>) {
+        let journal = match Journal::open_for_tool(&self.common_data) {
+            Ok(journal) => journal,
+            Err(e) => {
+                self.common_data.text_messages.errors.push(e);
+                return;
+            }
+        };
+
         let warnings: Vec<_> = mem::take(&mut self.bad_names_files)
             .into_par_iter()
             .map(|entry| {
@@ -116,8 +125,18 @@ impl BadNames {
                     return Some(Some(format!("Cannot rename {:?} to {:?}: target file already exists", entry.path, new_path)));
                 }
 
-                match fs::rename(&entry.path, &new_path) {
-                    Ok(()) => Some(None),
+                let journal_entry = match journal.as_ref().map(|journal| journal.start(JournalAction::Rename, &entry, Some(&new_path))).transpose() {
+                    Ok(journal_entry) => journal_entry,
+                    Err(e) => return Some(Some(e)),
+                };
+                let result = fs::rename(&entry.path, &new_path);
+                let journal_error = match (&journal, journal_entry) {
+                    (Some(journal), Some(journal_entry)) => journal.finish(journal_entry, result.is_ok()).err(),
+                    _ => None,
+                };
+
+                match result {
+                    Ok(()) => Some(journal_error),
                     Err(e) => Some(Some(format!("Failed to rename {:?} to {:?}: {}", entry.path, new_path, e))),
                 }
             })
@@ -76,8 +76,8 @@ impl PrintResults for BadNames {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
-        self.save_results_to_file_as_json_internal(file_name, &self.bad_names_files, pretty_print)
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> std::io::Result<()> {
+        self.write_results_as_json_internal(writer, &self.bad_names_files, pretty_print)
     }
 }
 
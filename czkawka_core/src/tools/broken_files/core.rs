@@ -39,6 +39,49 @@ impl BrokenFiles {
         }
     }
 
+    // Extensions of all files which may be checked with currently selected types
+    pub(crate) fn checked_extensions(&self) -> Vec<&'static str> {
+        let simple_mappings: &[(CheckedTypes, &[&'static str])] = &[
+            (CheckedTypes::PDF, PDF_FILES_EXTENSIONS),
+            (CheckedTypes::AUDIO, AUDIO_FILES_CONTENT_EXTENSIONS),
+            (CheckedTypes::IMAGE, IMAGE_RS_BROKEN_FILES_EXTENSIONS),
+        ];
+        let mut extensions: Vec<&'static str> = simple_mappings
+            .iter()
+            .filter(|(checked_type, _)| self.get_params().checked_types.contains(*checked_type))
+            .flat_map(|(_, exts)| exts.iter().copied())
+            .collect();
+
+        // Archive covers all supported compression/archive formats
+        if self.get_params().checked_types.contains(CheckedTypes::ARCHIVE) {
+            extensions.extend_from_slice(ZIP_FILES_EXTENSIONS);
+            extensions.extend_from_slice(SEVENZ_FILES_EXTENSIONS);
+            extensions.extend_from_slice(GZ_FILES_EXTENSIONS);
+            extensions.extend_from_slice(TAR_FILES_EXTENSIONS);
+            extensions.extend_from_slice(ZST_FILES_EXTENSIONS);
+            extensions.extend_from_slice(BZ2_FILES_EXTENSIONS);
+            extensions.extend_from_slice(XZ_FILES_EXTENSIONS);
+        }
+
+        if self.get_params().checked_types.contains(CheckedTypes::FONT) {
+            extensions.extend_from_slice(FONT_FILES_EXTENSIONS);
+        }
+
+        if self.get_params().checked_types.contains(CheckedTypes::MARKUP) {
+            extensions.extend_from_slice(JSON_FILES_EXTENSIONS);
+            extensions.extend_from_slice(XML_FILES_EXTENSIONS);
+            extensions.extend_from_slice(TOML_FILES_EXTENSIONS);
+            extensions.extend_from_slice(YAML_FILES_EXTENSIONS);
+            extensions.extend_from_slice(SVG_FILES_EXTENSIONS);
+        }
+
+        if self.get_params().checked_types.intersects(CheckedTypes::VIDEO_FFPROBE | CheckedTypes::VIDEO_FFMPEG) {
+            extensions.extend_from_slice(VIDEO_FILES_EXTENSIONS);
+        }
+
+        extensions
+    }
+
     #[fun_time(message = "check_files", level = "debug")]
     pub(crate) fn check_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
         let result = DirTraversalBuilder::new()
@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::{CacheEntry, ResultEntry};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
 pub enum CheckedTypesSingle {
@@ -57,6 +57,12 @@ impl ResultEntry for BrokenEntry {
     }
 }
 
+impl CacheEntry for BrokenEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
 impl FileEntry {
     fn into_broken_entry(self) -> BrokenEntry {
         BrokenEntry {
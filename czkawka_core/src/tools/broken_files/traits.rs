@@ -6,11 +6,6 @@ use std::time::Instant;
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
 
-use crate::common::consts::{
-    AUDIO_FILES_CONTENT_EXTENSIONS, BZ2_FILES_EXTENSIONS, FONT_FILES_EXTENSIONS, GZ_FILES_EXTENSIONS, IMAGE_RS_BROKEN_FILES_EXTENSIONS, JSON_FILES_EXTENSIONS,
-    PDF_FILES_EXTENSIONS, SEVENZ_FILES_EXTENSIONS, SVG_FILES_EXTENSIONS, TAR_FILES_EXTENSIONS, TOML_FILES_EXTENSIONS, VIDEO_FILES_EXTENSIONS, XML_FILES_EXTENSIONS,
-    XZ_FILES_EXTENSIONS, YAML_FILES_EXTENSIONS, ZIP_FILES_EXTENSIONS, ZST_FILES_EXTENSIONS,
-};
 use crate::common::ffmpeg_utils::check_if_ffprobe_ffmpeg_exists;
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
@@ -35,44 +30,7 @@ impl Search for BrokenFiles {
                 return;
             }
 
-            let simple_mappings: &[(CheckedTypes, &[&str])] = &[
-                (CheckedTypes::PDF, PDF_FILES_EXTENSIONS),
-                (CheckedTypes::AUDIO, AUDIO_FILES_CONTENT_EXTENSIONS),
-                (CheckedTypes::IMAGE, IMAGE_RS_BROKEN_FILES_EXTENSIONS),
-            ];
-            let mut extensions: Vec<&str> = simple_mappings
-                .iter()
-                .filter(|(checked_type, _)| self.get_params().checked_types.contains(*checked_type))
-                .flat_map(|(_, exts)| exts.iter().copied())
-                .collect();
-
-            // Archive covers all supported compression/archive formats
-            if self.get_params().checked_types.contains(CheckedTypes::ARCHIVE) {
-                extensions.extend_from_slice(ZIP_FILES_EXTENSIONS);
-                extensions.extend_from_slice(SEVENZ_FILES_EXTENSIONS);
-                extensions.extend_from_slice(GZ_FILES_EXTENSIONS);
-                extensions.extend_from_slice(TAR_FILES_EXTENSIONS);
-                extensions.extend_from_slice(ZST_FILES_EXTENSIONS);
-                extensions.extend_from_slice(BZ2_FILES_EXTENSIONS);
-                extensions.extend_from_slice(XZ_FILES_EXTENSIONS);
-            }
-
-            if self.get_params().checked_types.contains(CheckedTypes::FONT) {
-                extensions.extend_from_slice(FONT_FILES_EXTENSIONS);
-            }
-
-            if self.get_params().checked_types.contains(CheckedTypes::MARKUP) {
-                extensions.extend_from_slice(JSON_FILES_EXTENSIONS);
-                extensions.extend_from_slice(XML_FILES_EXTENSIONS);
-                extensions.extend_from_slice(TOML_FILES_EXTENSIONS);
-                extensions.extend_from_slice(YAML_FILES_EXTENSIONS);
-                extensions.extend_from_slice(SVG_FILES_EXTENSIONS);
-            }
-
-            if self.get_params().checked_types.intersects(video_types) {
-                extensions.extend_from_slice(VIDEO_FILES_EXTENSIONS);
-            }
-
+            let extensions = self.checked_extensions();
             if extensions.is_empty() {
                 self.common_data.text_messages.critical = Some(flc!("core_needs_to_set_at_least_one_broken_option"));
                 return;
@@ -127,8 +85,8 @@ impl PrintResults for BrokenFiles {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
-        self.save_results_to_file_as_json_internal(file_name, &self.broken_files, pretty_print)
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> std::io::Result<()> {
+        self.write_results_as_json_internal(writer, &self.broken_files, pretty_print)
     }
 }
 impl DeletingItems for BrokenFiles {
@@ -0,0 +1,277 @@
+//! Files stored inside archives, which are compared with normal files by duplicate finder.
+//!
+//! Every member is visible as virtual entry, which path consists of archive path, `!/` and path inside archive,
+//! e.g. `/exports/photos.zip!/2019/a.jpg`. Members cannot be opened directly, so they are never deleted or linked.
+//!
+//! Supported are zip, 7z and tar archives(also compressed with gzip, zstd, bzip2 or xz).
+//! Content of each member must be read to calculate its hash, so archives are always fully decompressed and
+//! hashes of their members are not cached.
+//! Xz archives are decompressed to temporary file, so archives bigger than 16 GiB after decompression are skipped.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crate::common::model::HashType;
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::tools::duplicate::DuplicateEntry;
+
+pub const ARCHIVE_MEMBER_SEPARATOR: &str = "!/";
+const MAX_DECOMPRESSED_XZ_SIZE: u64 = 16 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveType {
+    Zip,
+    SevenZ,
+    Tar,
+    TarGz,
+    TarZst,
+    TarBz2,
+    TarXz,
+}
+
+// Only compressed tar archives are supported, single compressed files(e.g. `notes.txt.gz`) are skipped
+const ARCHIVE_SUFFIXES: &[(&str, ArchiveType)] = &[
+    (".zip", ArchiveType::Zip),
+    (".jar", ArchiveType::Zip),
+    (".7z", ArchiveType::SevenZ),
+    (".tar", ArchiveType::Tar),
+    (".tar.gz", ArchiveType::TarGz),
+    (".tgz", ArchiveType::TarGz),
+    (".tar.zst", ArchiveType::TarZst),
+    (".tzst", ArchiveType::TarZst),
+    (".tar.bz2", ArchiveType::TarBz2),
+    (".tbz2", ArchiveType::TarBz2),
+    (".tbz", ArchiveType::TarBz2),
+    (".tar.xz", ArchiveType::TarXz),
+    (".txz", ArchiveType::TarXz),
+];
+
+pub fn get_archive_type(path: &Path) -> Option<ArchiveType> {
+    let file_name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    ARCHIVE_SUFFIXES
+        .iter()
+        .find(|(suffix, _)| file_name.len() > suffix.len() && file_name.ends_with(suffix))
+        .map(|(_, archive_type)| *archive_type)
+}
+
+pub fn archive_member_path(archive_path: &Path, member_name: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "{}{ARCHIVE_MEMBER_SEPARATOR}{}",
+        archive_path.to_string_lossy(),
+        member_name.trim_start_matches('/')
+    ))
+}
+
+// Separator alone is not enough, because `!` may be used in normal folder names, so part before it must also look like archive
+pub fn is_archive_member(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    path.match_indices(ARCHIVE_MEMBER_SEPARATOR)
+        .any(|(idx, _)| path.get(..idx).is_some_and(|archive_path| get_archive_type(Path::new(archive_path)).is_some()))
+}
+
+// Members which don't fit in size limits are skipped, directories and empty members are never returned
+// Returns Ok(None) when stop was requested
+pub(crate) fn hash_archive_members(
+    archive: &DuplicateEntry,
+    hash_type: HashType,
+    minimal_file_size: u64,
+    maximal_file_size: u64,
+    buffer: &mut [u8],
+    stop_flag: &Arc<AtomicBool>,
+) -> Result<Option<Vec<DuplicateEntry>>, String> {
+    let Some(archive_type) = get_archive_type(&archive.path) else {
+        return Ok(Some(Vec::new()));
+    };
+    let mut members = Vec::new();
+    let mut add_member = |name: &str, size: u64, reader: &mut dyn Read| -> io::Result<bool> {
+        if size == 0 || size < minimal_file_size || size > maximal_file_size {
+            return Ok(true);
+        }
+        let Some(hash) = hash_reader(reader, hash_type, buffer, stop_flag)? else {
+            return Ok(false);
+        };
+        members.push(DuplicateEntry {
+            path: archive_member_path(&archive.path, name),
+            // Not all archives store modification dates of members, so date of archive is used
+            modified_date: archive.modified_date,
+            size,
+            hash,
+        });
+        Ok(true)
+    };
+
+    let map_error = |e: &dyn std::fmt::Display| format!("Cannot read archive \"{}\", reason {e}", archive.path.to_string_lossy());
+    let file = File::open(&archive.path).map_err(|e| map_error(&e))?;
+    let finished = match archive_type {
+        ArchiveType::Zip => read_zip_members(file, &mut add_member).map_err(|e| map_error(&e))?,
+        ArchiveType::SevenZ => read_7z_members(file, &mut add_member).map_err(|e| map_error(&e))?,
+        ArchiveType::Tar => read_tar_members(BufReader::new(file), &mut add_member).map_err(|e| map_error(&e))?,
+        ArchiveType::TarGz => read_tar_members(flate2::read::GzDecoder::new(BufReader::new(file)), &mut add_member).map_err(|e| map_error(&e))?,
+        ArchiveType::TarZst => {
+            let decoder = ruzstd::decoding::StreamingDecoder::new(BufReader::new(file)).map_err(|e| map_error(&e))?;
+            read_tar_members(decoder, &mut add_member).map_err(|e| map_error(&e))?
+        }
+        ArchiveType::TarBz2 => read_tar_members(bzip2_rs::DecoderReader::new(BufReader::new(file)), &mut add_member).map_err(|e| map_error(&e))?,
+        ArchiveType::TarXz => {
+            // Xz decoder can only write decompressed data, so it is stored in temporary file instead of memory
+            let mut decompressed = LimitedWriter::new(tempfile::tempfile().map_err(|e| map_error(&e))?, MAX_DECOMPRESSED_XZ_SIZE, stop_flag);
+            if let Err(e) = lzma_rs::xz_decompress(&mut BufReader::new(file), &mut decompressed) {
+                return if decompressed.stopped { Ok(None) } else { Err(map_error(&e)) };
+            }
+            let mut decompressed = decompressed.file;
+            decompressed.seek(SeekFrom::Start(0)).map_err(|e| map_error(&e))?;
+            read_tar_members(BufReader::new(decompressed), &mut add_member).map_err(|e| map_error(&e))?
+        }
+    };
+
+    Ok(finished.then_some(members))
+}
+
+// Writes to file until limit is exceeded or stop is requested
+struct LimitedWriter<'a> {
+    file: File,
+    limit: u64,
+    written: u64,
+    stop_flag: &'a Arc<AtomicBool>,
+    stopped: bool,
+}
+
+impl<'a> LimitedWriter<'a> {
+    fn new(file: File, limit: u64, stop_flag: &'a Arc<AtomicBool>) -> Self {
+        Self {
+            file,
+            limit,
+            written: 0,
+            stop_flag,
+            stopped: false,
+        }
+    }
+}
+
+impl Write for LimitedWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if check_if_stop_received(self.stop_flag) {
+            self.stopped = true;
+            return Err(io::Error::other("Stopped by user"));
+        }
+        if self.written + buf.len() as u64 > self.limit {
+            return Err(io::Error::other(format!("decompressed archive is bigger than {} bytes", self.limit)));
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+// Returns None when stop was requested
+fn hash_reader(reader: &mut dyn Read, hash_type: HashType, buffer: &mut [u8], stop_flag: &Arc<AtomicBool>) -> io::Result<Option<String>> {
+    let hasher = &mut *hash_type.hasher();
+    loop {
+        let n = match reader.read(buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        #[expect(clippy::indexing_slicing)] // Safe, because read returns at most buffer size
+        hasher.update(&buffer[..n]);
+        if check_if_stop_received(stop_flag) {
+            return Ok(None);
+        }
+    }
+    Ok(Some(hasher.finalize()))
+}
+
+type AddMember<'a> = dyn FnMut(&str, u64, &mut dyn Read) -> io::Result<bool> + 'a;
+
+fn read_zip_members(file: File, add_member: &mut AddMember) -> io::Result<bool> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(io::Error::other)?;
+    for idx in 0..archive.len() {
+        // Encrypted members cannot be read without password, so they are skipped
+        let Ok(mut member) = archive.by_index(idx) else {
+            continue;
+        };
+        if member.is_dir() {
+            continue;
+        }
+        let name = member.name().to_string();
+        let size = member.size();
+        if !add_member(&name, size, &mut member)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn read_7z_members(file: File, add_member: &mut AddMember) -> io::Result<bool> {
+    let mut archive = sevenz_rust2::ArchiveReader::new(BufReader::new(file), sevenz_rust2::Password::empty()).map_err(io::Error::other)?;
+    let mut finished = true;
+    let mut member_error = None;
+    archive
+        .for_each_entries(|member, reader| {
+            if member.is_directory() {
+                return Ok(true);
+            }
+            // Members of solid archive are read one after another from the same stream, so skipped member must be read to the end
+            match add_member(member.name(), member.size(), reader).and_then(|added| io::copy(reader, &mut io::sink()).map(|_| added)) {
+                Ok(true) => Ok(true),
+                Ok(false) => {
+                    finished = false;
+                    Ok(false)
+                }
+                Err(e) => {
+                    member_error = Some(e);
+                    Ok(false)
+                }
+            }
+        })
+        .map_err(io::Error::other)?;
+    match member_error {
+        Some(e) => Err(e),
+        None => Ok(finished),
+    }
+}
+
+fn read_tar_members<R: Read>(reader: R, add_member: &mut AddMember) -> io::Result<bool> {
+    let mut archive = tar::Archive::new(reader);
+    for member in archive.entries()? {
+        let mut member = member?;
+        if !member.header().entry_type().is_file() {
+            continue;
+        }
+        let name = member.path()?.to_string_lossy().to_string();
+        let size = member.size();
+        if !add_member(&name, size, &mut member)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[test]
+    fn test_limited_writer() {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let mut writer = LimitedWriter::new(tempfile::tempfile().expect("Cannot create temp file"), 10, &stop_flag);
+        writer.write_all(b"0123456789").expect("Data fits in limit");
+        writer.write_all(b"a").expect_err("Data doesn't fit in limit");
+        assert!(!writer.stopped);
+
+        let mut writer = LimitedWriter::new(tempfile::tempfile().expect("Cannot create temp file"), 10, &stop_flag);
+        stop_flag.store(true, Ordering::Relaxed);
+        writer.write_all(b"a").expect_err("Writing should be stopped");
+        assert!(writer.stopped);
+    }
+}
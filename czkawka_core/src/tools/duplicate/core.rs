@@ -1,23 +1,28 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::time::Instant;
-use std::{mem, thread};
+use std::{iter, mem, thread};
 
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
 use log::debug;
 use rayon::prelude::*;
 
-use crate::common::cache::{CACHE_DUPLICATE_PREHASH_VERSION, CACHE_DUPLICATE_VERSION, load_and_split_cache_generalized_by_size, save_cache_to_file_generalized};
+use crate::common::cache::{CACHE_DUPLICATE_PREHASH_VERSION, CACHE_DUPLICATE_VERSION, load_and_split_cache_generalized_by_size, save_cache_generalized};
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
 use crate::common::model::{CheckingMethod, FileEntry, HashType, ToolType, WorkContinueStatus};
 use crate::common::progress_data::{CacheLoadPhase, DuplicateStage, ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
-use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::common::tool_data::{CommonData, CommonToolData, DeleteMethod};
 use crate::common::traits::ResultEntry;
+use crate::flc;
+use crate::tools::duplicate::archive::{get_archive_type, hash_archive_members, is_archive_member};
+use crate::tools::duplicate::hash_index::{HashIndex, save_hash_index};
+use crate::tools::duplicate::manifest::{ManifestMode, load_checksum_manifests};
 use crate::tools::duplicate::{
-    DuplicateEntry, DuplicateFinder, DuplicateFinderParameters, Info, PREHASHING_BUFFER_SIZE, THREAD_BUFFER, filter_hard_links, hash_calculation, hash_calculation_limit,
+    DuplicateEntry, DuplicateFinder, DuplicateFinderParameters, Info, PREHASHING_BUFFER_SIZE, THREAD_BUFFER, files_have_identical_content, filter_hard_links, hash_calculation,
+    hash_calculation_limit,
 };
 
 impl DuplicateFinder {
@@ -33,6 +38,10 @@ impl DuplicateFinder {
             files_with_identical_size_names_referenced: Default::default(),
             files_with_identical_size_referenced: Default::default(),
             files_with_identical_hashes_referenced: Default::default(),
+            files_missing_from_manifests: Vec::new(),
+            archive_members: Default::default(),
+            files_with_size_of_archive_members: Default::default(),
+            results_sender: None,
             params,
         }
     }
@@ -215,6 +224,33 @@ impl DuplicateFinder {
             DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
                 self.common_data.text_messages.warnings.extend(warnings);
 
+                let mut grouped_file_entries = grouped_file_entries;
+                if self.get_params().check_archives && self.get_params().check_method == CheckingMethod::Hash {
+                    let archives = grouped_file_entries
+                        .values()
+                        .flatten()
+                        .filter(|fe| get_archive_type(&fe.path).is_some())
+                        .map(|fe| fe.clone().into_duplicate_entry())
+                        .collect();
+                    let Some(archive_members) = self.hash_archive_files(stop_flag, progress_sender, archives) else {
+                        return WorkContinueStatus::Stop;
+                    };
+                    self.archive_members = archive_members;
+
+                    // Files with size of any member must be hashed, even if there is no other normal file with such size
+                    let (with_size_of_member, other): (BTreeMap<_, _>, BTreeMap<_, _>) =
+                        grouped_file_entries.into_iter().partition(|(size, _)| self.archive_members.contains_key(size));
+                    let hide_hard_links = self.get_hide_hard_links();
+                    self.files_with_size_of_archive_members = with_size_of_member
+                        .into_iter()
+                        .map(|(size, vec)| {
+                            let vec = if hide_hard_links { filter_hard_links(vec) } else { vec };
+                            (size, vec.into_iter().map(FileEntry::into_duplicate_entry).collect())
+                        })
+                        .collect();
+                    grouped_file_entries = other;
+                }
+
                 let grouped_file_entries: Vec<(u64, Vec<FileEntry>)> = grouped_file_entries.into_iter().collect();
                 let rayon_max_len = if self.get_hide_hard_links() { 3 } else { 100 };
 
@@ -348,7 +384,8 @@ impl DuplicateFinder {
                 }
             }
 
-            let messages = save_cache_to_file_generalized(
+            let messages = save_cache_generalized(
+                self.common_data.cache_backend,
                 &get_duplicate_cache_file(self.get_params().hash_type, true),
                 &save_cache_to_hashmap,
                 self.common_data.save_also_as_json,
@@ -528,7 +565,8 @@ impl DuplicateFinder {
             }
         }
 
-        let messages = save_cache_to_file_generalized(
+        let messages = save_cache_generalized(
+            self.common_data.cache_backend,
             &get_duplicate_cache_file(self.get_params().hash_type, false),
             &all_results,
             self.common_data.save_also_as_json,
@@ -548,13 +586,39 @@ impl DuplicateFinder {
             return WorkContinueStatus::Continue;
         }
 
+        let Some(full_hash_results) = self.calculate_full_hashes(stop_flag, progress_sender, pre_checked_map, true) else {
+            return WorkContinueStatus::Stop;
+        };
+
+        for (size, hash_map) in full_hash_results {
+            for (_hash, vec_file_entry) in hash_map {
+                if vec_file_entry.len() > 1 {
+                    self.files_with_identical_hashes.entry(size).or_default().push(vec_file_entry);
+                }
+            }
+        }
+
+        WorkContinueStatus::Continue
+    }
+
+    // Hashes all files(or takes hashes from cache) and groups them by size and hash
+    // When `send_groups` is set, groups are sent to results sender just after hashing all files with the same size
+    // Returns None only when stopped before hashing, partial results are returned when stopped during hashing
+    #[fun_time(message = "calculate_full_hashes", level = "debug")]
+    pub(crate) fn calculate_full_hashes(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        pre_checked_map: BTreeMap<u64, Vec<DuplicateEntry>>,
+        send_groups: bool,
+    ) -> Option<Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>)>> {
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::LoadingHashCache(CacheLoadPhase::Loading)), 0, 0);
 
         let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.full_hashing_load_cache_at_start(pre_checked_map);
 
         progress_handler.join_thread();
         if check_if_stop_received(stop_flag) {
-            return WorkContinueStatus::Stop;
+            return None;
         }
 
         let progress_handler = prepare_thread_handler_common(
@@ -564,6 +628,14 @@ impl DuplicateFinder {
             non_cached_files_to_check.iter().map(|(size, items)| (*size) * items.len() as u64).sum::<u64>(),
         );
 
+        if send_groups {
+            for (size, cached_entries) in &records_already_cached {
+                if !non_cached_files_to_check.contains_key(size) {
+                    self.send_groups_with_same_hash(cached_entries.iter());
+                }
+            }
+        }
+
         let non_cached_files_to_check: Vec<(u64, Vec<DuplicateEntry>)> = non_cached_files_to_check.into_iter().collect();
 
         let check_type = self.get_params().hash_type;
@@ -600,6 +672,10 @@ impl DuplicateFinder {
                     Some(())
                 })?;
 
+                if send_groups {
+                    self.send_groups_with_same_hash(hashmap_with_hash.values().flatten().chain(records_already_cached.get(&size).into_iter().flatten()));
+                }
+
                 Some((size, hashmap_with_hash, errors))
             })
             .while_some()
@@ -615,16 +691,72 @@ impl DuplicateFinder {
 
         progress_handler.join_thread();
 
-        for (size, hash_map, mut errors) in full_hash_results {
-            self.common_data.text_messages.warnings.append(&mut errors);
-            for (_hash, vec_file_entry) in hash_map {
-                if vec_file_entry.len() > 1 {
-                    self.files_with_identical_hashes.entry(size).or_default().push(vec_file_entry);
-                }
+        Some(
+            full_hash_results
+                .into_iter()
+                .map(|(size, hash_map, mut errors)| {
+                    self.common_data.text_messages.warnings.append(&mut errors);
+                    (size, hash_map)
+                })
+                .collect(),
+        )
+    }
+
+    // All files with same size are fully hashed in one batch, so groups are confirmed just after it
+    fn send_groups_with_same_hash<'a>(&self, entries: impl Iterator<Item = &'a DuplicateEntry>) {
+        if self.results_sender.is_none() {
+            return;
+        }
+        let mut groups: BTreeMap<&str, Vec<&DuplicateEntry>> = BTreeMap::new();
+        for entry in entries {
+            groups.entry(entry.hash.as_str()).or_default().push(entry);
+        }
+
+        for group in groups.into_values().filter(|group| group.len() > 1) {
+            let group: Vec<DuplicateEntry> = group.into_iter().cloned().collect();
+            if !self.common_data.use_reference_folders {
+                self.send_group(group);
+                continue;
+            }
+            // Same filtering as in hash_reference_folders
+            let (mut files_from_referenced_folders, normal_files): (Vec<_>, Vec<_>) =
+                group.into_iter().partition(|e| self.common_data.directories.is_in_referenced_directory(e.get_path()));
+            if !normal_files.is_empty()
+                && let Some(file) = files_from_referenced_folders.pop()
+            {
+                self.send_group(iter::once(file).chain(normal_files).collect());
             }
         }
+    }
 
-        WorkContinueStatus::Continue
+    // Groups by name or size are known only after collecting all files
+    pub(crate) fn send_collected_groups(&self) {
+        if self.results_sender.is_none() {
+            return;
+        }
+        fn referenced_group((file, normal_files): &(DuplicateEntry, Vec<DuplicateEntry>)) -> Vec<DuplicateEntry> {
+            iter::once(file.clone()).chain(normal_files.iter().cloned()).collect()
+        }
+
+        let groups: Vec<Vec<DuplicateEntry>> = match (self.get_params().check_method, self.common_data.use_reference_folders) {
+            (CheckingMethod::Name, false) => self.files_with_identical_names.values().cloned().collect(),
+            (CheckingMethod::Name, true) => self.files_with_identical_names_referenced.values().map(referenced_group).collect(),
+            (CheckingMethod::SizeName, false) => self.files_with_identical_size_names.values().cloned().collect(),
+            (CheckingMethod::SizeName, true) => self.files_with_identical_size_names_referenced.values().map(referenced_group).collect(),
+            (CheckingMethod::Size, false) => self.files_with_identical_size.values().cloned().collect(),
+            (CheckingMethod::Size, true) => self.files_with_identical_size_referenced.values().map(referenced_group).collect(),
+            _ => return,
+        };
+        for group in groups {
+            self.send_group(group);
+        }
+    }
+
+    fn send_group(&self, group: Vec<DuplicateEntry>) {
+        if let Some(results_sender) = &self.results_sender {
+            // Receiver may be already dropped when user is not interested in more results
+            let _ = results_sender.send(group);
+        }
     }
 
     #[fun_time(message = "hash_reference_folders", level = "debug")]
@@ -692,6 +824,10 @@ impl DuplicateFinder {
             return WorkContinueStatus::Stop;
         }
 
+        if self.check_archive_members(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+            return WorkContinueStatus::Stop;
+        }
+
         self.hash_reference_folders();
 
         // Clean unused data
@@ -700,6 +836,290 @@ impl DuplicateFinder {
 
         WorkContinueStatus::Continue
     }
+
+    // Hashes every scanned file, not only files with the same size as other files.
+    // Used to compare files with checksum manifests or hash indexes and to export hash index of all scanned files.
+    #[fun_time(message = "check_files_hash_all", level = "debug")]
+    pub(crate) fn check_files_hash_all(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let hash_type = self.get_params().hash_type;
+        let manifest_entries = if self.get_params().checksum_manifests.is_empty() {
+            None
+        } else {
+            match load_checksum_manifests(&self.get_params().checksum_manifests, hash_type) {
+                Ok(manifest_entries) => Some(manifest_entries),
+                Err(e) => {
+                    self.common_data.text_messages.critical = Some(e);
+                    return WorkContinueStatus::Continue;
+                }
+            }
+        };
+        let hash_index_export_path = self.get_params().hash_index_export_path.clone();
+
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|fe| fe.size)
+            .checking_method(CheckingMethod::Hash)
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        // Files are not grouped by size, because every file may be found in manifest or must be saved in index
+        let mut files_to_hash: BTreeMap<u64, Vec<DuplicateEntry>> = match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.common_data.text_messages.warnings.extend(warnings);
+                let hide_hard_links = self.get_hide_hard_links() && manifest_entries.is_none();
+                grouped_file_entries
+                    .into_iter()
+                    .map(|(size, vec)| {
+                        let vec = if hide_hard_links { filter_hard_links(vec) } else { vec };
+                        (size, vec.into_iter().map(FileEntry::into_duplicate_entry).collect())
+                    })
+                    .collect()
+            }
+            DirTraversalResult::Stopped => return WorkContinueStatus::Stop,
+        };
+
+        let archive_members = if self.get_params().check_archives {
+            let archives = files_to_hash.values().flatten().filter(|e| get_archive_type(&e.path).is_some()).cloned().collect();
+            let Some(archive_members) = self.hash_archive_files(stop_flag, progress_sender, archives) else {
+                return WorkContinueStatus::Stop;
+            };
+            archive_members
+        } else {
+            BTreeMap::new()
+        };
+
+        // Hash indexes contain sizes, so files with other sizes may be skipped, unless they must be exported
+        let known_sizes = manifest_entries
+            .as_ref()
+            .and_then(|entries| entries.values().map(|e| e.size).collect::<Option<HashSet<u64>>>());
+        if let Some(known_sizes) = known_sizes
+            && hash_index_export_path.is_none()
+        {
+            let (with_known_size, other): (BTreeMap<_, _>, BTreeMap<_, _>) = files_to_hash.into_iter().partition(|(size, _)| known_sizes.contains(size));
+            if self.get_params().manifest_mode == ManifestMode::Missing {
+                self.files_missing_from_manifests.extend(other.into_values().flatten());
+            }
+            files_to_hash = with_known_size;
+        }
+
+        let Some(mut full_hash_results) = self.calculate_full_hashes(stop_flag, progress_sender, files_to_hash, false) else {
+            return WorkContinueStatus::Stop;
+        };
+        // Not hashed files would be reported as missing or saved in index without hash
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+        merge_archive_members(&mut full_hash_results, archive_members);
+
+        if let Some(hash_index_export_path) = hash_index_export_path {
+            let hashed_files: Vec<DuplicateEntry> = full_hash_results.iter().flat_map(|(_size, hash_map)| hash_map.values().flatten().cloned()).collect();
+            match save_hash_index(&hash_index_export_path, &HashIndex::new(hash_type, &hashed_files)) {
+                Ok(()) => debug!(
+                    "check_files_hash_all - saved {} entries to hash index \"{}\"",
+                    hashed_files.len(),
+                    hash_index_export_path.to_string_lossy()
+                ),
+                Err(e) => self.common_data.text_messages.errors.push(e),
+            }
+        }
+
+        if let Some(manifest_entries) = manifest_entries {
+            self.match_manifest_entries(&manifest_entries, full_hash_results);
+        } else {
+            for (size, hash_map) in full_hash_results {
+                for (_hash, vec_file_entry) in hash_map {
+                    if vec_file_entry.len() > 1 {
+                        self.files_with_identical_hashes.entry(size).or_default().push(vec_file_entry);
+                    }
+                }
+            }
+            self.hash_reference_folders();
+        }
+
+        WorkContinueStatus::Continue
+    }
+
+    // Members are hashed in one pass over each archive, because they cannot be read separately(e.g. in solid 7z or compressed tar archives)
+    // Returns None when stopped
+    #[fun_time(message = "hash_archive_files", level = "debug")]
+    fn hash_archive_files(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        archives: Vec<DuplicateEntry>,
+    ) -> Option<BTreeMap<u64, Vec<DuplicateEntry>>> {
+        let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::FullHashing), archives.len(), 0);
+
+        let hash_type = self.get_params().hash_type;
+        let (minimal_file_size, maximal_file_size) = (self.common_data.minimal_file_size, self.common_data.maximal_file_size);
+        let results: Vec<Result<Vec<DuplicateEntry>, String>> = archives
+            .into_par_iter()
+            .map(|archive| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                let result = THREAD_BUFFER.with_borrow_mut(|buffer| hash_archive_members(&archive, hash_type, minimal_file_size, maximal_file_size, buffer, stop_flag));
+                progress_handler.increase_items(1);
+                result.transpose()
+            })
+            .while_some()
+            .collect();
+
+        progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let mut archive_members: BTreeMap<u64, Vec<DuplicateEntry>> = BTreeMap::new();
+        for result in results {
+            match result {
+                Ok(members) => {
+                    for member in members {
+                        archive_members.entry(member.size).or_default().push(member);
+                    }
+                }
+                // Broken or encrypted archives are common, so they don't stop search
+                Err(e) => self.common_data.text_messages.warnings.push(e),
+            }
+        }
+        debug!("hash_archive_files - found {} members in archives", archive_members.values().map(Vec::len).sum::<usize>());
+        Some(archive_members)
+    }
+
+    // Groups archive members with normal files of the same size and hash
+    #[fun_time(message = "check_archive_members", level = "debug")]
+    fn check_archive_members(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let archive_members = mem::take(&mut self.archive_members);
+        if archive_members.is_empty() {
+            return WorkContinueStatus::Continue;
+        }
+
+        let files_to_hash = mem::take(&mut self.files_with_size_of_archive_members);
+        let mut full_hash_results = if files_to_hash.is_empty() {
+            Vec::new()
+        } else {
+            let Some(full_hash_results) = self.calculate_full_hashes(stop_flag, progress_sender, files_to_hash, false) else {
+                return WorkContinueStatus::Stop;
+            };
+            full_hash_results
+        };
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+        merge_archive_members(&mut full_hash_results, archive_members);
+
+        for (size, hash_map) in full_hash_results {
+            // Files with sizes of archive members are hashed without sending groups, so all groups are sent here
+            self.send_groups_with_same_hash(hash_map.values().flatten());
+            for (_hash, vec_file_entry) in hash_map {
+                if vec_file_entry.len() > 1 {
+                    self.files_with_identical_hashes.entry(size).or_default().push(vec_file_entry);
+                }
+            }
+        }
+
+        WorkContinueStatus::Continue
+    }
+
+    // Groups found by hash, which are later deleted or linked, original file is the first item of each pair
+    // Returns None when stop was requested during verification
+    // Archive members are read only, so they are never deleted and never used as link target or to verify content
+    pub(crate) fn hash_groups_to_process(&mut self, stop_flag: &Arc<AtomicBool>) -> Option<Vec<(DuplicateEntry, Vec<DuplicateEntry>)>> {
+        let linking = matches!(self.common_data.delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::Symlink);
+        let original_must_be_file = linking || self.get_params().verify_before_delete;
+        let groups: Vec<(DuplicateEntry, Vec<DuplicateEntry>)> = if self.common_data.use_reference_folders {
+            self.files_with_identical_hashes_referenced
+                .values()
+                .flatten()
+                .filter(|(original, _files)| !original_must_be_file || !is_archive_member(&original.path))
+                .map(|(original, files)| (original.clone(), files.iter().filter(|e| !is_archive_member(&e.path)).cloned().collect::<Vec<_>>()))
+                .filter(|(_original, files)| !files.is_empty())
+                .collect()
+        } else {
+            self.files_with_identical_hashes
+                .values()
+                .flatten()
+                .filter_map(|group| {
+                    let mut files = group.iter().filter(|e| !is_archive_member(&e.path)).cloned();
+                    let original = files.next()?;
+                    let files: Vec<_> = files.collect();
+                    (!files.is_empty()).then_some((original, files))
+                })
+                .collect()
+        };
+
+        if self.get_params().verify_before_delete {
+            self.verify_content_before_delete(stop_flag, groups)
+        } else {
+            Some(groups)
+        }
+    }
+
+    // Every file is compared with the first file in group, so remaining files are identical to each other
+    // and it doesn't matter which of them will be kept by delete method
+    #[fun_time(message = "verify_content_before_delete", level = "debug")]
+    pub(crate) fn verify_content_before_delete(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        groups: Vec<(DuplicateEntry, Vec<DuplicateEntry>)>,
+    ) -> Option<Vec<(DuplicateEntry, Vec<DuplicateEntry>)>> {
+        let verified_groups: Vec<_> = groups
+            .into_par_iter()
+            .map(|(original, files)| {
+                let mut errors = Vec::new();
+                let mut verified_files = Vec::with_capacity(files.len());
+                for file in files {
+                    match files_have_identical_content(&original.path, &file.path, stop_flag) {
+                        Ok(Some(true)) => verified_files.push(file),
+                        Ok(Some(false)) => errors.push(flc!(
+                            "core_content_different_than_original",
+                            file = file.path.to_string_lossy(),
+                            original = original.path.to_string_lossy()
+                        )),
+                        Ok(None) => return None,
+                        Err(e) => errors.push(e),
+                    }
+                }
+                Some(((original, verified_files), errors))
+            })
+            .while_some()
+            .collect();
+
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let mut groups = Vec::with_capacity(verified_groups.len());
+        for ((original, files), errors) in verified_groups {
+            self.common_data.text_messages.errors.extend(errors);
+            if !files.is_empty() {
+                groups.push((original, files));
+            }
+        }
+        debug!("verify_content_before_delete - {} groups still contain duplicates after verification", groups.len());
+        Some(groups)
+    }
+}
+
+fn merge_archive_members(full_hash_results: &mut Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>)>, archive_members: BTreeMap<u64, Vec<DuplicateEntry>>) {
+    let mut archive_members: BTreeMap<u64, BTreeMap<String, Vec<DuplicateEntry>>> = archive_members
+        .into_iter()
+        .map(|(size, members)| {
+            let mut hash_map: BTreeMap<String, Vec<DuplicateEntry>> = BTreeMap::new();
+            for member in members {
+                hash_map.entry(member.hash.clone()).or_default().push(member);
+            }
+            (size, hash_map)
+        })
+        .collect();
+    for (size, hash_map) in full_hash_results.iter_mut() {
+        for (hash, members) in archive_members.remove(size).into_iter().flatten() {
+            hash_map.entry(hash).or_default().extend(members);
+        }
+    }
+    full_hash_results.extend(archive_members);
 }
 
 pub fn get_duplicate_cache_file(type_of_hash: HashType, is_prehash: bool) -> String {
@@ -0,0 +1,163 @@
+//! Portable index of hashed files, used to find duplicates between disks or machines which are never connected at the same time.
+//!
+//! Index is a single json object:
+//! `{"version":1,"hash_type":"Blake3","entries":[{"path":"/photos/a.jpg","size":1024,"modified_date":1700000000,"hash":"..."}]}`
+//! - `version` - format version, indexes with other version are refused
+//! - `hash_type` - algorithm used to calculate hashes, one of `Blake3`, `Crc32`, `Xxh3`, `Sha256`, `Sha1`, `Md5`
+//! - `path` - absolute path of file on machine where index was created
+//! - `size` - size in bytes
+//! - `modified_date` - modification time in seconds since Unix epoch
+//! - `hash` - hash of whole file content, in the same form as used by duplicate finder(lowercase hex for cryptographic hashes)
+//!
+//! Index may be later used as checksum manifest, then its entries act as reference files.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::model::HashType;
+use crate::tools::duplicate::DuplicateEntry;
+
+// Bump when the index layout changes
+pub const HASH_INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashIndex {
+    pub version: u32,
+    pub hash_type: HashType,
+    pub entries: Vec<HashIndexEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashIndexEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_date: u64,
+    pub hash: String,
+}
+
+impl HashIndex {
+    pub fn new(hash_type: HashType, entries: &[DuplicateEntry]) -> Self {
+        let mut entries: Vec<HashIndexEntry> = entries
+            .iter()
+            .map(|e| HashIndexEntry {
+                path: e.path.clone(),
+                size: e.size,
+                modified_date: e.modified_date,
+                hash: e.hash.clone(),
+            })
+            .collect();
+        entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        Self {
+            version: HASH_INDEX_VERSION,
+            hash_type,
+            entries,
+        }
+    }
+}
+
+// Index is written to temporary file first, so interrupted export doesn't destroy previous index
+pub fn save_hash_index(path: &Path, index: &HashIndex) -> Result<(), String> {
+    let tmp_path = path.with_extension("tmp");
+    let file = File::create(&tmp_path).map_err(|e| format!("Cannot create hash index \"{}\", reason {e}", tmp_path.to_string_lossy()))?;
+    let mut writer = BufWriter::new(file);
+    let result = serde_json::to_writer(&mut writer, index)
+        .map_err(io::Error::from)
+        .and_then(|()| writer.flush())
+        .and_then(|()| fs::rename(&tmp_path, path));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Cannot save hash index \"{}\", reason {e}", path.to_string_lossy()));
+    }
+    Ok(())
+}
+
+pub fn load_hash_index(path: &Path) -> Result<HashIndex, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read hash index \"{}\", reason {e}", path.to_string_lossy()))?;
+    parse_hash_index(&content).map_err(|e| format!("Invalid hash index \"{}\", {e}", path.to_string_lossy()))
+}
+
+pub(crate) fn parse_hash_index(content: &str) -> Result<HashIndex, String> {
+    let index: HashIndex = serde_json::from_str(content).map_err(|e| format!("reason {e}"))?;
+    if index.version != HASH_INDEX_VERSION {
+        return Err(format!("unsupported version {} (supported version: {HASH_INDEX_VERSION})", index.version));
+    }
+    Ok(index)
+}
+
+#[derive(Deserialize)]
+struct HashIndexHeader {
+    #[expect(dead_code)]
+    version: u32,
+}
+
+// Checksum lists are plain text, so json object with version may only be czkawka index
+pub(crate) fn looks_like_hash_index(content: &str) -> bool {
+    content.trim_start().starts_with('{') && serde_json::from_str::<HashIndexHeader>(content).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_hash_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("index.json");
+        let entries = vec![
+            DuplicateEntry {
+                path: PathBuf::from("/photos/b.jpg"),
+                modified_date: 20,
+                size: 200,
+                hash: "bbb".to_string(),
+            },
+            DuplicateEntry {
+                path: PathBuf::from("/photos/a.jpg"),
+                modified_date: 10,
+                size: 100,
+                hash: "aaa".to_string(),
+            },
+        ];
+        let index = HashIndex::new(HashType::Sha256, &entries);
+        save_hash_index(&path, &index).unwrap();
+
+        let loaded = load_hash_index(&path).unwrap();
+        assert_eq!(loaded, index);
+        assert_eq!(loaded.entries[0].path, PathBuf::from("/photos/a.jpg"));
+        assert!(looks_like_hash_index(&std::fs::read_to_string(&path).unwrap()));
+    }
+
+    #[test]
+    fn test_load_hash_index_other_version() {
+        let content = r#"{"version":999,"hash_type":"Blake3","entries":[]}"#;
+        let err = parse_hash_index(content).unwrap_err();
+        assert!(err.contains("unsupported version 999"));
+        parse_hash_index("not json").unwrap_err();
+    }
+
+    #[test]
+    fn test_looks_like_hash_index() {
+        assert!(looks_like_hash_index(r#"{"version":1,"hash_type":"Blake3","entries":[]}"#));
+        assert!(!looks_like_hash_index(r#"{"hash_type":"Blake3","entries":[]}"#));
+        assert!(!looks_like_hash_index("{not json"));
+        assert!(!looks_like_hash_index("a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e  file.txt"));
+    }
+
+    #[test]
+    fn test_save_hash_index_keeps_previous_index_on_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("index.json");
+        let index = HashIndex::new(HashType::Blake3, &[]);
+        save_hash_index(&path, &index).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+
+        // Temporary file cannot be created when its path is a folder
+        std::fs::create_dir(path.with_extension("tmp")).unwrap();
+        save_hash_index(&path, &HashIndex::new(HashType::Sha256, &[])).unwrap_err();
+        assert_eq!(load_hash_index(&path).unwrap(), index);
+    }
+}
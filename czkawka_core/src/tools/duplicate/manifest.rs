@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+use crate::common::model::HashType;
+use crate::tools::duplicate::hash_index::{looks_like_hash_index, parse_hash_index};
+use crate::tools::duplicate::{DuplicateEntry, DuplicateFinder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ManifestMode {
+    // Scanned files, which content is already listed in manifests, are shown as duplicates of manifest entries
+    #[default]
+    AlreadyPresent,
+    // Scanned files, which content is not listed in any manifest
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    // Path exactly as written in manifest, usually relative to folder in which manifest was created
+    pub path: PathBuf,
+    // Checksum lists contain only hashes, size and modification date are known only for entries from hash index
+    pub size: Option<u64>,
+    pub modified_date: u64,
+    pub hash: String,
+}
+
+// BSD style tag and length of hex digest, used by `sha256sum`, `sha1sum`, `md5sum` and `b3sum`
+fn manifest_digest_format(hash_type: HashType) -> Option<(&'static str, usize)> {
+    match hash_type {
+        HashType::Blake3 => Some(("BLAKE3", 64)),
+        HashType::Sha256 => Some(("SHA256", 64)),
+        HashType::Sha1 => Some(("SHA1", 40)),
+        HashType::Md5 => Some(("MD5", 32)),
+        HashType::Crc32 | HashType::Xxh3 => None,
+    }
+}
+
+/// Loads checksum list in GNU format(`<hash>  <path>` or `<hash> *<path>`), in BSD format(`SHA256 (<path>) = <hash>`)
+/// or hash index exported by duplicate finder.
+/// Hashes must be calculated with the same algorithm as used in search, otherwise error is returned.
+pub fn load_checksum_manifest(manifest_path: &Path, hash_type: HashType) -> Result<Vec<ManifestEntry>, String> {
+    let content = fs::read_to_string(manifest_path).map_err(|e| format!("Cannot read checksum manifest \"{}\", reason {e}", manifest_path.to_string_lossy()))?;
+    if looks_like_hash_index(&content) {
+        let index = parse_hash_index(&content).map_err(|e| format!("Invalid hash index \"{}\", {e}", manifest_path.to_string_lossy()))?;
+        if index.hash_type != hash_type {
+            return Err(format!(
+                "Hash index \"{}\" contains {:?} hashes, but search uses {hash_type:?}",
+                manifest_path.to_string_lossy(),
+                index.hash_type
+            ));
+        }
+        return Ok(index
+            .entries
+            .into_iter()
+            .map(|e| ManifestEntry {
+                path: e.path,
+                size: Some(e.size),
+                modified_date: e.modified_date,
+                hash: e.hash,
+            })
+            .collect());
+    }
+    parse_checksum_manifest(&content, hash_type).map_err(|e| format!("Invalid checksum manifest \"{}\", {e}", manifest_path.to_string_lossy()))
+}
+
+// Entries are keyed by size and hash, size is known only for entries from hash index
+pub(crate) type ManifestEntries = HashMap<(Option<u64>, String), ManifestEntry>;
+
+// When the same content is listed multiple times, first entry is used
+pub(crate) fn load_checksum_manifests(manifest_paths: &[PathBuf], hash_type: HashType) -> Result<ManifestEntries, String> {
+    let mut manifest_entries: ManifestEntries = HashMap::new();
+    for manifest_path in manifest_paths {
+        for entry in load_checksum_manifest(manifest_path, hash_type)? {
+            manifest_entries.entry((entry.size, entry.hash.clone())).or_insert(entry);
+        }
+    }
+    debug!(
+        "load_checksum_manifests - loaded {} unique hashes from {} manifests",
+        manifest_entries.len(),
+        manifest_paths.len()
+    );
+    Ok(manifest_entries)
+}
+
+pub(crate) fn parse_checksum_manifest(content: &str, hash_type: HashType) -> Result<Vec<ManifestEntry>, String> {
+    let Some((tag, digest_len)) = manifest_digest_format(hash_type) else {
+        return Err(format!("hash type {hash_type:?} is not used by any checksum tool"));
+    };
+
+    let mut entries = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let entry = parse_gnu_line(line, digest_len)
+            .or_else(|| parse_bsd_line(line, tag, digest_len))
+            .ok_or_else(|| format!("line {} is not valid {tag} checksum entry", idx + 1))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+// GNU tools escape file names containing newline or backslash and mark such lines with leading backslash
+fn parse_gnu_line(line: &str, digest_len: usize) -> Option<ManifestEntry> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    let (hash, rest) = line.split_once(' ')?;
+    if !is_hex_digest(hash, digest_len) {
+        return None;
+    }
+    let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*'))?;
+    if path.is_empty() {
+        return None;
+    }
+    let path = if escaped { path.replace("\\n", "\n").replace("\\\\", "\\") } else { path.to_string() };
+    Some(ManifestEntry {
+        path: PathBuf::from(path),
+        size: None,
+        modified_date: 0,
+        hash: hash.to_ascii_lowercase(),
+    })
+}
+
+fn parse_bsd_line(line: &str, tag: &str, digest_len: usize) -> Option<ManifestEntry> {
+    let (left, hash) = line.rsplit_once(") = ")?;
+    let (line_tag, path) = left.split_once(" (")?;
+    if !line_tag.eq_ignore_ascii_case(tag) || !is_hex_digest(hash, digest_len) || path.is_empty() {
+        return None;
+    }
+    Some(ManifestEntry {
+        path: PathBuf::from(path),
+        size: None,
+        modified_date: 0,
+        hash: hash.to_ascii_lowercase(),
+    })
+}
+
+fn is_hex_digest(hash: &str, digest_len: usize) -> bool {
+    hash.len() == digest_len && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+impl DuplicateFinder {
+    // Manifest entries are used as virtual reference files, so results have the same shape as search with reference folders
+    // Entries with known size must have the same size as files, so collision of short hashes(e.g. CRC32) cannot match file with different size
+    pub(crate) fn match_manifest_entries(&mut self, manifest_entries: &ManifestEntries, full_hash_results: Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>)>) {
+        let manifest_mode = self.get_params().manifest_mode;
+        for (size, hash_map) in full_hash_results {
+            for (hash, files) in hash_map {
+                let manifest_entry = manifest_entries.get(&(Some(size), hash.clone())).or_else(|| manifest_entries.get(&(None, hash.clone())));
+                match (manifest_entry, manifest_mode) {
+                    (Some(manifest_entry), ManifestMode::AlreadyPresent) => {
+                        let reference = DuplicateEntry {
+                            path: manifest_entry.path.clone(),
+                            modified_date: manifest_entry.modified_date,
+                            size,
+                            hash,
+                        };
+                        self.files_with_identical_hashes_referenced.entry(size).or_default().push((reference, files));
+                    }
+                    (None, ManifestMode::Missing) => self.files_missing_from_manifests.extend(files),
+                    _ => {}
+                }
+            }
+        }
+        self.files_missing_from_manifests.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        for (size, groups) in &self.files_with_identical_hashes_referenced {
+            for (_reference, files) in groups {
+                self.information.number_of_duplicated_files_by_hash += files.len();
+                self.information.number_of_groups_by_hash += 1;
+                self.information.lost_space_by_hash += (files.len() as u64) * size;
+            }
+        }
+        self.information.number_of_files_missing_from_manifests = self.files_missing_from_manifests.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gnu_manifest() {
+        let content = "\
+# comment
+a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e  photos/a.jpg
+A591A6D40BF420404A011733CFB7B190D62C65BF0BCDA32B57B277D9AD9F146E *photos/b b.jpg
+\\a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e  photos/c\\\\d.jpg
+
+";
+        let entries = parse_checksum_manifest(content, HashType::Sha256).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, PathBuf::from("photos/a.jpg"));
+        assert_eq!(entries[1].path, PathBuf::from("photos/b b.jpg"));
+        assert_eq!(entries[1].hash, "a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e");
+        assert_eq!(entries[2].path, PathBuf::from("photos/c\\d.jpg"));
+    }
+
+    #[test]
+    fn test_parse_bsd_manifest() {
+        let content = "MD5 (photos/a (1).jpg) = b10a8db164e0754105b7a99be72e3fe5\n";
+        let entries = parse_checksum_manifest(content, HashType::Md5).unwrap();
+        assert_eq!(
+            entries,
+            vec![ManifestEntry {
+                path: PathBuf::from("photos/a (1).jpg"),
+                size: None,
+                modified_date: 0,
+                hash: "b10a8db164e0754105b7a99be72e3fe5".to_string(),
+            }]
+        );
+
+        // Tag must match hash type used in search
+        parse_checksum_manifest(content, HashType::Sha1).unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_invalid_manifest() {
+        // MD5 digest used with SHA-256 search
+        parse_checksum_manifest("b10a8db164e0754105b7a99be72e3fe5  a.jpg\n", HashType::Sha256).unwrap_err();
+        parse_checksum_manifest("not a checksum line\n", HashType::Md5).unwrap_err();
+        parse_checksum_manifest("b10a8db164e0754105b7a99be72e3fe5  a.jpg\n", HashType::Xxh3).unwrap_err();
+    }
+}
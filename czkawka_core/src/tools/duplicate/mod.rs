@@ -1,11 +1,14 @@
+pub mod archive;
 pub mod core;
+pub mod hash_index;
+pub mod manifest;
 #[cfg(test)]
 mod tests;
 pub mod traits;
 
 use std::cell::RefCell;
 use std::collections::BTreeMap;
-use std::fmt::Debug;
+use std::fmt::{Debug, Write as _};
 #[cfg(target_family = "unix")]
 use std::fs;
 use std::fs::File;
@@ -19,19 +22,23 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
+use crossbeam_channel::Sender;
 use indexmap::IndexSet;
 use serde::{Deserialize, Serialize};
+use sha2::Digest;
 use static_assertions::const_assert;
 use xxhash_rust::xxh3::Xxh3;
 
 use crate::common::model::{CheckingMethod, FileEntry, HashType};
 use crate::common::progress_stop_handler::check_if_stop_received;
 use crate::common::tool_data::CommonToolData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::{CacheEntry, ResultEntry};
 use crate::flc;
+use crate::tools::duplicate::manifest::ManifestMode;
 
 pub const PREHASHING_BUFFER_SIZE: u64 = 4 * 1024;
 pub const THREAD_BUFFER_SIZE: usize = 2 * 1024 * 1024;
+pub const VERIFICATION_BUFFER_SIZE: usize = 256 * 1024;
 
 thread_local! {
     static THREAD_BUFFER: RefCell<Vec<u8>> = RefCell::new(vec![0u8; THREAD_BUFFER_SIZE]);
@@ -54,6 +61,15 @@ impl ResultEntry for DuplicateEntry {
     fn get_size(&self) -> u64 {
         self.size
     }
+    fn get_hash(&self) -> Option<&str> {
+        if self.hash.is_empty() { None } else { Some(&self.hash) }
+    }
+}
+
+impl CacheEntry for DuplicateEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
 }
 
 impl FileEntry {
@@ -79,6 +95,7 @@ pub struct Info {
     pub number_of_duplicated_files_by_size_name: usize,
     pub lost_space_by_size: u64,
     pub lost_space_by_hash: u64,
+    pub number_of_files_missing_from_manifests: usize,
     pub scanning_time: Duration,
 }
 
@@ -90,6 +107,11 @@ pub struct DuplicateFinderParameters {
     pub minimal_cache_file_size: u64,
     pub minimal_prehash_cache_file_size: u64,
     pub case_sensitive_name_comparison: bool,
+    pub verify_before_delete: bool,
+    pub checksum_manifests: Vec<PathBuf>,
+    pub manifest_mode: ManifestMode,
+    pub hash_index_export_path: Option<PathBuf>,
+    pub check_archives: bool,
 }
 
 impl DuplicateFinderParameters {
@@ -108,6 +130,11 @@ impl DuplicateFinderParameters {
             minimal_cache_file_size,
             minimal_prehash_cache_file_size,
             case_sensitive_name_comparison,
+            verify_before_delete: false,
+            checksum_manifests: Vec::new(),
+            manifest_mode: ManifestMode::default(),
+            hash_index_export_path: None,
+            check_archives: false,
         }
     }
 }
@@ -131,6 +158,13 @@ pub struct DuplicateFinder {
     files_with_identical_size_referenced: BTreeMap<u64, (DuplicateEntry, Vec<DuplicateEntry>)>,
     // File Size, next grouped by file size, next grouped by hash
     files_with_identical_hashes_referenced: BTreeMap<u64, Vec<(DuplicateEntry, Vec<DuplicateEntry>)>>,
+    // Files which content is not listed in any of checksum manifests
+    files_missing_from_manifests: Vec<DuplicateEntry>,
+    // File Size, already hashed files found inside archives
+    archive_members: BTreeMap<u64, Vec<DuplicateEntry>>,
+    // File Size, normal files which have the same size as any archive member
+    files_with_size_of_archive_members: BTreeMap<u64, Vec<DuplicateEntry>>,
+    results_sender: Option<Sender<Vec<DuplicateEntry>>>,
     params: DuplicateFinderParameters,
 }
 
@@ -198,6 +232,42 @@ impl DuplicateFinder {
         self.common_data.dry_run = dry_run;
     }
 
+    /// Before deleting or linking files found by hash, compares every file byte by byte with file which is kept.
+    /// Files with different content are reported as errors and left untouched.
+    pub fn set_verify_before_delete(&mut self, verify_before_delete: bool) {
+        self.params.verify_before_delete = verify_before_delete;
+    }
+
+    /// Instead of looking for duplicates between scanned files, compares them with hashes from checksum manifests.
+    /// Manifests must use the same hash type as search.
+    pub fn set_checksum_manifests(&mut self, checksum_manifests: Vec<PathBuf>, manifest_mode: ManifestMode) {
+        self.params.checksum_manifests = checksum_manifests;
+        self.params.manifest_mode = manifest_mode;
+    }
+
+    /// Saves hashes of all scanned files to given file, which later may be used as checksum manifest on other machine.
+    /// Every file is hashed, even when no other file has the same size.
+    pub fn set_hash_index_export_path(&mut self, hash_index_export_path: Option<PathBuf>) {
+        self.params.hash_index_export_path = hash_index_export_path;
+    }
+
+    /// Looks also inside zip, 7z and tar archives, their members are compared with normal files and with each other.
+    /// Members are only reported, they are never deleted or linked. Works only when checking by hash.
+    pub fn set_check_archives(&mut self, check_archives: bool) {
+        self.params.check_archives = check_archives;
+    }
+
+    pub fn get_files_missing_from_manifests(&self) -> &[DuplicateEntry] {
+        &self.files_missing_from_manifests
+    }
+
+    /// Every confirmed group of duplicates is sent during search, without waiting for all groups.
+    /// When checking by hash, group is sent just after hashing all files with its size.
+    /// When reference folders are used, first entry of group is file from reference folder.
+    pub fn set_results_sender(&mut self, results_sender: Sender<Vec<DuplicateEntry>>) {
+        self.results_sender = Some(results_sender);
+    }
+
     pub fn get_use_reference(&self) -> bool {
         self.common_data.use_reference_folders
     }
@@ -294,6 +364,44 @@ fn read_filling(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize
     Ok(total)
 }
 
+// Hash may collide(especially CRC32 and XXH3) or come from outdated cache entry, so only comparing whole content
+// gives certainty that removed file is really the same as kept one
+// Returns None when stop was requested
+pub(crate) fn files_have_identical_content(first: &Path, second: &Path, stop_flag: &Arc<AtomicBool>) -> Result<Option<bool>, String> {
+    let open_file = |path: &Path| File::open(path).map_err(|e| flc!("core_unable_verify_content_of_file", file = path.to_string_lossy(), reason = e.to_string()));
+    let mut first_file = open_file(first)?;
+    let mut second_file = open_file(second)?;
+
+    let (first_len, second_len) = match (first_file.metadata(), second_file.metadata()) {
+        (Ok(first_metadata), Ok(second_metadata)) => (first_metadata.len(), second_metadata.len()),
+        (Err(e), _) => return Err(flc!("core_unable_verify_content_of_file", file = first.to_string_lossy(), reason = e.to_string())),
+        (_, Err(e)) => return Err(flc!("core_unable_verify_content_of_file", file = second.to_string_lossy(), reason = e.to_string())),
+    };
+    if first_len != second_len {
+        return Ok(Some(false));
+    }
+
+    let mut first_buffer = vec![0u8; VERIFICATION_BUFFER_SIZE];
+    let mut second_buffer = vec![0u8; VERIFICATION_BUFFER_SIZE];
+    loop {
+        let first_n =
+            read_filling(&mut first_file, &mut first_buffer).map_err(|e| flc!("core_unable_verify_content_of_file", file = first.to_string_lossy(), reason = e.to_string()))?;
+        let second_n =
+            read_filling(&mut second_file, &mut second_buffer).map_err(|e| flc!("core_unable_verify_content_of_file", file = second.to_string_lossy(), reason = e.to_string()))?;
+
+        #[expect(clippy::indexing_slicing)] // Safe, because read_filling returns at most buffer size
+        if first_buffer[..first_n] != second_buffer[..second_n] {
+            return Ok(Some(false));
+        }
+        if first_n == 0 {
+            return Ok(Some(true));
+        }
+        if check_if_stop_received(stop_flag) {
+            return Ok(None);
+        }
+    }
+}
+
 pub fn hash_calculation(
     buffer: &mut [u8],
     file_entry: &DuplicateEntry,
@@ -353,6 +461,42 @@ impl MyHasher for Xxh3 {
     }
 }
 
+impl MyHasher for sha2::Sha256 {
+    fn update(&mut self, bytes: &[u8]) {
+        Digest::update(self, bytes);
+    }
+    fn finalize(&self) -> String {
+        digest_to_hex(&Digest::finalize(self.clone()))
+    }
+}
+
+impl MyHasher for sha1::Sha1 {
+    fn update(&mut self, bytes: &[u8]) {
+        Digest::update(self, bytes);
+    }
+    fn finalize(&self) -> String {
+        digest_to_hex(&Digest::finalize(self.clone()))
+    }
+}
+
+impl MyHasher for md5::Md5 {
+    fn update(&mut self, bytes: &[u8]) {
+        Digest::update(self, bytes);
+    }
+    fn finalize(&self) -> String {
+        digest_to_hex(&Digest::finalize(self.clone()))
+    }
+}
+
+// Lowercase hex, the same format as used by `sha256sum` and similar tools
+fn digest_to_hex(digest: &[u8]) -> String {
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
 #[cfg(test)]
 mod tests2 {
     use std::fs::File;
@@ -533,4 +677,24 @@ mod tests2 {
         assert!(!r.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn test_files_have_identical_content() -> io::Result<()> {
+        let dir = tempfile::Builder::new().tempdir()?;
+        let (a, b, c, d) = (dir.path().join("a"), dir.path().join("b"), dir.path().join("c"), dir.path().join("d"));
+        let content = vec![7u8; VERIFICATION_BUFFER_SIZE + 10];
+        let mut changed_content = content.clone();
+        changed_content[VERIFICATION_BUFFER_SIZE + 5] = 8;
+        std::fs::write(&a, &content)?;
+        std::fs::write(&b, &content)?;
+        std::fs::write(&c, &changed_content)?;
+        std::fs::write(&d, &content[..100])?;
+
+        let stop_flag = Arc::default();
+        assert_eq!(files_have_identical_content(&a, &b, &stop_flag), Ok(Some(true)));
+        assert_eq!(files_have_identical_content(&a, &c, &stop_flag), Ok(Some(false)));
+        assert_eq!(files_have_identical_content(&a, &d, &stop_flag), Ok(Some(false)));
+        files_have_identical_content(&a, &dir.path().join("missing"), &stop_flag).expect_err("Missing file should not be verified");
+        Ok(())
+    }
 }
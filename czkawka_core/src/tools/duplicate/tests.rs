@@ -1,12 +1,17 @@
 use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
+use crossbeam_channel::unbounded;
 use tempfile::TempDir;
 
-use crate::common::model::{CheckingMethod, HashType};
-use crate::common::tool_data::CommonData;
-use crate::common::traits::Search;
+use crate::common::model::{CheckingMethod, HashType, WorkContinueStatus};
+use crate::common::tool_data::{CommonData, DeleteMethod};
+use crate::common::traits::{DeletingItems, Search};
+use crate::tools::duplicate::archive::is_archive_member;
+use crate::tools::duplicate::hash_index::{load_hash_index, save_hash_index};
+use crate::tools::duplicate::manifest::ManifestMode;
 use crate::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
 
 #[test]
@@ -35,6 +40,71 @@ fn test_find_duplicates_by_hash() {
     assert_eq!(info.number_of_duplicated_files_by_hash, 1, "Should find 1 duplicate file");
 }
 
+#[test]
+fn test_results_sender_streams_confirmed_groups() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("a1.txt"), b"first content").unwrap();
+    fs::write(path.join("a2.txt"), b"first content").unwrap();
+    fs::write(path.join("b1.txt"), b"second content, longer").unwrap();
+    fs::write(path.join("b2.txt"), b"second content, longer").unwrap();
+    fs::write(path.join("b3.txt"), b"second content, LONGER").unwrap();
+
+    for check_method in [CheckingMethod::Hash, CheckingMethod::Size] {
+        let params = DuplicateFinderParameters::new(check_method, HashType::Blake3, false, 0, 0, true);
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_recursive_search(true);
+        finder.set_use_cache(false);
+        let (results_sender, results_receiver) = unbounded();
+        finder.set_results_sender(results_sender);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        finder.search(&stop_flag, None);
+        drop(finder);
+
+        let mut group_sizes: Vec<usize> = results_receiver.iter().map(|group| group.len()).collect();
+        group_sizes.sort_unstable();
+        let expected = if check_method == CheckingMethod::Hash { vec![2, 2] } else { vec![2, 3] };
+        assert_eq!(group_sizes, expected, "{check_method:?}");
+    }
+}
+
+#[test]
+fn test_results_sender_skips_files_differing_only_in_middle() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    // Bigger than prehash, which contains only start and end of file
+    let content = vec![b'a'; 64 * 1024];
+    let mut changed_in_middle = content.clone();
+    changed_in_middle[32 * 1024] = b'b';
+    fs::write(path.join("first.bin"), &content).unwrap();
+    fs::write(path.join("second.bin"), &content).unwrap();
+    fs::write(path.join("changed.bin"), &changed_in_middle).unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    let (results_sender, results_receiver) = unbounded();
+    finder.set_results_sender(results_sender);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    let full_hash = finder.get_files_sorted_by_hash().values().flatten().next().unwrap()[0].hash.clone();
+    drop(finder);
+
+    let groups: Vec<_> = results_receiver.iter().collect();
+    assert_eq!(groups.len(), 1, "{groups:?}");
+    let mut paths: Vec<_> = groups[0].iter().map(|e| e.path.clone()).collect();
+    paths.sort();
+    assert_eq!(paths, vec![path.join("first.bin"), path.join("second.bin")]);
+    assert!(groups[0].iter().all(|e| e.hash == full_hash));
+}
+
 #[test]
 fn test_find_duplicates_by_size() {
     let temp_dir = TempDir::new().unwrap();
@@ -140,3 +210,318 @@ fn test_lost_space_calculation() {
     let info = finder.get_information();
     assert_eq!(info.lost_space_by_hash, 200, "Should calculate 200 bytes lost space (2 duplicate files * 100 bytes)");
 }
+
+#[test]
+fn test_verify_before_delete_skips_changed_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let content = vec![b'A'; 100];
+    fs::write(path.join("file1.txt"), &content).unwrap();
+    fs::write(path.join("file2.txt"), &content).unwrap();
+    fs::write(path.join("file3.txt"), &content).unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_verify_before_delete(true);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    assert_eq!(finder.get_information().number_of_duplicated_files_by_hash, 2);
+
+    // Simulates file changed after hashing, or outdated cache entry
+    fs::write(path.join("file3.txt"), vec![b'B'; 100]).unwrap();
+
+    finder.set_delete_method(DeleteMethod::Delete);
+    assert_eq!(finder.delete_files(&stop_flag, None), WorkContinueStatus::Continue);
+
+    assert!(path.join("file3.txt").exists(), "File with different content should not be deleted");
+    assert!(!finder.get_text_messages().errors.is_empty(), "Different content should be reported as error");
+}
+
+#[test]
+fn test_links_rejected_for_files_with_same_size() {
+    for delete_method in [DeleteMethod::HardLink, DeleteMethod::Reflink, DeleteMethod::Symlink] {
+        let temp_dir = TempDir::new().unwrap();
+        let reference_dir = temp_dir.path().join("reference");
+        let other_dir = temp_dir.path().join("other");
+        fs::create_dir(&reference_dir).unwrap();
+        fs::create_dir(&other_dir).unwrap();
+        fs::write(reference_dir.join("file.txt"), b"content A").unwrap();
+        fs::write(other_dir.join("file.txt"), b"content B").unwrap();
+
+        let params = DuplicateFinderParameters::new(CheckingMethod::Size, HashType::Blake3, false, 0, 0, true);
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(false);
+        finder.set_included_paths(vec![reference_dir.clone(), other_dir.clone()]);
+        finder.set_reference_paths(vec![reference_dir.clone()]);
+        finder.set_delete_method(delete_method);
+        finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+        assert_eq!(fs::read(other_dir.join("file.txt")).unwrap(), b"content B");
+        assert!(!fs::symlink_metadata(other_dir.join("file.txt")).unwrap().is_symlink());
+        assert!(finder.get_text_messages().errors.iter().any(|e| e.contains("HASH")), "{delete_method:?}");
+    }
+}
+
+#[test]
+fn test_find_files_from_checksum_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("copied.txt"), b"Hello World").unwrap();
+    fs::write(path.join("new.txt"), b"Not yet archived").unwrap();
+    let manifest_path = manifest_dir.path().join("archive.sha256");
+    fs::write(&manifest_path, "a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e  archive/copied.txt\n").unwrap();
+
+    for manifest_mode in [ManifestMode::AlreadyPresent, ManifestMode::Missing] {
+        let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Sha256, false, 0, 0, true);
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(false);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_checksum_manifests(vec![manifest_path.clone()], manifest_mode);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        finder.search(&stop_flag, None);
+        assert!(finder.get_text_messages().critical.is_none());
+
+        match manifest_mode {
+            ManifestMode::AlreadyPresent => {
+                let groups: Vec<_> = finder.get_files_with_identical_hashes_referenced().values().flatten().collect();
+                assert_eq!(groups.len(), 1);
+                let (reference, files) = groups[0];
+                assert_eq!(reference.path, std::path::PathBuf::from("archive/copied.txt"));
+                assert_eq!(files.len(), 1);
+                assert_eq!(files[0].path, path.join("copied.txt"));
+                assert!(finder.get_files_missing_from_manifests().is_empty());
+            }
+            ManifestMode::Missing => {
+                assert!(finder.get_files_with_identical_hashes_referenced().is_empty());
+                let missing: Vec<_> = finder.get_files_missing_from_manifests().iter().map(|e| e.path.clone()).collect();
+                assert_eq!(missing, vec![path.join("new.txt")]);
+                assert_eq!(finder.get_information().number_of_files_missing_from_manifests, 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_verify_before_delete_rejected_with_checksum_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("copied.txt"), b"Hello World").unwrap();
+    let manifest_path = manifest_dir.path().join("archive.sha256");
+    fs::write(&manifest_path, "a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e  archive/copied.txt\n").unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Sha256, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_checksum_manifests(vec![manifest_path], ManifestMode::AlreadyPresent);
+    finder.set_verify_before_delete(true);
+    finder.set_delete_method(DeleteMethod::Delete);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert!(path.join("copied.txt").exists(), "File should not be deleted without verification");
+    assert!(finder.get_text_messages().errors.iter().any(|e| e.contains("checksum manifests")));
+}
+
+#[test]
+fn test_hash_index_entries_match_only_files_with_same_size() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_dir = TempDir::new().unwrap();
+    let index_path = index_dir.path().join("index.json");
+    fs::write(temp_dir.path().join("file.txt"), b"Hello World").unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Crc32, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_hash_index_export_path(Some(index_path.clone()));
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    // Simulates collision of hash with file of different size
+    let mut index = load_hash_index(&index_path).unwrap();
+    let mut other_entry = index.entries[0].clone();
+    other_entry.hash = "0".to_string();
+    index.entries[0].size += 1;
+    // File with the same size is present, so scanned file is hashed
+    index.entries.push(other_entry);
+    save_hash_index(&index_path, &index).unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Crc32, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_checksum_manifests(vec![index_path], ManifestMode::AlreadyPresent);
+    finder.set_delete_method(DeleteMethod::Delete);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert!(finder.get_files_with_identical_hashes_referenced().is_empty());
+    assert!(temp_dir.path().join("file.txt").exists());
+}
+
+#[test]
+fn test_export_hash_index_and_use_as_manifest() {
+    let first_machine = TempDir::new().unwrap();
+    let second_machine = TempDir::new().unwrap();
+    let index_dir = TempDir::new().unwrap();
+    let index_path = index_dir.path().join("index.json");
+
+    // Unique sizes, so files would not be hashed in normal search
+    fs::write(first_machine.path().join("photo.jpg"), b"Hello World").unwrap();
+    fs::write(first_machine.path().join("document.txt"), b"Only on first machine").unwrap();
+    fs::write(second_machine.path().join("photo_copy.jpg"), b"Hello World").unwrap();
+    fs::write(second_machine.path().join("new.txt"), b"Only on second").unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_included_paths(vec![first_machine.path().to_path_buf()]);
+    finder.set_hash_index_export_path(Some(index_path.clone()));
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    assert!(finder.get_text_messages().errors.is_empty());
+    assert!(finder.get_files_sorted_by_hash().is_empty());
+
+    let index = load_hash_index(&index_path).unwrap();
+    assert_eq!(index.hash_type, HashType::Blake3);
+    assert_eq!(index.entries.len(), 2);
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_included_paths(vec![second_machine.path().to_path_buf()]);
+    finder.set_checksum_manifests(vec![index_path.clone()], ManifestMode::AlreadyPresent);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    assert!(finder.get_text_messages().critical.is_none());
+
+    let groups: Vec<_> = finder.get_files_with_identical_hashes_referenced().values().flatten().collect();
+    assert_eq!(groups.len(), 1);
+    let (reference, files) = groups[0];
+    assert_eq!(reference.path, first_machine.path().join("photo.jpg"));
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, second_machine.path().join("photo_copy.jpg"));
+
+    // Index created with other hash type cannot be used
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Xxh3, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![second_machine.path().to_path_buf()]);
+    finder.set_checksum_manifests(vec![index_path], ManifestMode::AlreadyPresent);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    assert!(finder.get_text_messages().critical.is_some());
+}
+
+#[test]
+fn test_find_duplicates_inside_archives() {
+    use std::io::Write;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    let photo: &[u8] = b"photo content, stored also in archives";
+    fs::write(path.join("photo.jpg"), photo).unwrap();
+    fs::write(path.join("notes.txt"), b"notes").unwrap();
+
+    let mut zip = zip::ZipWriter::new(fs::File::create(path.join("export.zip")).unwrap());
+    zip.start_file("2019/photo.jpg", zip::write::SimpleFileOptions::default()).unwrap();
+    zip.write_all(photo).unwrap();
+    zip.start_file("other.txt", zip::write::SimpleFileOptions::default()).unwrap();
+    zip.write_all(b"other").unwrap();
+    zip.finish().unwrap();
+
+    let encoder = flate2::write::GzEncoder::new(fs::File::create(path.join("backup.tar.gz")).unwrap(), flate2::Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+    let mut header = tar::Header::new_gnu();
+    header.set_size(photo.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, "photos/photo_copy.jpg", photo).unwrap();
+    tar.into_inner().unwrap().finish().unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_check_archives(true);
+    finder.set_delete_method(DeleteMethod::AllExceptNewest);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert!(finder.get_text_messages().warnings.is_empty(), "{:?}", finder.get_text_messages().warnings);
+    let groups: Vec<_> = finder.get_files_sorted_by_hash().values().flatten().collect();
+    assert_eq!(groups.len(), 1, "{groups:?}");
+    let mut paths: Vec<_> = groups[0].iter().map(|e| e.path.clone()).collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            path.join("backup.tar.gz!/photos/photo_copy.jpg"),
+            path.join("export.zip!/2019/photo.jpg"),
+            path.join("photo.jpg")
+        ]
+    );
+    assert!(paths.iter().take(2).all(|p| is_archive_member(p)));
+    assert!(!is_archive_member(&path.join("photo.jpg")));
+
+    // Members are read only, so the only normal file is kept
+    assert!(finder.get_text_messages().errors.is_empty(), "{:?}", finder.get_text_messages().errors);
+    assert!(path.join("photo.jpg").exists());
+}
+
+#[test]
+fn test_results_sender_streams_groups_with_size_of_archive_members() {
+    use std::io::Write;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    // Normal files with the same size as member, but different content, are hashed together with archive members
+    fs::write(path.join("first.txt"), b"content 1").unwrap();
+    fs::write(path.join("second.txt"), b"content 1").unwrap();
+    fs::write(path.join("photo.jpg"), b"content 2").unwrap();
+
+    let mut zip = zip::ZipWriter::new(fs::File::create(path.join("export.zip")).unwrap());
+    zip.start_file("photo.jpg", zip::write::SimpleFileOptions::default()).unwrap();
+    zip.write_all(b"content 2").unwrap();
+    zip.finish().unwrap();
+
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_check_archives(true);
+    let (results_sender, results_receiver) = unbounded();
+    finder.set_results_sender(results_sender);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    drop(finder);
+
+    let mut groups: Vec<Vec<PathBuf>> = results_receiver
+        .iter()
+        .map(|group| {
+            let mut paths: Vec<PathBuf> = group.into_iter().map(|e| e.path).collect();
+            paths.sort();
+            paths
+        })
+        .collect();
+    groups.sort();
+    assert_eq!(
+        groups,
+        vec![
+            vec![path.join("export.zip!/photo.jpg"), path.join("photo.jpg")],
+            vec![path.join("first.txt"), path.join("second.txt")]
+        ]
+    );
+}
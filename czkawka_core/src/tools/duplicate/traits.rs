@@ -8,10 +8,12 @@ use crossbeam_channel::Sender;
 use fun_time::fun_time;
 use humansize::{BINARY, format_size};
 
+use crate::common::deletion::groups_with_reference_first;
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
 use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::tools::duplicate::manifest::ManifestMode;
 use crate::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters, Info};
 
 impl AllTraits for DuplicateFinder {}
@@ -23,21 +25,80 @@ impl DeletingItems for DuplicateFinder {
             return WorkContinueStatus::Continue;
         }
 
+        // Files found by name or size may have different content, so replacing them with link to other file would lose their data
+        if self.get_params().check_method != CheckingMethod::Hash
+            && matches!(self.common_data.delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::Symlink)
+        {
+            self.common_data
+                .text_messages
+                .errors
+                .push("Files can be replaced with links only when they are found by HASH search method, because other methods don't compare content".to_string());
+            return WorkContinueStatus::Continue;
+        }
+
+        if !self.get_params().checksum_manifests.is_empty() {
+            // Missing files are unique, so there is nothing to remove
+            if self.get_params().manifest_mode == ManifestMode::Missing {
+                return WorkContinueStatus::Continue;
+            }
+            // Files from manifests exists only as hashes, so cannot be used as link target
+            if matches!(self.common_data.delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::Symlink) {
+                self.common_data
+                    .text_messages
+                    .errors
+                    .push("Files found in checksum manifests cannot be replaced with links, because manifest entries are not real files".to_string());
+                return WorkContinueStatus::Continue;
+            }
+            // Original file may be only manifest entry, so there is no content to compare with
+            if self.get_params().verify_before_delete {
+                self.common_data
+                    .text_messages
+                    .errors
+                    .push("Content of files cannot be verified before deleting when using checksum manifests, because manifest entries are not real files".to_string());
+                return WorkContinueStatus::Continue;
+            }
+        }
+
         if self.common_data.use_reference_folders {
-            if self.common_data.delete_method == DeleteMethod::HardLink {
+            let hash_groups = if self.get_params().check_method == CheckingMethod::Hash {
+                let Some(hash_groups) = self.hash_groups_to_process(stop_flag) else {
+                    return WorkContinueStatus::Stop;
+                };
+                hash_groups
+            } else {
+                Vec::new()
+            };
+
+            if matches!(self.common_data.delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::Symlink) {
                 // For each group the referenced map already holds (original, destinations).
-                // Feed this directly into HardlinkingFiles so the reference file is used as
-                // the hardlink source and the non-reference duplicates are replaced.
+                // Feed this directly into Hardlinking/Reflinking/SymlinkingFiles so the reference file is used as
+                // the link source and the non-reference duplicates are replaced.
                 let hardlink_items = match self.get_params().check_method {
                     CheckingMethod::Name => self.files_with_identical_names_referenced.values().cloned().collect::<Vec<_>>(),
                     CheckingMethod::SizeName => self.files_with_identical_size_names_referenced.values().cloned().collect::<Vec<_>>(),
                     CheckingMethod::Size => self.files_with_identical_size_referenced.values().cloned().collect::<Vec<_>>(),
-                    CheckingMethod::Hash => self.files_with_identical_hashes_referenced.values().flatten().cloned().collect::<Vec<_>>(),
+                    CheckingMethod::Hash => hash_groups,
                     _ => panic!(),
                 };
-                return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::HardlinkingFiles(hardlink_items));
+                let delete_item_type = match self.common_data.delete_method {
+                    DeleteMethod::Reflink => DeleteItemType::ReflinkingFiles(hardlink_items),
+                    DeleteMethod::Symlink => DeleteItemType::SymlinkingFiles(hardlink_items),
+                    _ => DeleteItemType::HardlinkingFiles(hardlink_items),
+                };
+                return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, delete_item_type);
             }
-            // For non-HardLink methods with reference folders, delete every non-reference
+            if self.common_data.delete_method == DeleteMethod::Rule {
+                // Rule may compare files with reference, so reference is passed as first item of each group
+                let groups = match self.get_params().check_method {
+                    CheckingMethod::Name => groups_with_reference_first(self.files_with_identical_names_referenced.values()),
+                    CheckingMethod::SizeName => groups_with_reference_first(self.files_with_identical_size_names_referenced.values()),
+                    CheckingMethod::Size => groups_with_reference_first(self.files_with_identical_size_referenced.values()),
+                    CheckingMethod::Hash => groups_with_reference_first(hash_groups.iter()),
+                    _ => panic!(),
+                };
+                return self.delete_advanced_elements_and_add_to_messages(stop_flag, progress_sender, groups);
+            }
+            // For non-link methods with reference folders, delete every non-reference
             // duplicate.  The reference file is already retained as the "original", so we
             // flatten all destination lists and delete them unconditionally - bypassing the
             // "keep one" group-selection logic of delete_advanced_elements which would
@@ -50,12 +111,7 @@ impl DeletingItems for DuplicateFinder {
                     .flat_map(|(_, files)| files.iter().cloned())
                     .collect(),
                 CheckingMethod::Size => self.files_with_identical_size_referenced.values().flat_map(|(_, files)| files.iter().cloned()).collect(),
-                CheckingMethod::Hash => self
-                    .files_with_identical_hashes_referenced
-                    .values()
-                    .flatten()
-                    .flat_map(|(_, files)| files.iter().cloned())
-                    .collect(),
+                CheckingMethod::Hash => hash_groups.into_iter().flat_map(|(_, files)| files).collect(),
                 _ => panic!(),
             };
             return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
@@ -64,7 +120,12 @@ impl DeletingItems for DuplicateFinder {
         let files_to_delete = match self.get_params().check_method {
             CheckingMethod::Name => self.files_with_identical_names.values().cloned().collect::<Vec<_>>(),
             CheckingMethod::SizeName => self.files_with_identical_size_names.values().cloned().collect::<Vec<_>>(),
-            CheckingMethod::Hash => self.files_with_identical_hashes.values().flatten().cloned().collect::<Vec<_>>(),
+            CheckingMethod::Hash => {
+                let Some(hash_groups) = self.hash_groups_to_process(stop_flag) else {
+                    return WorkContinueStatus::Stop;
+                };
+                groups_with_reference_first(hash_groups.iter())
+            }
             CheckingMethod::Size => self.files_with_identical_size.values().cloned().collect::<Vec<_>>(),
             _ => panic!(),
         };
@@ -89,18 +150,31 @@ impl Search for DuplicateFinder {
                     if self.common_data.stopped_search {
                         return;
                     }
+                    self.send_collected_groups();
                 }
                 CheckingMethod::SizeName => {
                     self.common_data.stopped_search = self.check_files_size_name(stop_flag, progress_sender) == WorkContinueStatus::Stop;
                     if self.common_data.stopped_search {
                         return;
                     }
+                    self.send_collected_groups();
                 }
                 CheckingMethod::Size => {
                     self.common_data.stopped_search = self.check_files_size(stop_flag, progress_sender) == WorkContinueStatus::Stop;
                     if self.common_data.stopped_search {
                         return;
                     }
+                    self.send_collected_groups();
+                }
+                CheckingMethod::Hash if !self.get_params().checksum_manifests.is_empty() || self.get_params().hash_index_export_path.is_some() => {
+                    // Entries from manifests behave like files from reference folders
+                    if !self.get_params().checksum_manifests.is_empty() {
+                        self.common_data.use_reference_folders = true;
+                    }
+                    self.common_data.stopped_search = self.check_files_hash_all(stop_flag, progress_sender) == WorkContinueStatus::Stop;
+                    if self.common_data.stopped_search || self.common_data.text_messages.critical.is_some() {
+                        return;
+                    }
                 }
                 CheckingMethod::Hash => {
                     self.common_data.stopped_search = self.check_files_size(stop_flag, progress_sender) == WorkContinueStatus::Stop;
@@ -298,6 +372,24 @@ impl PrintResults for DuplicateFinder {
                     write!(writer, "Not found any duplicates.")?;
                 }
             }
+            CheckingMethod::Hash if self.get_params().manifest_mode == ManifestMode::Missing && !self.get_params().checksum_manifests.is_empty() => {
+                if !self.files_missing_from_manifests.is_empty() {
+                    writeln!(
+                        writer,
+                        "-------------------------------------------------Files missing from checksum manifests-------------------------------------------------"
+                    )?;
+                    writeln!(
+                        writer,
+                        "Found {} files which are not listed in any checksum manifest.",
+                        self.information.number_of_files_missing_from_manifests
+                    )?;
+                    for file_entry in &self.files_missing_from_manifests {
+                        writeln!(writer, "\"{}\"", file_entry.path.to_string_lossy())?;
+                    }
+                } else {
+                    write!(writer, "All files are listed in checksum manifests.")?;
+                }
+            }
             CheckingMethod::Hash => {
                 if !self.files_with_identical_hashes.is_empty() {
                     writeln!(
@@ -351,23 +443,26 @@ impl PrintResults for DuplicateFinder {
     }
 
     // TODO - check if is possible to save also data in header about size and name in SizeName mode - https://github.com/qarmin/czkawka/issues/1137
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> io::Result<()> {
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> io::Result<()> {
+        if self.get_params().manifest_mode == ManifestMode::Missing && !self.get_params().checksum_manifests.is_empty() {
+            return self.write_results_as_json_internal(writer, &self.files_missing_from_manifests, pretty_print);
+        }
         if self.get_use_reference() {
             match self.get_params().check_method {
-                CheckingMethod::Name => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_names_referenced, pretty_print),
+                CheckingMethod::Name => self.write_results_as_json_internal(writer, &self.files_with_identical_names_referenced, pretty_print),
                 CheckingMethod::SizeName => {
-                    self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size_names_referenced.values().collect::<Vec<_>>(), pretty_print)
+                    self.write_results_as_json_internal(writer, &self.files_with_identical_size_names_referenced.values().collect::<Vec<_>>(), pretty_print)
                 }
-                CheckingMethod::Size => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size_referenced, pretty_print),
-                CheckingMethod::Hash => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_hashes_referenced, pretty_print),
+                CheckingMethod::Size => self.write_results_as_json_internal(writer, &self.files_with_identical_size_referenced, pretty_print),
+                CheckingMethod::Hash => self.write_results_as_json_internal(writer, &self.files_with_identical_hashes_referenced, pretty_print),
                 _ => panic!(),
             }
         } else {
             match self.get_params().check_method {
-                CheckingMethod::Name => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_names, pretty_print),
-                CheckingMethod::SizeName => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size_names.values().collect::<Vec<_>>(), pretty_print),
-                CheckingMethod::Size => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size, pretty_print),
-                CheckingMethod::Hash => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_hashes, pretty_print),
+                CheckingMethod::Name => self.write_results_as_json_internal(writer, &self.files_with_identical_names, pretty_print),
+                CheckingMethod::SizeName => self.write_results_as_json_internal(writer, &self.files_with_identical_size_names.values().collect::<Vec<_>>(), pretty_print),
+                CheckingMethod::Size => self.write_results_as_json_internal(writer, &self.files_with_identical_size, pretty_print),
+                CheckingMethod::Hash => self.write_results_as_json_internal(writer, &self.files_with_identical_hashes, pretty_print),
                 _ => panic!(),
             }
         }
@@ -398,5 +493,6 @@ impl CommonData for DuplicateFinder {
             || self.get_information().number_of_duplicated_files_by_name > 0
             || self.get_information().number_of_duplicated_files_by_size > 0
             || self.get_information().number_of_duplicated_files_by_size_name > 0
+            || self.get_information().number_of_files_missing_from_manifests > 0
     }
 }
@@ -0,0 +1,356 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::model::{CheckingMethod, ToolType, WorkContinueStatus};
+use crate::common::progress_data::ProgressData;
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::tools::duplicate::{DuplicateEntry, DuplicateFinder, DuplicateFinderParameters};
+use crate::tools::duplicate_folders::{DuplicateFolderEntry, DuplicateFolders, DuplicateFoldersParameters, Info, SimilarFolderPair};
+
+// Folder with at least one file inside it or inside its subfolders
+#[derive(Default)]
+pub(crate) struct FolderNode {
+    // Indexes of files placed directly in folder
+    files: Vec<usize>,
+    subfolders: Vec<PathBuf>,
+}
+
+#[derive(Clone, Copy)]
+struct FolderSummary {
+    size: u64,
+    number_of_files: usize,
+    modified_date: u64,
+    // Calculated from names and sizes, folders with different structure hash cannot have identical content
+    structure_hash: blake3::Hash,
+    content_hash: Option<blake3::Hash>,
+}
+
+impl DuplicateFolders {
+    pub fn new(params: DuplicateFoldersParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::DuplicateFolders),
+            information: Info::default(),
+            duplicated_folders: Vec::new(),
+            similar_folders: Vec::new(),
+            files_in_folders: HashMap::new(),
+            params,
+        }
+    }
+
+    pub fn get_params(&self) -> &DuplicateFoldersParameters {
+        &self.params
+    }
+
+    pub const fn get_duplicated_folders(&self) -> &Vec<Vec<DuplicateFolderEntry>> {
+        &self.duplicated_folders
+    }
+
+    pub const fn get_similar_folders(&self) -> &Vec<SimilarFolderPair> {
+        &self.similar_folders
+    }
+
+    /// Instead of identical folders, reports pairs of folders in which at least `minimal_overlap` percent of files have the same content.
+    /// Files are compared only by content, so renamed or moved files are also treated as common.
+    pub fn set_minimal_overlap(&mut self, minimal_overlap: Option<u8>) {
+        self.params.minimal_overlap = minimal_overlap;
+    }
+
+    pub const fn get_information(&self) -> Info {
+        self.information
+    }
+
+    #[fun_time(message = "find_duplicated_folders", level = "debug")]
+    pub(crate) fn find_duplicated_folders(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|fe| fe.size)
+            .checking_method(CheckingMethod::Hash)
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        let files: Vec<DuplicateEntry> = match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.common_data.text_messages.warnings.extend(warnings);
+                grouped_file_entries
+                    .into_values()
+                    .flatten()
+                    .map(|fe| DuplicateEntry {
+                        path: fe.path,
+                        modified_date: fe.modified_date,
+                        size: fe.size,
+                        hash: String::new(),
+                    })
+                    .collect()
+            }
+            DirTraversalResult::Stopped => return WorkContinueStatus::Stop,
+        };
+
+        let folders = build_folder_tree(&files, &self.common_data.directories.included_directories);
+        match self.params.minimal_overlap {
+            Some(minimal_overlap) => self.find_similar_folders(stop_flag, progress_sender, &files, &folders, minimal_overlap),
+            None => self.find_identical_folders(stop_flag, progress_sender, &files, &folders),
+        }
+    }
+
+    fn find_identical_folders(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        files: &[DuplicateEntry],
+        folders: &HashMap<PathBuf, FolderNode>,
+    ) -> WorkContinueStatus {
+        let mut summaries = summarize_folders(folders, files, &HashMap::new());
+
+        // Only files inside folders which may have duplicates are hashed
+        let mut same_structure: HashMap<blake3::Hash, Vec<&Path>> = HashMap::new();
+        for (path, summary) in &summaries {
+            same_structure.entry(summary.structure_hash).or_default().push(path);
+        }
+        let candidates: HashSet<&Path> = same_structure.into_values().filter(|paths| paths.len() > 1).flatten().collect();
+        let mut files_to_hash: BTreeMap<u64, Vec<DuplicateEntry>> = BTreeMap::new();
+        for file in files {
+            if file.path.ancestors().skip(1).any(|folder| candidates.contains(folder)) {
+                files_to_hash.entry(file.size).or_default().push(file.clone());
+            }
+        }
+        debug!(
+            "find_identical_folders - {} folders, {} may be duplicated, {} files to hash",
+            folders.len(),
+            candidates.len(),
+            files_to_hash.values().map(Vec::len).sum::<usize>()
+        );
+        let candidates: HashSet<PathBuf> = candidates.into_iter().map(Path::to_path_buf).collect();
+
+        let Some(file_hashes) = self.calculate_file_hashes(stop_flag, progress_sender, files_to_hash) else {
+            return WorkContinueStatus::Stop;
+        };
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+        summaries = summarize_folders(folders, files, &file_hashes);
+
+        let mut groups: HashMap<blake3::Hash, Vec<&Path>> = HashMap::new();
+        for path in &candidates {
+            if let Some(content_hash) = summaries.get(path).and_then(|summary| summary.content_hash) {
+                groups.entry(content_hash).or_default().push(path);
+            }
+        }
+        groups.retain(|_hash, group| group.len() > 1);
+
+        let duplicated: HashMap<&Path, blake3::Hash> = groups.iter().flat_map(|(hash, group)| group.iter().map(|path| (*path, *hash))).collect();
+        let mut duplicated_folders: Vec<Vec<DuplicateFolderEntry>> = groups
+            .into_values()
+            .filter(|group| !is_implied_by_parents(group, &duplicated))
+            .map(|group| {
+                let mut group: Vec<DuplicateFolderEntry> = group
+                    .into_iter()
+                    .filter_map(|path| {
+                        let summary = summaries.get(path)?;
+                        Some(DuplicateFolderEntry {
+                            path: path.to_path_buf(),
+                            size: summary.size,
+                            number_of_files: summary.number_of_files,
+                            modified_date: summary.modified_date,
+                            hash: summary.content_hash.map(|hash| hash.to_hex().to_string()).unwrap_or_default(),
+                        })
+                    })
+                    .collect();
+                group.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+                group
+            })
+            .collect();
+        duplicated_folders.sort_by_cached_key(|group| group.first().map(|folder| (Reverse(folder.size), folder.path.clone())));
+
+        for folder in duplicated_folders.iter().flatten() {
+            let mut folder_files = Vec::with_capacity(folder.number_of_files);
+            collect_files_in_folder(folders, files, &file_hashes, &folder.path, &mut folder_files);
+            self.files_in_folders.insert(folder.path.clone(), folder_files);
+        }
+        for group in &duplicated_folders {
+            self.information.number_of_groups += 1;
+            self.information.number_of_duplicated_folders += group.len() - 1;
+            self.information.lost_space += (group.len() as u64 - 1) * group.first().map_or(0, |folder| folder.size);
+        }
+        self.duplicated_folders = duplicated_folders;
+
+        WorkContinueStatus::Continue
+    }
+
+    // Files are hashed in the same way as in duplicate finder, so both tools share hash cache
+    pub(crate) fn calculate_file_hashes(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        files_to_hash: BTreeMap<u64, Vec<DuplicateEntry>>,
+    ) -> Option<HashMap<PathBuf, String>> {
+        let params = DuplicateFinderParameters::new(CheckingMethod::Hash, self.params.hash_type, false, self.params.minimal_cache_file_size, 0, true);
+        let mut duplicate_finder = DuplicateFinder::new(params);
+        *duplicate_finder.get_cd_mut() = mem::take(&mut self.common_data);
+        let full_hash_results = duplicate_finder.calculate_full_hashes(stop_flag, progress_sender, files_to_hash, false);
+        self.common_data = mem::take(duplicate_finder.get_cd_mut());
+
+        Some(
+            full_hash_results?
+                .into_iter()
+                .flat_map(|(_size, hash_map)| hash_map.into_values().flatten())
+                .map(|entry| (entry.path, entry.hash))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn get_files_in_folder(&self, folder: &Path) -> &[DuplicateEntry] {
+        self.files_in_folders.get(folder).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn build_folder_tree(files: &[DuplicateEntry], included_directories: &[PathBuf]) -> HashMap<PathBuf, FolderNode> {
+    let mut folders: HashMap<PathBuf, FolderNode> = HashMap::new();
+    for (idx, file) in files.iter().enumerate() {
+        // Files added directly to search, outside included folders, are not part of any tree
+        let Some(root) = included_directories
+            .iter()
+            .filter(|root| file.path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+        else {
+            continue;
+        };
+        let Some(parent) = file.path.parent() else {
+            continue;
+        };
+
+        folders.entry(parent.to_path_buf()).or_default().files.push(idx);
+        let mut child = parent.to_path_buf();
+        while child != *root
+            && let Some(parent) = child.parent()
+        {
+            let already_added = folders.contains_key(parent);
+            let node = folders.entry(parent.to_path_buf()).or_default();
+            if !node.subfolders.contains(&child) {
+                node.subfolders.push(child.clone());
+            }
+            if already_added {
+                break;
+            }
+            child = parent.to_path_buf();
+        }
+    }
+    folders
+}
+
+// Calculates summaries from the deepest folders, so summaries of subfolders are always known earlier
+// Content hash is known only when all files inside folder are hashed
+fn summarize_folders(folders: &HashMap<PathBuf, FolderNode>, files: &[DuplicateEntry], file_hashes: &HashMap<PathBuf, String>) -> HashMap<PathBuf, FolderSummary> {
+    let mut sorted_folders: Vec<(&PathBuf, &FolderNode)> = folders.iter().collect();
+    sorted_folders.sort_unstable_by_key(|(path, _node)| Reverse(path.components().count()));
+
+    let mut summaries: HashMap<PathBuf, FolderSummary> = HashMap::with_capacity(folders.len());
+    for (path, node) in sorted_folders {
+        let mut size = 0;
+        let mut number_of_files = 0;
+        let mut modified_date = 0;
+        let mut structure_entries = Vec::with_capacity(node.files.len() + node.subfolders.len());
+        let mut content_entries = Some(Vec::with_capacity(node.files.len() + node.subfolders.len()));
+
+        for file in node.files.iter().filter_map(|idx| files.get(*idx)) {
+            let name = file.path.file_name().unwrap_or_default().as_encoded_bytes();
+            size += file.size;
+            number_of_files += 1;
+            modified_date = modified_date.max(file.modified_date);
+            structure_entries.push((b'f', name, file.size.to_le_bytes().to_vec()));
+            content_entries = content_entries.and_then(|mut entries| {
+                let hash = file_hashes.get(&file.path)?;
+                entries.push((b'f', name, hash.as_bytes().to_vec()));
+                Some(entries)
+            });
+        }
+        for subfolder in &node.subfolders {
+            let name = subfolder.file_name().unwrap_or_default().as_encoded_bytes();
+            let Some(summary) = summaries.get(subfolder) else {
+                continue;
+            };
+            size += summary.size;
+            number_of_files += summary.number_of_files;
+            modified_date = modified_date.max(summary.modified_date);
+            structure_entries.push((b'd', name, summary.structure_hash.as_bytes().to_vec()));
+            content_entries = content_entries.and_then(|mut entries| {
+                entries.push((b'd', name, summary.content_hash?.as_bytes().to_vec()));
+                Some(entries)
+            });
+        }
+
+        summaries.insert(
+            path.clone(),
+            FolderSummary {
+                size,
+                number_of_files,
+                modified_date,
+                structure_hash: hash_folder_entries(structure_entries),
+                content_hash: content_entries.map(hash_folder_entries),
+            },
+        );
+    }
+    summaries
+}
+
+// Entries are sorted by name, so order of reading folder does not change hash
+fn hash_folder_entries(mut entries: Vec<(u8, &[u8], Vec<u8>)>) -> blake3::Hash {
+    entries.sort_unstable_by(|a, b| a.1.cmp(b.1));
+    let mut hasher = blake3::Hasher::new();
+    for (kind, name, value) in entries {
+        hasher.update(&[kind]);
+        hasher.update(&(name.len() as u64).to_le_bytes());
+        hasher.update(name);
+        hasher.update(&(value.len() as u64).to_le_bytes());
+        hasher.update(&value);
+    }
+    hasher.finalize()
+}
+
+// Group of subfolders is not reported, when each of them is inside different folder from one group of duplicated folders,
+// because this is already visible from the group of parent folders
+fn is_implied_by_parents(group: &[&Path], duplicated: &HashMap<&Path, blake3::Hash>) -> bool {
+    let parent_hashes: Option<Vec<(&Path, blake3::Hash)>> = group
+        .iter()
+        .map(|path| {
+            let parent = path.parent()?;
+            Some((parent, *duplicated.get(parent)?))
+        })
+        .collect();
+    let Some(parent_hashes) = parent_hashes else {
+        return false;
+    };
+    let unique_parents: HashSet<&Path> = parent_hashes.iter().map(|(parent, _hash)| *parent).collect();
+    unique_parents.len() == group.len() && parent_hashes.iter().all(|(_parent, hash)| Some(hash) == parent_hashes.first().map(|(_parent, hash)| hash))
+}
+
+pub(crate) fn collect_files_in_folder(
+    folders: &HashMap<PathBuf, FolderNode>,
+    files: &[DuplicateEntry],
+    file_hashes: &HashMap<PathBuf, String>,
+    folder: &Path,
+    result: &mut Vec<DuplicateEntry>,
+) {
+    let Some(node) = folders.get(folder) else {
+        return;
+    };
+    for file in node.files.iter().filter_map(|idx| files.get(*idx)) {
+        let mut file = file.clone();
+        file.hash = file_hashes.get(&file.path).cloned().unwrap_or_default();
+        result.push(file);
+    }
+    for subfolder in &node.subfolders {
+        collect_files_in_folder(folders, files, file_hashes, subfolder, result);
+    }
+}
@@ -0,0 +1,98 @@
+pub mod core;
+pub mod similar;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::common::model::HashType;
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+use crate::tools::duplicate::DuplicateEntry;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DuplicateFolderEntry {
+    pub path: PathBuf,
+    // Sum of sizes of all files inside folder and its subfolders
+    pub size: u64,
+    pub number_of_files: usize,
+    // Modification date of the newest file inside folder
+    pub modified_date: u64,
+    pub hash: String,
+}
+
+impl ResultEntry for DuplicateFolderEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+    fn get_hash(&self) -> Option<&str> {
+        if self.hash.is_empty() { None } else { Some(&self.hash) }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SimilarFolderPair {
+    pub first: DuplicateFolderEntry,
+    pub second: DuplicateFolderEntry,
+    // Percent of files with the same content in both folders, compared to number of all files in both folders
+    pub overlap: f64,
+    pub common_files: usize,
+    pub common_size: u64,
+    pub only_in_first: Vec<DuplicateEntry>,
+    pub only_in_second: Vec<DuplicateEntry>,
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Info {
+    pub number_of_groups: usize,
+    pub number_of_duplicated_folders: usize,
+    pub lost_space: u64,
+    pub number_of_similar_folder_pairs: usize,
+    pub scanning_time: Duration,
+}
+
+#[derive(Clone)]
+pub struct DuplicateFoldersParameters {
+    pub hash_type: HashType,
+    pub minimal_cache_file_size: u64,
+    // When set, pairs of folders sharing at least given percent of files are reported instead of identical folders
+    pub minimal_overlap: Option<u8>,
+}
+
+impl DuplicateFoldersParameters {
+    pub fn new(hash_type: HashType, minimal_cache_file_size: u64) -> Self {
+        Self {
+            hash_type,
+            minimal_cache_file_size,
+            minimal_overlap: None,
+        }
+    }
+}
+
+/// Finds folders with identical content.
+/// Content hash of folder is calculated from names and hashes of files and subfolders inside it, so two folders are equal only when
+/// they contain the same tree of files. When whole folders are duplicated, only the topmost ones are reported, not every subfolder.
+/// Folders are identical when files matching search filters(e.g. size limits or extensions) are identical, other files are not compared at all.
+/// By default minimal file size is 1 byte, so folders may differ by empty files. Such files are not removed with duplicated folder,
+/// so folder which still contains them is not removed and error is reported. Empty folders are ignored.
+/// Optionally may report pairs of folders, which only partially share content, e.g. diverged copies of backup.
+pub struct DuplicateFolders {
+    common_data: CommonToolData,
+    information: Info,
+    duplicated_folders: Vec<Vec<DuplicateFolderEntry>>,
+    similar_folders: Vec<SimilarFolderPair>,
+    // All files inside reported folders, used to delete or link folders file by file
+    files_in_folders: HashMap<PathBuf, Vec<DuplicateEntry>>,
+    params: DuplicateFoldersParameters,
+}
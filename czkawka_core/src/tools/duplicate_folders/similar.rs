@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::model::WorkContinueStatus;
+use crate::common::progress_data::ProgressData;
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::tools::duplicate::DuplicateEntry;
+use crate::tools::duplicate_folders::core::{FolderNode, collect_files_in_folder};
+use crate::tools::duplicate_folders::{DuplicateFolderEntry, DuplicateFolders, SimilarFolderPair};
+
+// Files copied to many places(e.g. licenses or icons) say nothing about similarity of folders,
+// and pairing all of them would be too slow
+const MAX_FILES_WITH_SAME_HASH_TO_PAIR: usize = 100;
+
+impl DuplicateFolders {
+    #[fun_time(message = "find_similar_folders", level = "debug")]
+    pub(crate) fn find_similar_folders(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        files: &[DuplicateEntry],
+        folders: &HashMap<PathBuf, FolderNode>,
+        minimal_overlap: u8,
+    ) -> WorkContinueStatus {
+        // Files with unique size cannot be found anywhere else, so hashing them is not needed
+        let mut files_to_hash: BTreeMap<u64, Vec<DuplicateEntry>> = BTreeMap::new();
+        for file in files {
+            files_to_hash.entry(file.size).or_default().push(file.clone());
+        }
+        files_to_hash.retain(|_size, files| files.len() > 1);
+
+        let Some(file_hashes) = self.calculate_file_hashes(stop_flag, progress_sender, files_to_hash) else {
+            return WorkContinueStatus::Stop;
+        };
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        let candidates = find_candidate_pairs(files, folders, &file_hashes);
+        debug!("find_similar_folders - {} folders, {} candidate pairs", folders.len(), candidates.len());
+
+        let mut folder_files: HashMap<&Path, Vec<DuplicateEntry>> = HashMap::new();
+        for (first, second) in &candidates {
+            for folder in [first, second] {
+                folder_files.entry(folder).or_insert_with(|| {
+                    let mut result = Vec::new();
+                    collect_files_in_folder(folders, files, &file_hashes, folder, &mut result);
+                    result
+                });
+            }
+        }
+
+        let pairs: Vec<SimilarFolderPair> = candidates
+            .par_iter()
+            .filter_map(|(first, second)| compare_folders(first, folder_files.get(first.as_path())?, second, folder_files.get(second.as_path())?))
+            .filter(|pair| pair.overlap >= f64::from(minimal_overlap))
+            .collect();
+
+        // Subfolders of similar folders are usually similar too, so only the topmost pairs are reported
+        let reported: HashSet<(&Path, &Path)> = pairs.iter().map(|pair| (pair.first.path.as_path(), pair.second.path.as_path())).collect();
+        let mut pairs: Vec<SimilarFolderPair> = pairs
+            .iter()
+            .filter(|pair| match (pair.first.path.parent(), pair.second.path.parent()) {
+                (Some(first_parent), Some(second_parent)) => !reported.contains(&(first_parent, second_parent)) && !reported.contains(&(second_parent, first_parent)),
+                _ => true,
+            })
+            .cloned()
+            .collect();
+        pairs.sort_by(|a, b| {
+            b.overlap
+                .total_cmp(&a.overlap)
+                .then_with(|| b.common_size.cmp(&a.common_size))
+                .then_with(|| a.first.path.cmp(&b.first.path))
+        });
+
+        self.information.number_of_similar_folder_pairs = pairs.len();
+        self.similar_folders = pairs;
+
+        WorkContinueStatus::Continue
+    }
+}
+
+// Folders are paired when they contain files with the same content at the same depth, e.g. `/photos/2019/a.jpg` and `/backup/2019/a.jpg`
+// pair `/photos/2019` with `/backup/2019` and `/photos` with `/backup`
+fn find_candidate_pairs(files: &[DuplicateEntry], folders: &HashMap<PathBuf, FolderNode>, file_hashes: &HashMap<PathBuf, String>) -> HashSet<(PathBuf, PathBuf)> {
+    let mut same_hash: HashMap<&str, Vec<&Path>> = HashMap::new();
+    for file in files {
+        if let Some(hash) = file_hashes.get(&file.path) {
+            same_hash.entry(hash).or_default().push(&file.path);
+        }
+    }
+
+    let mut candidates = HashSet::new();
+    for paths in same_hash.values().filter(|paths| paths.len() > 1 && paths.len() <= MAX_FILES_WITH_SAME_HASH_TO_PAIR) {
+        for (idx, first_file) in paths.iter().enumerate() {
+            for second_file in paths.iter().skip(idx + 1) {
+                let mut first = first_file.parent();
+                let mut second = second_file.parent();
+                while let (Some(first_folder), Some(second_folder)) = (first, second) {
+                    if first_folder == second_folder || !folders.contains_key(first_folder) || !folders.contains_key(second_folder) {
+                        break;
+                    }
+                    // Folder is always similar to its own content
+                    if !first_folder.starts_with(second_folder) && !second_folder.starts_with(first_folder) {
+                        let pair = if first_folder < second_folder {
+                            (first_folder, second_folder)
+                        } else {
+                            (second_folder, first_folder)
+                        };
+                        candidates.insert((pair.0.to_path_buf(), pair.1.to_path_buf()));
+                    }
+                    first = first_folder.parent();
+                    second = second_folder.parent();
+                }
+            }
+        }
+    }
+    candidates
+}
+
+// Files are matched only by content, every file may be matched only once
+fn compare_folders(first_path: &Path, first_files: &[DuplicateEntry], second_path: &Path, second_files: &[DuplicateEntry]) -> Option<SimilarFolderPair> {
+    let mut second_by_hash: HashMap<&str, Vec<&DuplicateEntry>> = HashMap::new();
+    let mut only_in_second = Vec::new();
+    for file in second_files {
+        if file.hash.is_empty() {
+            only_in_second.push(file.clone());
+        } else {
+            second_by_hash.entry(&file.hash).or_default().push(file);
+        }
+    }
+
+    let mut only_in_first = Vec::new();
+    let mut common_files = 0;
+    let mut common_size = 0;
+    for file in first_files {
+        match second_by_hash.get_mut(file.hash.as_str()).and_then(Vec::pop) {
+            Some(_matched) => {
+                common_files += 1;
+                common_size += file.size;
+            }
+            None => only_in_first.push(file.clone()),
+        }
+    }
+    if common_files == 0 {
+        return None;
+    }
+    only_in_second.extend(second_by_hash.into_values().flatten().cloned());
+    only_in_first.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    only_in_second.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+    let all_files = common_files + only_in_first.len() + only_in_second.len();
+    Some(SimilarFolderPair {
+        first: folder_entry(first_path, first_files),
+        second: folder_entry(second_path, second_files),
+        overlap: common_files as f64 * 100.0 / all_files as f64,
+        common_files,
+        common_size,
+        only_in_first,
+        only_in_second,
+    })
+}
+
+fn folder_entry(path: &Path, files: &[DuplicateEntry]) -> DuplicateFolderEntry {
+    DuplicateFolderEntry {
+        path: path.to_path_buf(),
+        size: files.iter().map(|file| file.size).sum(),
+        number_of_files: files.len(),
+        modified_date: files.iter().map(|file| file.modified_date).max().unwrap_or_default(),
+        hash: String::new(),
+    }
+}
@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use tempfile::TempDir;
+
+use crate::common::model::HashType;
+use crate::common::tool_data::{CommonData, DeleteMethod};
+use crate::common::traits::Search;
+use crate::tools::duplicate_folders::{DuplicateFolders, DuplicateFoldersParameters};
+
+fn create_photo_tree(path: &Path) {
+    fs::create_dir_all(path.join("2019/summer")).unwrap();
+    fs::write(path.join("2019/a.jpg"), b"photo a").unwrap();
+    fs::write(path.join("2019/summer/b.jpg"), b"photo b").unwrap();
+    fs::write(path.join("2019/summer/c.jpg"), b"photo c, bigger").unwrap();
+}
+
+fn create_finder(path: &Path) -> DuplicateFolders {
+    let mut finder = DuplicateFolders::new(DuplicateFoldersParameters::new(HashType::Blake3, 0));
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder
+}
+
+#[test]
+fn test_find_only_topmost_duplicated_folders() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_photo_tree(&path.join("photos"));
+    create_photo_tree(&path.join("backup"));
+    // Same structure, but different content
+    create_photo_tree(&path.join("other"));
+    fs::write(path.join("other/2019/summer/c.jpg"), b"photo x, bigger").unwrap();
+
+    let mut finder = create_finder(path);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    let groups = finder.get_duplicated_folders();
+    assert_eq!(groups.len(), 1, "{groups:?}");
+    let paths: Vec<_> = groups[0].iter().map(|e| e.path.clone()).collect();
+    assert_eq!(paths, vec![path.join("backup"), path.join("photos")]);
+    assert_eq!(groups[0][0].number_of_files, 3);
+    assert_eq!(groups[0][0].size, 29);
+    assert_eq!(finder.get_information().number_of_duplicated_folders, 1);
+}
+
+#[test]
+fn test_different_file_names_are_not_duplicates() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::create_dir_all(path.join("first")).unwrap();
+    fs::create_dir_all(path.join("second")).unwrap();
+    fs::write(path.join("first/a.txt"), b"content").unwrap();
+    fs::write(path.join("second/b.txt"), b"content").unwrap();
+
+    let mut finder = create_finder(path);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert!(finder.get_duplicated_folders().is_empty());
+}
+
+#[test]
+fn test_delete_duplicated_folder() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_photo_tree(&path.join("photos"));
+    create_photo_tree(&path.join("backup"));
+
+    let mut finder = create_finder(path);
+    finder.set_delete_method(DeleteMethod::AllExceptOldest);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert!(finder.get_text_messages().errors.is_empty(), "{:?}", finder.get_text_messages().errors);
+    let remaining = [path.join("photos"), path.join("backup")].into_iter().filter(|p| p.exists()).count();
+    assert_eq!(remaining, 1);
+}
+
+#[test]
+fn test_delete_skips_folders_inside_other_group() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_photo_tree(&path.join("photos"));
+    create_photo_tree(&path.join("backup"));
+    // Copy of subfolder of duplicated folders, forms separate group with subfolders of both of them
+    fs::create_dir_all(path.join("other/summer")).unwrap();
+    fs::write(path.join("other/summer/b.jpg"), b"photo b").unwrap();
+    fs::write(path.join("other/summer/c.jpg"), b"photo c, bigger").unwrap();
+    fs::write(path.join("other/d.jpg"), b"photo d").unwrap();
+
+    let mut finder = create_finder(path);
+    finder.set_delete_method(DeleteMethod::AllExceptNewest);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert_eq!(finder.get_duplicated_folders().len(), 2);
+    assert!(finder.get_text_messages().errors.is_empty(), "{:?}", finder.get_text_messages().errors);
+    assert!(path.join("other/summer/b.jpg").exists());
+    let remaining: Vec<_> = [path.join("photos"), path.join("backup")].into_iter().filter(|p| p.exists()).collect();
+    assert_eq!(remaining.len(), 1);
+    assert!(remaining[0].join("2019/summer/c.jpg").exists());
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn test_hardlink_duplicated_folder() {
+    use std::os::unix::fs::MetadataExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_photo_tree(&path.join("photos"));
+    create_photo_tree(&path.join("backup"));
+
+    let mut finder = create_finder(path);
+    finder.set_delete_method(DeleteMethod::HardLink);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert!(finder.get_text_messages().errors.is_empty(), "{:?}", finder.get_text_messages().errors);
+    for relative_path in ["2019/a.jpg", "2019/summer/b.jpg", "2019/summer/c.jpg"] {
+        let first = fs::metadata(path.join("photos").join(relative_path)).unwrap();
+        let second = fs::metadata(path.join("backup").join(relative_path)).unwrap();
+        assert_eq!(first.ino(), second.ino());
+    }
+}
+
+#[test]
+fn test_find_similar_folders() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_photo_tree(&path.join("photos"));
+    create_photo_tree(&path.join("backup"));
+    // Backup is outdated and contains file, which was later removed from photos
+    fs::write(path.join("photos/2019/summer/d.jpg"), b"new photo").unwrap();
+    fs::write(path.join("backup/2019/removed.jpg"), b"removed photo").unwrap();
+    // Unrelated folder
+    fs::create_dir_all(path.join("documents")).unwrap();
+    fs::write(path.join("documents/a.txt"), b"document").unwrap();
+
+    let mut finder = create_finder(path);
+    finder.set_minimal_overlap(Some(50));
+    finder.set_delete_method(DeleteMethod::AllExceptOldest);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    let pairs = finder.get_similar_folders();
+    assert_eq!(pairs.len(), 1, "{pairs:?}");
+    let pair = &pairs[0];
+    assert_eq!(pair.first.path, path.join("backup"));
+    assert_eq!(pair.second.path, path.join("photos"));
+    assert_eq!(pair.common_files, 3);
+    assert!((pair.overlap - 60.0).abs() < 0.01);
+    assert_eq!(
+        pair.only_in_first.iter().map(|e| e.path.clone()).collect::<Vec<_>>(),
+        vec![path.join("backup/2019/removed.jpg")]
+    );
+    assert_eq!(
+        pair.only_in_second.iter().map(|e| e.path.clone()).collect::<Vec<_>>(),
+        vec![path.join("photos/2019/summer/d.jpg")]
+    );
+
+    // Similar folders are never deleted
+    assert!(path.join("backup/2019/a.jpg").exists());
+    assert_eq!(finder.get_text_messages().errors.len(), 1);
+
+    finder.set_minimal_overlap(Some(70));
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    assert!(finder.get_similar_folders().is_empty());
+}
@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+
+use crate::common::deletion::{DeleteItemType, SelectedElements, select_advanced_elements};
+use crate::common::model::WorkContinueStatus;
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData, DeleteMethod};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::tools::duplicate::DuplicateEntry;
+use crate::tools::duplicate_folders::{DuplicateFolderEntry, DuplicateFolders, DuplicateFoldersParameters, Info};
+
+impl AllTraits for DuplicateFolders {}
+
+impl Search for DuplicateFolders {
+    #[fun_time(message = "find_duplicated_folders", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            if self.prepare_items(None).is_err() {
+                return;
+            }
+            if self.find_duplicated_folders(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+            if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl DebugPrint for DuplicateFolders {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+
+        println!("---------------DEBUG PRINT---------------");
+        println!("Number of groups - {}", self.information.number_of_groups);
+        println!("Number of duplicated folders - {}", self.information.number_of_duplicated_folders);
+        println!("Lost space - {}", format_size(self.information.lost_space, BINARY));
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+impl PrintResults for DuplicateFolders {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        self.write_base_search_paths(writer)?;
+
+        if self.params.minimal_overlap.is_some() {
+            return self.write_similar_folders(writer);
+        }
+
+        if !self.duplicated_folders.is_empty() {
+            writeln!(
+                writer,
+                "-------------------------------------------------Folders with same content-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} duplicated folders in {} groups which takes {}.",
+                self.information.number_of_duplicated_folders,
+                self.information.number_of_groups,
+                format_size(self.information.lost_space, BINARY)
+            )?;
+            for group in &self.duplicated_folders {
+                if let Some(first) = group.first() {
+                    write!(
+                        writer,
+                        "\n---- Size {} ({}), {} files - {} folders \n",
+                        format_size(first.size, BINARY),
+                        first.size,
+                        first.number_of_files,
+                        group.len()
+                    )?;
+                }
+                for folder_entry in group {
+                    writeln!(writer, "\"{}\"", folder_entry.path.to_string_lossy())?;
+                }
+            }
+        } else {
+            write!(writer, "Not found any duplicated folders.")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> std::io::Result<()> {
+        if self.params.minimal_overlap.is_some() {
+            return self.write_results_as_json_internal(writer, &self.similar_folders, pretty_print);
+        }
+        self.write_results_as_json_internal(writer, &self.duplicated_folders, pretty_print)
+    }
+}
+
+impl CommonData for DuplicateFolders {
+    type Info = Info;
+    type Parameters = DuplicateFoldersParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_duplicated_folders > 0 || self.information.number_of_similar_folder_pairs > 0
+    }
+}
+
+impl DeletingItems for DuplicateFolders {
+    // Folders are chosen in the same way as files in duplicate finder, but deleted or linked file by file,
+    // so every change is visible in journal and may be undone
+    #[fun_time(message = "delete_files", level = "debug")]
+    fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        if self.common_data.delete_method == DeleteMethod::None {
+            return WorkContinueStatus::Continue;
+        }
+        // Similar folders differ, so removing any of them would lose files
+        if self.params.minimal_overlap.is_some() {
+            self.common_data
+                .text_messages
+                .errors
+                .push("Similar folders are only reported, because they contain different files, use identical folders mode to delete or link them".to_string());
+            return WorkContinueStatus::Continue;
+        }
+
+        let selected = match select_advanced_elements(&self.common_data, groups_without_nested_folders(&self.duplicated_folders)) {
+            Ok(selected) => selected,
+            Err(e) => {
+                self.common_data.text_messages.errors.push(e);
+                return WorkContinueStatus::Continue;
+            }
+        };
+
+        match selected {
+            SelectedElements::Deleting(folders) => {
+                let files: Vec<DuplicateEntry> = folders.iter().flat_map(|folder| self.get_files_in_folder(&folder.path)).cloned().collect();
+                if self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files)) == WorkContinueStatus::Stop {
+                    return WorkContinueStatus::Stop;
+                }
+                // After removing files, folders contain at most empty subfolders
+                self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFolders(folders))
+            }
+            SelectedElements::Linking(folders) => {
+                let files: Vec<(DuplicateEntry, Vec<DuplicateEntry>)> = folders
+                    .iter()
+                    .flat_map(|(original, folders)| folders.iter().flat_map(|folder| self.pair_files_with_original(original, folder)))
+                    .collect();
+                let delete_item_type = match self.common_data.delete_method {
+                    DeleteMethod::Reflink => DeleteItemType::ReflinkingFiles(files),
+                    DeleteMethod::Symlink => DeleteItemType::SymlinkingFiles(files),
+                    _ => DeleteItemType::HardlinkingFiles(files),
+                };
+                self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, delete_item_type)
+            }
+        }
+    }
+}
+
+// Group may contain folders which are inside folders from other group, e.g. when subfolder of duplicated folder is also copied elsewhere.
+// Such folders are already deleted or linked with their parent, and choosing them again could remove every copy of their files,
+// so they are skipped and groups with less than two remaining folders are not changed.
+fn groups_without_nested_folders(groups: &[Vec<DuplicateFolderEntry>]) -> Vec<Vec<DuplicateFolderEntry>> {
+    let all_folders: HashSet<&Path> = groups.iter().flatten().map(|folder| folder.path.as_path()).collect();
+    groups
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .filter(|folder| !folder.path.ancestors().skip(1).any(|ancestor| all_folders.contains(ancestor)))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+impl DuplicateFolders {
+    fn write_similar_folders<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        if self.similar_folders.is_empty() {
+            write!(writer, "Not found any similar folders.")?;
+            return Ok(());
+        }
+
+        writeln!(
+            writer,
+            "-------------------------------------------------Similar folders-------------------------------------------------"
+        )?;
+        writeln!(writer, "Found {} pairs of similar folders.", self.information.number_of_similar_folder_pairs)?;
+        for pair in &self.similar_folders {
+            write!(
+                writer,
+                "\n---- {:.1}% common - {} files ({})\n",
+                pair.overlap,
+                pair.common_files,
+                format_size(pair.common_size, BINARY)
+            )?;
+            for (folder, unique_files) in [(&pair.first, &pair.only_in_first), (&pair.second, &pair.only_in_second)] {
+                writeln!(
+                    writer,
+                    "\"{}\" - {} files, {} only in this folder",
+                    folder.path.to_string_lossy(),
+                    folder.number_of_files,
+                    unique_files.len()
+                )?;
+                for file in unique_files {
+                    writeln!(writer, "    \"{}\"", file.path.to_string_lossy())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Identical folders contain files with the same relative paths, so each file is linked to its counterpart in original folder
+    fn pair_files_with_original(&self, original: &DuplicateFolderEntry, folder: &DuplicateFolderEntry) -> Vec<(DuplicateEntry, Vec<DuplicateEntry>)> {
+        let original_files: HashMap<&Path, &DuplicateEntry> = self
+            .get_files_in_folder(&original.path)
+            .iter()
+            .filter_map(|file| Some((file.path.strip_prefix(&original.path).ok()?, file)))
+            .collect();
+        self.get_files_in_folder(&folder.path)
+            .iter()
+            .filter_map(|file| {
+                let relative_path = file.path.strip_prefix(&folder.path).ok()?;
+                let original_file = original_files.get(relative_path)?;
+                Some(((*original_file).clone(), vec![file.clone()]))
+            })
+            .collect()
+    }
+}
@@ -10,7 +10,7 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 
 use crate::common::tool_data::CommonToolData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::{CacheEntry, ResultEntry};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Info {
@@ -62,6 +62,12 @@ impl ResultEntry for ExifEntry {
     }
 }
 
+impl CacheEntry for ExifEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
 pub struct ExifRemover {
     common_data: CommonToolData,
     information: Info,
@@ -13,8 +13,9 @@ use rusty_chromaprint::Configuration;
 use serde::{Deserialize, Serialize};
 
 use crate::common::model::{CheckingMethod, FileEntry};
+use crate::common::select_rules::RuleValue;
 use crate::common::tool_data::CommonToolData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::{CacheEntry, ResultEntry};
 
 bitflags! {
     #[derive(PartialEq, Copy, Clone, Debug)]
@@ -56,6 +57,23 @@ impl ResultEntry for MusicEntry {
     fn get_size(&self) -> u64 {
         self.size
     }
+    fn get_rule_field(&self, name: &str) -> Option<RuleValue> {
+        match name {
+            "title" => Some(RuleValue::Text(self.track_title.clone())),
+            "artist" => Some(RuleValue::Text(self.track_artist.clone())),
+            "year" => Some(RuleValue::Text(self.year.clone())),
+            "genre" => Some(RuleValue::Text(self.genre.clone())),
+            "bitrate" => Some(RuleValue::Number(f64::from(self.bitrate))),
+            "length" => Some(RuleValue::Number(f64::from(self.length))),
+            _ => None,
+        }
+    }
+}
+
+impl CacheEntry for MusicEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
 }
 
 impl FileEntry {
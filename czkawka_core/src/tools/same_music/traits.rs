@@ -7,6 +7,7 @@ use crossbeam_channel::Sender;
 use fun_time::fun_time;
 
 use crate::common::consts::{AUDIO_FILES_CONTENT_EXTENSIONS, AUDIO_FILES_TAGS_EXTENSIONS};
+use crate::common::deletion::groups_with_reference_first;
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
@@ -126,11 +127,11 @@ impl PrintResults for SameMusic {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> std::io::Result<()> {
         if self.get_use_reference() {
-            self.save_results_to_file_as_json_internal(file_name, &self.duplicated_music_entries_referenced, pretty_print)
+            self.write_results_as_json_internal(writer, &self.duplicated_music_entries_referenced, pretty_print)
         } else {
-            self.save_results_to_file_as_json_internal(file_name, &self.duplicated_music_entries, pretty_print)
+            self.write_results_as_json_internal(writer, &self.duplicated_music_entries, pretty_print)
         }
     }
 }
@@ -180,6 +181,10 @@ impl DeletingItems for SameMusic {
             return WorkContinueStatus::Continue;
         }
         if self.get_use_reference_folders() {
+            if self.get_cd().delete_method == DeleteMethod::Rule {
+                let groups = groups_with_reference_first(self.duplicated_music_entries_referenced.iter());
+                return self.delete_advanced_elements_and_add_to_messages(stop_flag, progress_sender, groups);
+            }
             let files_to_delete: Vec<_> = self.duplicated_music_entries_referenced.iter().flat_map(|(_, files)| files.iter().cloned()).collect();
             return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
         }
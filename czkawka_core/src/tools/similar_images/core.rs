@@ -2,7 +2,7 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
-use std::{mem, panic};
+use std::{iter, mem, panic};
 
 use bk_tree::BKTree;
 use crossbeam_channel::Sender;
@@ -34,6 +34,7 @@ impl SimilarImages {
             params,
             images_to_check: Default::default(),
             image_hashes: Default::default(),
+            results_sender: None,
         }
     }
 
@@ -528,6 +529,7 @@ impl SimilarImages {
         self.exclude_items_with_same_resolution();
 
         self.remove_multiple_records_from_reference_folders();
+        self.send_groups();
 
         if self.common_data.use_reference_folders {
             for (_fe, vector) in &self.similar_referenced_vectors {
@@ -549,6 +551,22 @@ impl SimilarImages {
         WorkContinueStatus::Continue
     }
 
+    fn send_groups(&self) {
+        let Some(results_sender) = &self.results_sender else {
+            return;
+        };
+        // Receiver may be already dropped when user is not interested in more results
+        if self.common_data.use_reference_folders {
+            for (file, similar_files) in &self.similar_referenced_vectors {
+                let _ = results_sender.send(iter::once(file.clone()).chain(similar_files.iter().cloned()).collect());
+            }
+        } else {
+            for group in &self.similar_vectors {
+                let _ = results_sender.send(group.clone());
+            }
+        }
+    }
+
     #[fun_time(message = "exclude_items_with_same_size", level = "debug")]
     fn exclude_items_with_same_size(&mut self) {
         if self.get_params().exclude_images_with_same_size {
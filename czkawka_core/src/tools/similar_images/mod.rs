@@ -11,14 +11,16 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use bk_tree::BKTree;
+use crossbeam_channel::Sender;
 use hamming_bitwise_fast::hamming_bitwise_fast;
 use image_hasher::{FilterType, HashAlg};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use crate::common::model::FileEntry;
+use crate::common::select_rules::RuleValue;
 use crate::common::tool_data::CommonToolData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::{CacheEntry, ResultEntry};
 
 type ImHash = Vec<u8>;
 
@@ -51,6 +53,19 @@ impl ResultEntry for ImagesEntry {
     fn get_size(&self) -> u64 {
         self.size
     }
+    fn get_rule_field(&self, name: &str) -> Option<RuleValue> {
+        match name {
+            "width" => Some(RuleValue::Number(f64::from(self.width))),
+            "height" => Some(RuleValue::Number(f64::from(self.height))),
+            _ => None,
+        }
+    }
+}
+
+impl CacheEntry for ImagesEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
 }
 impl FileEntry {
     fn into_images_entry(self) -> ImagesEntry {
@@ -156,6 +171,7 @@ pub struct SimilarImages {
     // Hashmap with image hashes and Vector with names of files
     image_hashes: IndexMap<ImHash, Vec<ImagesEntry>>,
     images_to_check: BTreeMap<String, ImagesEntry>,
+    results_sender: Option<Sender<Vec<ImagesEntry>>>,
     params: SimilarImagesParameters,
 }
 
@@ -187,4 +203,11 @@ impl SimilarImages {
     pub const fn get_information(&self) -> Info {
         self.information
     }
+
+    /// Every group of similar images is sent as soon as it is confirmed, before search finishes.
+    /// Image may be similar to any other image, so groups are confirmed only after comparing hashes of all images.
+    /// When reference folders are used, first entry of group is image from reference folder.
+    pub fn set_results_sender(&mut self, results_sender: Sender<Vec<ImagesEntry>>) {
+        self.results_sender = Some(results_sender);
+    }
 }
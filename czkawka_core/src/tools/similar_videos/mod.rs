@@ -14,8 +14,9 @@ use serde::{Deserialize, Serialize};
 use similario_core::VideoSignature;
 
 use crate::common::model::FileEntry;
+use crate::common::select_rules::RuleValue;
 use crate::common::tool_data::CommonToolData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::{CacheEntry, ResultEntry};
 
 pub const MAX_TOLERANCE: i32 = 20;
 
@@ -91,6 +92,12 @@ impl ResultEntry for VideoAudioEntry {
     }
 }
 
+impl CacheEntry for VideoAudioEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
 impl ResultEntry for VideosEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -101,6 +108,22 @@ impl ResultEntry for VideosEntry {
     fn get_size(&self) -> u64 {
         self.size
     }
+    // Missing properties are reported as 0 or empty text, like in GUI
+    fn get_rule_field(&self, name: &str) -> Option<RuleValue> {
+        match name {
+            "codec" => Some(RuleValue::Text(self.codec.clone().unwrap_or_default())),
+            "bitrate" => Some(RuleValue::Number(self.bitrate.unwrap_or(0) as f64)),
+            // Rounded down to hundredths of second, the same precision as GUI uses
+            "duration" => Some(RuleValue::Number(self.duration.map_or(0.0, |d| (d * 100.0).trunc() / 100.0))),
+            _ => None,
+        }
+    }
+}
+
+impl CacheEntry for VideosEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
 }
 
 impl FileEntry {
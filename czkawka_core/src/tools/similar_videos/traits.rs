@@ -8,6 +8,7 @@ use fun_time::fun_time;
 use humansize::{BINARY, format_size};
 
 use crate::common::consts::VIDEO_FILES_EXTENSIONS;
+use crate::common::deletion::groups_with_reference_first;
 use crate::common::ffmpeg_utils::check_if_ffprobe_ffmpeg_exists;
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
@@ -89,6 +90,10 @@ impl DeletingItems for SimilarVideos {
             return WorkContinueStatus::Continue;
         }
         if self.get_use_reference_folders() {
+            if self.get_cd().delete_method == DeleteMethod::Rule {
+                let groups = groups_with_reference_first(self.similar_referenced_vectors.iter());
+                return self.delete_advanced_elements_and_add_to_messages(stop_flag, progress_sender, groups);
+            }
             let files_to_delete: Vec<_> = self.similar_referenced_vectors.iter().flat_map(|(_, files)| files.iter().cloned()).collect();
             return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
         }
@@ -175,11 +180,11 @@ impl PrintResults for SimilarVideos {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> std::io::Result<()> {
         if self.get_use_reference() {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_referenced_vectors, pretty_print)
+            self.write_results_as_json_internal(writer, &self.similar_referenced_vectors, pretty_print)
         } else {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_vectors, pretty_print)
+            self.write_results_as_json_internal(writer, &self.similar_vectors, pretty_print)
         }
     }
 }
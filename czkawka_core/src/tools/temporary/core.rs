@@ -1,3 +1,4 @@
+use std::ffi::OsStr;
 use std::fs::DirEntry;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -33,6 +34,22 @@ impl Temporary {
             return WorkContinueStatus::Stop;
         }
 
+        if let Some(collected_files) = self.common_data.collected_files.clone() {
+            self.temporary_files = collected_files
+                .files
+                .iter()
+                .filter(|fe| fe.path.file_name().is_some_and(|file_name| self.is_temporary_file_name(file_name)))
+                .map(|fe| TemporaryFileEntry {
+                    path: fe.path.clone(),
+                    modified_date: fe.modified_date,
+                    size: fe.size,
+                })
+                .collect();
+            self.common_data.text_messages.warnings.extend(collected_files.warnings.iter().cloned());
+            self.information.number_of_temporary_files = self.temporary_files.len();
+            return WorkContinueStatus::Continue;
+        }
+
         let mut folders_to_check: Vec<PathBuf> = self.common_data.directories.included_directories.clone();
 
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::CollectingFiles(CheckingMethod::None), 0, 0);
@@ -109,10 +126,7 @@ impl Temporary {
             return None;
         }
 
-        let file_name = entry_data.file_name();
-        let file_name_ascii_lowercase = file_name.to_ascii_lowercase();
-        let file_name_lowercase = file_name_ascii_lowercase.to_string_lossy();
-        if !self.params.extensions.iter().any(|f| file_name_lowercase.ends_with(f.as_str())) {
+        if !self.is_temporary_file_name(&entry_data.file_name()) {
             return None;
         }
 
@@ -127,6 +141,12 @@ impl Temporary {
             path: current_file_name,
         })
     }
+
+    fn is_temporary_file_name(&self, file_name: &OsStr) -> bool {
+        let file_name_ascii_lowercase = file_name.to_ascii_lowercase();
+        let file_name_lowercase = file_name_ascii_lowercase.to_string_lossy();
+        self.params.extensions.iter().any(|f| file_name_lowercase.ends_with(f.as_str()))
+    }
 }
 
 #[cfg_attr(target_family = "windows", expect(clippy::needless_pass_by_ref_mut))]
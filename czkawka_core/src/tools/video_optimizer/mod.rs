@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 pub use self::encoding::{HardwareEncoder, NoiseReductionMethod, VideoCodec};
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
-use crate::common::traits::ResultEntry;
+use crate::common::traits::{CacheEntry, ResultEntry};
 use crate::flc;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -230,6 +230,12 @@ impl ResultEntry for VideoTranscodeEntry {
     }
 }
 
+impl CacheEntry for VideoTranscodeEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
 impl ResultEntry for VideoCropEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -242,6 +248,12 @@ impl ResultEntry for VideoCropEntry {
     }
 }
 
+impl CacheEntry for VideoCropEntry {
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
 impl FileEntry {
     fn into_video_transcode_entry(self) -> VideoTranscodeEntry {
         VideoTranscodeEntry {
@@ -114,10 +114,10 @@ impl PrintResults for VideoOptimizer {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+    fn write_results_as_json<W: Write>(&self, writer: &mut W, pretty_print: bool) -> std::io::Result<()> {
         match &self.params {
-            VideoOptimizerParameters::VideoTranscode(_) => self.save_results_to_file_as_json_internal(file_name, &self.video_transcode_result_entries, pretty_print),
-            VideoOptimizerParameters::VideoCrop(_) => self.save_results_to_file_as_json_internal(file_name, &self.video_crop_result_entries, pretty_print),
+            VideoOptimizerParameters::VideoTranscode(_) => self.write_results_as_json_internal(writer, &self.video_transcode_result_entries, pretty_print),
+            VideoOptimizerParameters::VideoCrop(_) => self.write_results_as_json_internal(writer, &self.video_crop_result_entries, pretty_print),
         }
     }
 }
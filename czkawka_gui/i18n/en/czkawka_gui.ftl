@@ -68,7 +68,7 @@ duplicate_mode_size_combo_box = Size
 duplicate_mode_hash_combo_box = Hash
 
 duplicate_hash_type_tooltip = 
-        Czkawka offers 3 types of hashes:
+        Czkawka offers 6 types of hashes:
 
         Blake3 - cryptographic hash function. This is the default because it is very fast.
 
@@ -76,6 +76,8 @@ duplicate_hash_type_tooltip =
 
         XXH3 - very similar in performance and hash quality to Blake3 (but non-cryptographic). So, such modes can be easily interchanged.
 
+        SHA-256, SHA-1, MD5 - slower cryptographic hashes, which give the same results as sha256sum, sha1sum and md5sum, so they can be compared with external checksum lists.
+
 duplicate_check_method_tooltip = 
         For now, Czkawka offers three types of method to find duplicates by:
 
@@ -20,6 +20,7 @@ rust_rename_single_same_name = New name is the same as the current one.
 rust_renamed_file = Renamed { $old_name } to { $new_name }
 rust_no_included_paths = Cannot start scan when no included paths are set.
 rust_all_paths_referenced = Cannot start scan when all included paths are set as referenced paths, you need to disable reference checkbox next to input path.
+rust_invalid_date_filter = Cannot start scan with invalid date filter - { $reason }
 rust_found_empty_folders = Found { $items_found } empty folders in { $time }
 rust_found_empty_files = Found { $items_found } empty files in { $time }
 rust_found_similar_images = Found { $items_found } similar image files in { $groups } groups in { $time }
@@ -41,6 +42,8 @@ rust_saved_preset = Saved preset { $preset_idx }
 rust_cannot_save_preset = Cannot save preset { $preset_idx } - reason { $reason }
 rust_reset_preset = Reset preset { $preset_idx }
 rust_cannot_create_output_folder = Cannot create output folder { $output_folder }, reason: { $error }
+rust_cannot_open_journal = Cannot use journal, so no file was changed, reason: { $error }
+rust_cannot_use_selection_rule = Cannot use selection rule, reason: { $error }
 
 rust_delete_summary = Deleted { $deleted } items, failed to remove { $failed } items, out of { $total } items
 rust_rename_summary = Renamed { $renamed } items, failed to rename { $failed } items, out of { $total } items
@@ -198,6 +201,7 @@ selection_all_except_oldest = Select all except oldest
 selection_all_except_longest_path = Select all except longest path
 selection_all_except_shortest_path = Select all except shortest path
 selection_custom_select_unselect = Custom Select/Unselect
+selection_by_rule = Select using rule
 stage_current = Current Stage:
 stage_all = All Stages:
 subsettings = Subsettings
@@ -298,13 +302,26 @@ settings_show_only_icons = Show only icons
 settings_excluded_items = Excluded item:
 settings_allowed_extensions = Allowed extensions:
 settings_excluded_extensions = Excluded extensions:
+settings_preferred_paths = Preferred paths:
 settings_file_size = File Size(Kilobytes)
 settings_minimum_file_size = Min:
 settings_maximum_file_size = Max:
+settings_modified_date = Modified date:
+settings_created_date = Created date:
+settings_accessed_date = Accessed date:
+settings_date_after = After:
+settings_date_before = Before:
+settings_date_hint = Dates can be absolute (UTC), e.g. 2024-05-31 or 2024-05-31 14:30, or time ago with unit h, d, w, m (30 days) or y (365 days), e.g. 30d. Empty fields are not used. Creation and access time are not available on every platform and filesystem, there files are not filtered by them
 settings_recursive_search = Recursive search
 settings_use_cache = Use cache
 settings_save_as_json = Also save cache as JSON file
 settings_ignore_other_filesystems = Ignore other filesystems (only Linux)
+settings_use_database_cache = Use database cache
+settings_use_database_cache_hint = Cache is kept in single database, where only entries of checked files are read and written, instead of loading and saving whole cache files. Other apps using the same database wait until it is free, only when it stays used for too long the scan runs without cache. Both kinds of cache are kept separately
+settings_use_dir_snapshot = Use directory snapshot
+settings_use_dir_snapshot_hint = Content of unchanged folders is taken from snapshot saved in cache instead of being listed again. Size and dates of files are still checked in every scan, so it helps mostly on slow network filesystems
+settings_respect_gitignore = Respect .gitignore files
+settings_respect_gitignore_hint = Files ignored by .gitignore files found in scanned folders are skipped. Rules from .czkawkaignore files are always used
 settings_delete_outdated_cache_entries = Delete automatically outdated cache entries
 settings_delete_outdated_cache_entries_hint = When enabled, the app will verify during cache loading (at most once per week) whether the cached records still point to existing and unmodified files/data
 settings_hide_hard_links = Hide hard links
@@ -344,6 +361,10 @@ settings_video_thumbnails_grid_tiles_per_side_hint = Number of thumbnail tiles p
 settings_similar_images_tool = Similar Images tool
 settings_general_settings = General Settings
 settings_select_header_text = Select Popup Options
+settings_selection_rule_text = Selection rule:
+settings_selection_rule_hint_text = Used by "Select using rule", e.g. keep where path ~ "/raw/" or (size max). The same rule may be passed to CLI with --select-rule
+settings_journal_path_text = Journal file:
+settings_journal_path_hint_text = When set, every delete, move, rename and link made from results is recorded in this file and can be reverted later with "czkawka_cli undo"
 settings_select_group_size_text = Size
 settings_select_group_date_text = Modification Date
 settings_select_group_resolution_text = Resolution
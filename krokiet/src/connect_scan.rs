@@ -21,6 +21,8 @@ use std::sync::{Arc, Mutex};
 
 use chrono::{Local, TimeZone, Utc};
 use crossbeam_channel::Sender;
+use czkawka_core::common::cache::CacheBackend;
+use czkawka_core::common::date_filter::{DateFilter, DateRange, parse_date_limit};
 use czkawka_core::common::progress_data::ProgressData;
 use czkawka_core::common::tool_data::CommonData;
 use czkawka_core::helpers::messages::MessageLimit;
@@ -53,6 +55,7 @@ pub struct ScanData {
     pub progress_sender: Sender<ProgressData>,
     pub stop_flag: Arc<AtomicBool>,
     pub custom_settings: SettingsCustom,
+    pub date_filter: DateFilter,
     pub basic_settings: BasicSettings,
     pub combo_box_items: ComboBoxItems,
     pub shared_models: Arc<Mutex<SharedModels>>,
@@ -96,6 +99,13 @@ pub(crate) fn connect_scan_button(
         });
 
         let custom_settings = collect_settings(&app);
+        let date_filter = match parse_date_filter(&custom_settings) {
+            Ok(date_filter) => date_filter,
+            Err(e) => {
+                app.invoke_scan_ended(flk!("rust_invalid_date_filter", reason = e).into());
+                return;
+            }
+        };
         let basic_settings = collect_base_settings(&app);
         let combo_box_items = collect_combo_box_settings(&app);
 
@@ -110,6 +120,7 @@ pub(crate) fn connect_scan_button(
             progress_sender,
             stop_flag,
             custom_settings,
+            date_filter,
             basic_settings,
             combo_box_items,
             shared_models: cloned_model,
@@ -174,7 +185,21 @@ where
     (text_messages.critical.clone(), text_messages.create_messages_text(limit))
 }
 
-fn set_common_settings<T>(component: &mut T, custom_settings: &SettingsCustom, stop_flag: &Arc<AtomicBool>)
+// Empty field means no limit
+fn parse_date_range(after: &str, before: &str) -> Result<DateRange, String> {
+    let parse = |value: &str| if value.trim().is_empty() { Ok(None) } else { parse_date_limit(value).map(Some) };
+    Ok(DateRange::new(parse(after)?, parse(before)?))
+}
+
+fn parse_date_filter(custom_settings: &SettingsCustom) -> Result<DateFilter, String> {
+    Ok(DateFilter {
+        modified: parse_date_range(&custom_settings.modified_after, &custom_settings.modified_before)?,
+        created: parse_date_range(&custom_settings.created_after, &custom_settings.created_before)?,
+        accessed: parse_date_range(&custom_settings.accessed_after, &custom_settings.accessed_before)?,
+    })
+}
+
+fn set_common_settings<T>(component: &mut T, custom_settings: &SettingsCustom, date_filter: DateFilter, stop_flag: &Arc<AtomicBool>)
 where
     T: CommonData,
 {
@@ -189,8 +214,13 @@ where
     component.set_allowed_extensions(custom_settings.allowed_extensions.split(',').map(str::to_string).collect());
     component.set_excluded_extensions(custom_settings.excluded_extensions.split(',').map(str::to_string).collect());
     component.set_excluded_items(custom_settings.excluded_items.split(',').map(str::to_string).collect());
+    component.set_date_filter(date_filter);
+    component.set_preferred_paths(custom_settings.preferred_paths.split(',').map(str::to_string).collect());
     component.set_exclude_other_filesystems(custom_settings.ignore_other_file_systems);
+    component.set_respect_gitignore(custom_settings.respect_gitignore);
     component.set_use_cache(custom_settings.use_cache);
+    component.set_use_dir_snapshot(custom_settings.use_dir_snapshot);
+    component.set_cache_backend(if custom_settings.use_database_cache { CacheBackend::Database } else { CacheBackend::Bincode });
     component.set_save_also_as_json(custom_settings.save_also_as_json);
     component.set_delete_outdated_cache(custom_settings.delete_outdated_cache_entries);
     component.set_hide_hard_links(custom_settings.hide_hard_links);
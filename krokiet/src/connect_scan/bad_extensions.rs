@@ -20,7 +20,7 @@ pub(crate) fn scan_bad_extensions(a: Weak<MainWindow>, sd: ScanData) {
         .spawn(move || {
             let params = BadExtensionsParameters::new();
             let mut tool = BadExtensions::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
             let mut vector = tool.get_bad_extensions_files().clone();
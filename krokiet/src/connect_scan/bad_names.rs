@@ -32,7 +32,7 @@ pub(crate) fn scan_bad_names(a: Weak<MainWindow>, sd: ScanData) {
             };
             let params = BadNamesParameters::new(checked_issues);
             let mut tool = BadNames::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
             let mut vector = tool.get_bad_names_files().clone();
@@ -24,7 +24,7 @@ pub(crate) fn scan_big_files(a: Weak<MainWindow>, sd: ScanData) {
             let params = BigFileParameters::new(sd.custom_settings.biggest_files_sub_number_of_files as usize, big_files_mode);
             let mut tool = BigFile::new(params);
 
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
             let mut vector = tool.get_big_files().clone();
@@ -55,7 +55,7 @@ pub(crate) fn scan_broken_files(a: Weak<MainWindow>, sd: ScanData) {
 
             let params = BrokenFilesParameters::new(checked_types);
             let mut tool = BrokenFiles::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
 
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
@@ -33,7 +33,7 @@ pub(crate) fn scan_duplicates(a: Weak<MainWindow>, sd: ScanData) {
             );
             let mut tool = DuplicateFinder::new(params);
 
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
             let (critical, messages) = get_text_messages(&tool, &sd.basic_settings);
 
@@ -25,7 +25,7 @@ pub(crate) fn scan_empty_files(a: Weak<MainWindow>, sd: ScanData) {
                 search_non_printable_content_files: sd.custom_settings.empty_files_sub_non_printable_content,
             };
             let mut tool = EmptyFiles::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
             let mut vector = tool.get_empty_files().clone();
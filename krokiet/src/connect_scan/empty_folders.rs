@@ -19,7 +19,7 @@ pub(crate) fn scan_empty_folders(a: Weak<MainWindow>, sd: ScanData) {
         .stack_size(DEFAULT_THREAD_SIZE)
         .spawn(move || {
             let mut tool = EmptyFolder::new();
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
             let mut vector = tool.get_empty_folder_list().values().cloned().collect::<Vec<_>>();
@@ -30,7 +30,7 @@ pub(crate) fn scan_exif_remover(a: Weak<MainWindow>, sd: ScanData) {
 
             let params = ExifRemoverParameters::new(ignored_tags);
             let mut tool = ExifRemover::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
             let mut vector = tool.get_exif_files().clone();
@@ -19,7 +19,7 @@ pub(crate) fn scan_invalid_symlinks(a: Weak<MainWindow>, sd: ScanData) {
         .stack_size(DEFAULT_THREAD_SIZE)
         .spawn(move || {
             let mut tool = InvalidSymlinks::new();
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
 
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
@@ -48,7 +48,7 @@ pub(crate) fn scan_similar_music(a: Weak<MainWindow>, sd: ScanData) {
                 sd.custom_settings.similar_music_compare_fingerprints_only_with_similar_titles,
             );
             let mut tool = SameMusic::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
 
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
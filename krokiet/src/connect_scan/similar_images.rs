@@ -40,7 +40,7 @@ pub(crate) fn scan_similar_images(a: Weak<MainWindow>, sd: ScanData) {
             );
             let mut tool = SimilarImages::new(params);
 
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
 
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
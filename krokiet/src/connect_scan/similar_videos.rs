@@ -42,7 +42,7 @@ pub(crate) fn scan_similar_videos(a: Weak<MainWindow>, sd: ScanData) {
                 sd.custom_settings.similar_videos_audio_min_duration_seconds,
             );
             let mut tool = SimilarVideos::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
 
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
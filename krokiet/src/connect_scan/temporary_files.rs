@@ -32,7 +32,7 @@ pub(crate) fn scan_temporary_files(a: Weak<MainWindow>, sd: ScanData) {
                 TemporaryParameters { extensions }
             };
             let mut tool = Temporary::new(params);
-            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+            set_common_settings(&mut tool, &sd.custom_settings, sd.date_filter, &sd.stop_flag);
 
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
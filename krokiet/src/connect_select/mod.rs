@@ -1,8 +1,12 @@
 pub(crate) mod custom_select;
+pub(crate) mod rule_select;
 
 use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+use czkawka_core::common::preferred_paths::PreferredPaths;
+use czkawka_core::common::select_rules::SelectionRule;
 use log::error;
 use regex::Regex;
 use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
@@ -13,7 +17,7 @@ use crate::connect_translation::translate_select_mode;
 use crate::settings::model::{SavedCustomSelectColumnState, SavedCustomSelectTabState};
 use crate::settings::{get_custom_select_state_file, load_data_from_file, save_data_to_file};
 use crate::shared_models::SharedModels;
-use crate::{ActiveTab, Callabler, CustomSelectColumnModel, GuiState, MainWindow, SelectMode, SelectModel, Settings, SingleMainListModel};
+use crate::{ActiveTab, Callabler, CustomSelectColumnModel, GuiState, MainWindow, SelectMode, SelectModel, Settings, SingleMainListModel, flk};
 
 type SelectionResult = (u64, u64, ModelRc<SingleMainListModel>);
 
@@ -34,28 +38,39 @@ pub(crate) fn connect_select(app: &MainWindow, shared_models: &Arc<Mutex<SharedM
         let app = a.upgrade().expect("Failed to upgrade app :(");
         let active_tab = app.global::<GuiState>().get_active_tab();
         let current_model = active_tab.get_tool_model(&app);
+        let preferred_paths = PreferredPaths::new(app.global::<Settings>().get_preferred_paths().split(',').map(str::to_string).collect());
 
         let (checked_items, unchecked_items, new_model) = match select_mode {
             SelectMode::SelectAll => select_all(&current_model),
             SelectMode::UnselectAll => deselect_all(&current_model),
             SelectMode::InvertSelection => invert_selection(&current_model),
             SelectMode::InvertSelectionInGroup => invert_selection_in_group(&current_model),
-            SelectMode::SelectTheBiggestSize => select_by_property(&current_model, active_tab, Property::Size, true),
-            SelectMode::SelectTheSmallestSize => select_by_property(&current_model, active_tab, Property::Size, false),
-            SelectMode::SelectTheBiggestResolution => select_by_property(&current_model, active_tab, Property::Resolution, true),
-            SelectMode::SelectTheSmallestResolution => select_by_property(&current_model, active_tab, Property::Resolution, false),
-            SelectMode::SelectNewest => select_by_property(&current_model, active_tab, Property::Date, true),
-            SelectMode::SelectOldest => select_by_property(&current_model, active_tab, Property::Date, false),
-            SelectMode::SelectShortestPath => select_by_property(&current_model, active_tab, Property::PathLength, false),
-            SelectMode::SelectLongestPath => select_by_property(&current_model, active_tab, Property::PathLength, true),
-            SelectMode::SelectAllExceptBiggestSize => select_all_except_by_property(&current_model, active_tab, Property::Size, true),
-            SelectMode::SelectAllExceptSmallestSize => select_all_except_by_property(&current_model, active_tab, Property::Size, false),
-            SelectMode::SelectAllExceptBiggestResolution => select_all_except_by_property(&current_model, active_tab, Property::Resolution, true),
-            SelectMode::SelectAllExceptSmallestResolution => select_all_except_by_property(&current_model, active_tab, Property::Resolution, false),
-            SelectMode::SelectAllExceptNewest => select_all_except_by_property(&current_model, active_tab, Property::Date, true),
-            SelectMode::SelectAllExceptOldest => select_all_except_by_property(&current_model, active_tab, Property::Date, false),
-            SelectMode::SelectAllExceptLongestPath => select_all_except_by_property(&current_model, active_tab, Property::PathLength, true),
-            SelectMode::SelectAllExceptShortestPath => select_all_except_by_property(&current_model, active_tab, Property::PathLength, false),
+            SelectMode::SelectTheBiggestSize => select_by_property(&current_model, active_tab, &preferred_paths, Property::Size, true),
+            SelectMode::SelectTheSmallestSize => select_by_property(&current_model, active_tab, &preferred_paths, Property::Size, false),
+            SelectMode::SelectTheBiggestResolution => select_by_property(&current_model, active_tab, &preferred_paths, Property::Resolution, true),
+            SelectMode::SelectTheSmallestResolution => select_by_property(&current_model, active_tab, &preferred_paths, Property::Resolution, false),
+            SelectMode::SelectNewest => select_by_property(&current_model, active_tab, &preferred_paths, Property::Date, true),
+            SelectMode::SelectOldest => select_by_property(&current_model, active_tab, &preferred_paths, Property::Date, false),
+            SelectMode::SelectShortestPath => select_by_property(&current_model, active_tab, &preferred_paths, Property::PathLength, false),
+            SelectMode::SelectLongestPath => select_by_property(&current_model, active_tab, &preferred_paths, Property::PathLength, true),
+            SelectMode::SelectAllExceptBiggestSize => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::Size, true),
+            SelectMode::SelectAllExceptSmallestSize => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::Size, false),
+            SelectMode::SelectAllExceptBiggestResolution => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::Resolution, true),
+            SelectMode::SelectAllExceptSmallestResolution => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::Resolution, false),
+            SelectMode::SelectAllExceptNewest => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::Date, true),
+            SelectMode::SelectAllExceptOldest => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::Date, false),
+            SelectMode::SelectAllExceptLongestPath => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::PathLength, true),
+            SelectMode::SelectAllExceptShortestPath => select_all_except_by_property(&current_model, active_tab, &preferred_paths, Property::PathLength, false),
+            SelectMode::SelectByRule => {
+                let rule = app.global::<Settings>().get_selection_rule();
+                match SelectionRule::parse(rule.as_str()).and_then(|rule| rule_select::select_by_rule(&current_model, active_tab, &rule)) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        app.global::<GuiState>().set_info_text(flk!("rust_cannot_use_selection_rule", error = e).into());
+                        return;
+                    }
+                }
+            }
 
             SelectMode::SelectCustom => return,
         };
@@ -178,6 +193,7 @@ pub(crate) fn set_select_buttons(app: &MainWindow) {
             (SelectMode::SelectAllExceptBiggestSize, settings.get_select_show_except_biggest_size()),
             (SelectMode::SelectAllExceptShortestPath, settings.get_select_show_except_shortest_path()),
             (SelectMode::SelectAllExceptLongestPath, settings.get_select_show_except_longest_path()),
+            (SelectMode::SelectByRule, !settings.get_selection_rule().is_empty()),
         ],
         ActiveTab::SimilarImages => vec![
             (SelectMode::InvertSelectionInGroup, true),
@@ -197,6 +213,7 @@ pub(crate) fn set_select_buttons(app: &MainWindow) {
             (SelectMode::SelectAllExceptBiggestResolution, settings.get_select_show_except_biggest_resolution()),
             (SelectMode::SelectAllExceptShortestPath, settings.get_select_show_except_shortest_path()),
             (SelectMode::SelectAllExceptLongestPath, settings.get_select_show_except_longest_path()),
+            (SelectMode::SelectByRule, !settings.get_selection_rule().is_empty()),
         ],
         ActiveTab::EmptyFolders
         | ActiveTab::BigFiles
@@ -250,7 +267,18 @@ fn extract_comparable_field(model: &SingleMainListModel, property: Property, act
     }
 }
 
-fn select_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTab, property: Property, select_max: bool) -> SelectionResult {
+// Lower rank means that item is located in more preferred path and should be kept
+fn extract_preferred_rank(model: &SingleMainListModel, active_tab: ActiveTab, preferred_paths: &PreferredPaths) -> usize {
+    if preferred_paths.is_empty() {
+        return 0;
+    }
+    let path = model.val_str.iter().nth(active_tab.get_str_path_idx()).expect("can find file path property");
+    let name = model.val_str.iter().nth(active_tab.get_str_name_idx()).expect("can find file name property");
+    preferred_paths.rank(&Path::new(path.as_str()).join(name.as_str()))
+}
+
+// Selects in each group one item from the least preferred path, ties are broken by the extreme property value.
+fn select_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTab, preferred_paths: &PreferredPaths, property: Property, select_max: bool) -> SelectionResult {
     let mut checked_items = 0;
 
     let is_header_mode = active_tab.get_is_header_mode();
@@ -261,12 +289,15 @@ fn select_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTa
     if select_max {
         for i in 0..(headers_idx.len() - 1) {
             let mut max_item = 0;
+            let mut max_item_rank = 0;
             let mut max_item_idx = 1;
             #[expect(clippy::needless_range_loop)]
             for j in (headers_idx[i] + 1)..headers_idx[i + 1] {
                 let item = extract_comparable_field(&old_data[j], property, active_tab);
-                if item > max_item {
+                let rank = extract_preferred_rank(&old_data[j], active_tab, preferred_paths);
+                if rank > max_item_rank || rank == max_item_rank && item > max_item {
                     max_item = item;
+                    max_item_rank = rank;
                     max_item_idx = j;
                 }
             }
@@ -278,12 +309,15 @@ fn select_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTa
     } else {
         for i in 0..(headers_idx.len() - 1) {
             let mut min_item = u64::MAX;
+            let mut min_item_rank = 0;
             let mut min_item_idx = 1;
             #[expect(clippy::needless_range_loop)]
             for j in (headers_idx[i] + 1)..headers_idx[i + 1] {
                 let item = extract_comparable_field(&old_data[j], property, active_tab);
-                if item < min_item {
+                let rank = extract_preferred_rank(&old_data[j], active_tab, preferred_paths);
+                if rank > min_item_rank || rank == min_item_rank && item < min_item {
                     min_item = item;
+                    min_item_rank = rank;
                     min_item_idx = j;
                 }
             }
@@ -300,7 +334,14 @@ fn select_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTa
 // Selects all items in each group EXCEPT the one with the extreme property value.
 // `spare_max: true`  → spares the biggest/newest/longest item (selects all others).
 // `spare_max: false` → spares the smallest/oldest/shortest item (selects all others).
-fn select_all_except_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTab, property: Property, spare_max: bool) -> SelectionResult {
+// When preferred paths are set, item from the most preferred path is spared and property only breaks ties.
+fn select_all_except_by_property(
+    model: &ModelRc<SingleMainListModel>,
+    active_tab: ActiveTab,
+    preferred_paths: &PreferredPaths,
+    property: Property,
+    spare_max: bool,
+) -> SelectionResult {
     let mut checked_items = 0;
     let mut unchecked_items = 0;
 
@@ -319,11 +360,14 @@ fn select_all_except_by_property(model: &ModelRc<SingleMainListModel>, active_ta
 
         // Find the extreme item to spare.
         let mut extreme_val = if spare_max { 0u64 } else { u64::MAX };
+        let mut extreme_rank = usize::MAX;
         let mut extreme_idx = group_start;
         for j in group_start..group_end {
             let val = extract_comparable_field(&old_data[j], property, active_tab);
-            if spare_max && val > extreme_val || !spare_max && val < extreme_val {
+            let rank = extract_preferred_rank(&old_data[j], active_tab, preferred_paths);
+            if rank < extreme_rank || rank == extreme_rank && (spare_max && val > extreme_val || !spare_max && val < extreme_val) {
                 extreme_val = val;
+                extreme_rank = rank;
                 extreme_idx = j;
             }
         }
@@ -608,7 +652,7 @@ mod tests {
         let items = vec![header, make_item_with_size(100), make_item_with_size(200), make_item_with_size(300)];
         let model = create_model_from_model_vec(&items);
 
-        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::Size, true);
+        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::Size, true);
 
         assert_eq!(checked_items, 2);
         assert_eq!(unchecked_items, 0);
@@ -625,7 +669,7 @@ mod tests {
         let items = vec![header, make_item_with_size(100), make_item_with_size(200), make_item_with_size(300)];
         let model = create_model_from_model_vec(&items);
 
-        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::Size, false);
+        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::Size, false);
 
         assert_eq!(checked_items, 2);
         assert_eq!(unchecked_items, 0);
@@ -652,7 +696,7 @@ mod tests {
         ];
         let model = create_model_from_model_vec(&items);
 
-        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::Size, true);
+        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::Size, true);
 
         assert!(new_model.row_data(1).unwrap().checked); // 100 - selected
         assert!(!new_model.row_data(2).unwrap().checked); // 300 - spared (biggest in group 1)
@@ -673,7 +717,7 @@ mod tests {
         let items = vec![header, item_small, item_medium, item_large];
         let model = create_model_from_model_vec(&items);
 
-        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::Size, true);
+        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::Size, true);
 
         // item_small was already checked → not counted as newly checked
         // item_medium was already checked → not counted as newly checked
@@ -699,7 +743,7 @@ mod tests {
         let items = vec![header, item_small, item_medium, item_large];
         let model = create_model_from_model_vec(&items);
 
-        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::Size, true);
+        let (checked_items, unchecked_items, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::Size, true);
 
         assert_eq!(checked_items, 2); // small and medium go from unchecked → checked
         assert_eq!(unchecked_items, 1); // large goes from checked → unchecked (spared)
@@ -725,7 +769,7 @@ mod tests {
         ];
         let model = create_model_from_model_vec(&items);
 
-        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::PathLength, true);
+        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::PathLength, true);
 
         assert!(new_model.row_data(1).unwrap().checked); // short - selected
         assert!(new_model.row_data(2).unwrap().checked); // medium - selected
@@ -745,7 +789,7 @@ mod tests {
         ];
         let model = create_model_from_model_vec(&items);
 
-        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::PathLength, false);
+        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::PathLength, false);
 
         assert!(!new_model.row_data(1).unwrap().checked); // short - spared (shortest)
         assert!(new_model.row_data(2).unwrap().checked); // medium - selected
@@ -766,7 +810,7 @@ mod tests {
         ];
         let model = create_model_from_model_vec(&items);
 
-        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::PathLength, false);
+        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::PathLength, false);
 
         assert!(!new_model.row_data(1).unwrap().checked); // IMG_0001.JPG - spared (shortest)
         assert!(new_model.row_data(2).unwrap().checked);
@@ -789,9 +833,34 @@ mod tests {
         ];
         let model = create_model_from_model_vec(&items);
 
-        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, Property::PathLength, false);
+        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &PreferredPaths::default(), Property::PathLength, false);
 
         assert!(!new_model.row_data(1).unwrap().checked); // shorter dir - spared
         assert!(new_model.row_data(2).unwrap().checked); // longer dir - selected
     }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn select_all_except_spares_item_from_preferred_path() {
+        let mut header = crate::test_common::get_main_list_model();
+        header.header_row = true;
+        let items = vec![
+            header,
+            make_item_with_path("/a", "x.jpg"),
+            make_item_with_path("/photos/originals", "x.jpg"),
+            make_item_with_path("/backup", "x.jpg"),
+        ];
+        let model = create_model_from_model_vec(&items);
+        let preferred_paths = PreferredPaths::new(vec!["/photos/originals".to_string(), "/backup".to_string()]);
+
+        let (_checked, _unchecked, new_model) = select_all_except_by_property(&model, ActiveTab::DuplicateFiles, &preferred_paths, Property::PathLength, false);
+        assert!(new_model.row_data(1).unwrap().checked);
+        assert!(!new_model.row_data(2).unwrap().checked); // preferred path wins over shorter path
+        assert!(new_model.row_data(3).unwrap().checked);
+
+        let (_checked, _unchecked, new_model) = select_by_property(&model, ActiveTab::DuplicateFiles, &preferred_paths, Property::PathLength, true);
+        assert!(new_model.row_data(1).unwrap().checked); // not preferred path is selected, even if shorter
+        assert!(!new_model.row_data(2).unwrap().checked);
+        assert!(!new_model.row_data(3).unwrap().checked);
+    }
 }
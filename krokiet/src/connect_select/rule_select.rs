@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use czkawka_core::common::select_rules::{RuleValue, SelectionRule};
+use czkawka_core::common::traits::ResultEntry;
+use slint::{Model, ModelRc, VecModel};
+
+use super::SelectionResult;
+use crate::common::{IntDataSimilarMusic, IntDataSimilarVideos, StrDataSimilarMusic, StrDataSimilarVideos, connect_i32_into_u64};
+use crate::{ActiveTab, SingleMainListModel};
+
+// Row converted to entry accepted by rules from czkawka_core, so the same rule selects the same files as in CLI
+struct RuleRow {
+    path: PathBuf,
+    size: u64,
+    modified_date: u64,
+    fields: Vec<(&'static str, RuleValue)>,
+}
+
+impl ResultEntry for RuleRow {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+    fn get_rule_field(&self, name: &str) -> Option<RuleValue> {
+        self.fields.iter().find(|(field_name, _)| *field_name == name).map(|(_, value)| value.clone())
+    }
+}
+
+fn rule_row(model: &SingleMainListModel, active_tab: ActiveTab) -> RuleRow {
+    let val_str = model.val_str.iter().collect::<Vec<_>>();
+    let val_int = model.val_int.iter().collect::<Vec<_>>();
+    let str_field = |idx: usize| RuleValue::Text(val_str[idx].to_string());
+    let int_field = |idx: usize| RuleValue::Number(f64::from(val_int[idx]));
+    let int_pair = |idx: usize| connect_i32_into_u64(val_int[idx], val_int[idx + 1]);
+
+    let fields = match active_tab {
+        ActiveTab::SimilarImages => vec![("width", int_field(active_tab.get_int_width_idx())), ("height", int_field(active_tab.get_int_height_idx()))],
+        ActiveTab::SimilarMusic => vec![
+            ("title", str_field(StrDataSimilarMusic::Title as usize)),
+            ("artist", str_field(StrDataSimilarMusic::Artist as usize)),
+            ("year", str_field(StrDataSimilarMusic::Year as usize)),
+            ("genre", str_field(StrDataSimilarMusic::Genre as usize)),
+            ("bitrate", int_field(IntDataSimilarMusic::Bitrate as usize)),
+            ("length", int_field(IntDataSimilarMusic::Length as usize)),
+        ],
+        ActiveTab::SimilarVideos => vec![
+            ("codec", str_field(StrDataSimilarVideos::Codec as usize)),
+            ("bitrate", RuleValue::Number(int_pair(IntDataSimilarVideos::BitratePart1 as usize) as f64)),
+            // Duration is stored in hundredths of second
+            ("duration", RuleValue::Number(f64::from(val_int[IntDataSimilarVideos::Duration as usize]) / 100.0)),
+        ],
+        _ => Vec::new(),
+    };
+
+    RuleRow {
+        path: Path::new(val_str[active_tab.get_str_path_idx()].as_str()).join(val_str[active_tab.get_str_name_idx()].as_str()),
+        size: int_pair(active_tab.get_int_size_idx()),
+        modified_date: int_pair(active_tab.get_int_modification_date_idx()),
+        fields,
+    }
+}
+
+// Header row filled with data contains reference file, which takes part in comparisons, but is never selected
+pub(super) fn select_by_rule(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTab, rule: &SelectionRule) -> Result<SelectionResult, String> {
+    let mut checked_items = 0;
+    let mut unchecked_items = 0;
+    let mut old_data = model.iter().collect::<Vec<_>>();
+
+    let mut group_start = 0;
+    while group_start < old_data.len() {
+        let group_end = old_data[group_start + 1..].iter().position(|e| e.header_row).map_or(old_data.len(), |idx| idx + group_start + 1);
+        let header = &old_data[group_start];
+        let has_reference = header.header_row && header.filled_header_row;
+        let items_start = if header.header_row { group_start + 1 } else { group_start };
+
+        let mut group = Vec::new();
+        if has_reference {
+            group.push(rule_row(header, active_tab));
+        }
+        group.extend(old_data[items_start..group_end].iter().map(|e| rule_row(e, active_tab)));
+
+        let mut selected = rule.select_group(&group, !has_reference)?;
+        if has_reference {
+            selected.remove(0);
+        }
+        for (item, selected) in old_data[items_start..group_end].iter_mut().zip(selected) {
+            if item.checked != selected {
+                if selected {
+                    checked_items += 1;
+                } else {
+                    unchecked_items += 1;
+                }
+            }
+            item.checked = selected;
+        }
+        group_start = group_end;
+    }
+
+    Ok((checked_items, unchecked_items, ModelRc::new(VecModel::from(old_data))))
+}
+
+#[cfg(test)]
+mod tests {
+    use slint::{Model, ModelRc, SharedString, VecModel};
+
+    use super::*;
+    use crate::common::{MAX_INT_DATA_DUPLICATE_FILES, create_model_from_model_vec, split_u64_into_i32s};
+
+    fn make_item(path: &str, name: &str, size: u64) -> SingleMainListModel {
+        let (part1, part2) = split_u64_into_i32s(size);
+        let ints: [i32; MAX_INT_DATA_DUPLICATE_FILES] = [0, 0, part1, part2];
+        let strs = [SharedString::from(""), SharedString::from(name), SharedString::from(path), SharedString::from("")];
+        SingleMainListModel {
+            val_int: ModelRc::new(VecModel::from(ints.to_vec())),
+            val_str: ModelRc::new(VecModel::from(strs.to_vec())),
+            ..crate::test_common::get_main_list_model()
+        }
+    }
+
+    #[test]
+    fn select_by_rule_uses_core_rules() {
+        let mut header = crate::test_common::get_main_list_model();
+        header.header_row = true;
+        let items = vec![
+            header.clone(),
+            make_item("/photos/raw", "a.jpg", 100),
+            make_item("/photos/export", "a.jpg", 200),
+            header,
+            make_item("/backup", "b.jpg", 100),
+            make_item("/other", "b.jpg", 100),
+        ];
+        let model = create_model_from_model_vec(&items);
+
+        let rule = SelectionRule::parse("keep where path ~ \"/raw/\"").unwrap();
+        let (checked, unchecked, new_model) = select_by_rule(&model, ActiveTab::DuplicateFiles, &rule).unwrap();
+        assert_eq!((checked, unchecked), (1, 0));
+        assert!(new_model.row_data(2).unwrap().checked);
+        // Rule would select whole second group, so it is left untouched
+        assert!(!new_model.row_data(4).unwrap().checked && !new_model.row_data(5).unwrap().checked);
+
+        let rule = SelectionRule::parse("delete where folder == \"/other\"").unwrap();
+        let (checked, _unchecked, new_model) = select_by_rule(&model, ActiveTab::DuplicateFiles, &rule).unwrap();
+        assert_eq!(checked, 1);
+        assert!(new_model.row_data(5).unwrap().checked);
+
+        let rule = SelectionRule::parse("keep where width max").unwrap();
+        assert!(matches!(select_by_rule(&model, ActiveTab::DuplicateFiles, &rule), Err(e) if e.contains("Unknown field")));
+    }
+}
@@ -407,13 +407,26 @@ fn translate_items(app: &MainWindow) {
     translation.set_settings_excluded_items_text(flk!("settings_excluded_items").into());
     translation.set_settings_allowed_extensions_text(flk!("settings_allowed_extensions").into());
     translation.set_settings_excluded_extensions_text(flk!("settings_excluded_extensions").into());
+    translation.set_settings_preferred_paths_text(flk!("settings_preferred_paths").into());
     translation.set_settings_file_size_text(flk!("settings_file_size").into());
     translation.set_settings_minimum_file_size_text(flk!("settings_minimum_file_size").into());
     translation.set_settings_maximum_file_size_text(flk!("settings_maximum_file_size").into());
+    translation.set_settings_modified_date_text(flk!("settings_modified_date").into());
+    translation.set_settings_created_date_text(flk!("settings_created_date").into());
+    translation.set_settings_accessed_date_text(flk!("settings_accessed_date").into());
+    translation.set_settings_date_after_text(flk!("settings_date_after").into());
+    translation.set_settings_date_before_text(flk!("settings_date_before").into());
+    translation.set_settings_date_hint_text(flk!("settings_date_hint").into());
     translation.set_settings_recursive_search_text(flk!("settings_recursive_search").into());
     translation.set_settings_use_cache_text(flk!("settings_use_cache").into());
     translation.set_settings_save_as_json_text(flk!("settings_save_as_json").into());
     translation.set_settings_ignore_other_filesystems_text(flk!("settings_ignore_other_filesystems").into());
+    translation.set_settings_use_database_cache_text(flk!("settings_use_database_cache").into());
+    translation.set_settings_use_database_cache_hint_text(flk!("settings_use_database_cache_hint").into());
+    translation.set_settings_use_dir_snapshot_text(flk!("settings_use_dir_snapshot").into());
+    translation.set_settings_use_dir_snapshot_hint_text(flk!("settings_use_dir_snapshot_hint").into());
+    translation.set_settings_respect_gitignore_text(flk!("settings_respect_gitignore").into());
+    translation.set_settings_respect_gitignore_hint_text(flk!("settings_respect_gitignore_hint").into());
     translation.set_settings_thread_number_text(flk!("settings_thread_number").into());
     translation.set_settings_restart_required_text(flk!("settings_restart_required").into());
     translation.set_settings_duplicate_image_preview_text(flk!("settings_duplicate_image_preview").into());
@@ -490,6 +503,10 @@ fn translate_items(app: &MainWindow) {
     translation.set_stop_text(flk!("stop_text").into());
     translation.set_settings_cache_header_text(flk!("settings_cache_header_text").into());
     translation.set_settings_select_header_text(flk!("settings_select_header_text").into());
+    translation.set_settings_selection_rule_text(flk!("settings_selection_rule_text").into());
+    translation.set_settings_selection_rule_hint_text(flk!("settings_selection_rule_hint_text").into());
+    translation.set_settings_journal_path_text(flk!("settings_journal_path_text").into());
+    translation.set_settings_journal_path_hint_text(flk!("settings_journal_path_hint_text").into());
     translation.set_settings_select_group_size_text(flk!("settings_select_group_size_text").into());
     translation.set_settings_select_group_date_text(flk!("settings_select_group_date_text").into());
     translation.set_settings_select_group_resolution_text(flk!("settings_select_group_resolution_text").into());
@@ -669,6 +686,7 @@ pub(crate) fn translate_select_mode(select_mode: SelectMode) -> SharedString {
         SelectMode::SelectAllExceptOldest => flk!("selection_all_except_oldest").into(),
         SelectMode::SelectAllExceptLongestPath => flk!("selection_all_except_longest_path").into(),
         SelectMode::SelectAllExceptShortestPath => flk!("selection_all_except_shortest_path").into(),
+        SelectMode::SelectByRule => flk!("selection_by_rule").into(),
         SelectMode::SelectCustom => flk!("selection_custom_select_unselect").into(),
     }
 }
@@ -1,12 +1,14 @@
-use std::path::MAIN_SEPARATOR;
+use std::path::{MAIN_SEPARATOR, Path};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 
 use crossbeam_channel::Sender;
+use czkawka_core::common::journal::{Journal, JournalAction};
 use czkawka_core::common::progress_data::ProgressData;
 use slint::{ComponentHandle, Weak};
 
+use crate::file_actions::{open_journal, run_with_journal};
 use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
 use crate::simpler_model::{SimplerSingleMainListModel, ToSimplerVec};
 use crate::{ActiveTab, Callabler, GuiState, MainWindow};
@@ -21,8 +23,12 @@ pub(crate) fn connect_delete_button(app: &MainWindow, progress_sender: Sender<Pr
         let app = a.upgrade().expect("Failed to upgrade app :(");
         let active_tab = app.global::<GuiState>().get_active_tab();
 
+        let Ok(journal) = open_journal(&app) else {
+            return;
+        };
+
         let processor = ModelProcessor::new(active_tab);
-        processor.delete_selected_items(false, progress_sender, weak_app, stop_flag);
+        processor.delete_selected_items(false, progress_sender, weak_app, stop_flag, journal);
     });
 }
 
@@ -36,26 +42,37 @@ pub(crate) fn connect_trash_button(app: &MainWindow, progress_sender: Sender<Pro
         let app = a.upgrade().expect("Failed to upgrade app :(");
         let active_tab = app.global::<GuiState>().get_active_tab();
 
+        let Ok(journal) = open_journal(&app) else {
+            return;
+        };
+
         let processor = ModelProcessor::new(active_tab);
-        processor.delete_selected_items(true, progress_sender, weak_app, stop_flag);
+        processor.delete_selected_items(true, progress_sender, weak_app, stop_flag, journal);
     });
 }
 
 impl ModelProcessor {
-    fn delete_selected_items(self, remove_to_trash: bool, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>) {
+    fn delete_selected_items(
+        self,
+        remove_to_trash: bool,
+        progress_sender: Sender<ProgressData>,
+        weak_app: Weak<MainWindow>,
+        stop_flag: Arc<AtomicBool>,
+        journal: Option<Arc<Journal>>,
+    ) {
         let is_empty_folder_tab = self.active_tab == ActiveTab::EmptyFolders;
         let model = self.active_tab.get_tool_model(&weak_app.upgrade().expect("Failed to upgrade app :("));
         let simpler_model = model.to_simpler_enumerated_vec();
         thread::spawn(move || {
             let path_idx = self.active_tab.get_str_path_idx();
             let name_idx = self.active_tab.get_str_name_idx();
+            let journal_action = if remove_to_trash { JournalAction::Trash } else { JournalAction::Delete };
 
             let dlt_fnc = move |data: &SimplerSingleMainListModel| {
-                remove_single_item(
-                    &format!("{}{MAIN_SEPARATOR}{}", data.val_str[path_idx], data.val_str[name_idx]),
-                    is_empty_folder_tab,
-                    remove_to_trash,
-                )
+                let full_path = format!("{}{MAIN_SEPARATOR}{}", data.val_str[path_idx], data.val_str[name_idx]);
+                run_with_journal(journal.as_deref(), journal_action, Path::new(&full_path), None, || {
+                    remove_single_item(&full_path, is_empty_folder_tab, remove_to_trash)
+                })
             };
 
             self.process_and_update_gui_state(
@@ -5,9 +5,11 @@ use std::sync::{Arc, Mutex};
 use std::{fs, path, thread};
 
 use crossbeam_channel::Sender;
+use czkawka_core::common::journal::{Journal, JournalAction};
 use czkawka_core::common::progress_data::ProgressData;
 use slint::{ComponentHandle, Weak};
 
+use crate::file_actions::{open_journal, run_with_journal};
 use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
 use crate::simpler_model::{SimplerSingleMainListModel, ToSimplerVec};
 use crate::{Callabler, GuiState, MainWindow, Settings, flk};
@@ -25,13 +27,32 @@ pub(crate) fn connect_move(app: &MainWindow, progress_sender: Sender<ProgressDat
         let preserve_structure = app.global::<Settings>().get_popup_move_preserve_folder_structure();
         let copy_mode = app.global::<Settings>().get_popup_move_copy_mode();
         let rename_on_conflict = app.global::<Settings>().get_popup_move_rename_on_conflict();
+        // Copying doesn't change original files, so there is nothing to revert
+        let journal = if copy_mode {
+            None
+        } else {
+            let Ok(journal) = open_journal(&app) else {
+                return;
+            };
+            journal
+        };
 
         let processor = ModelProcessor::new(active_tab);
-        processor.move_selected_items(progress_sender, weak_app, stop_flag, preserve_structure, copy_mode, rename_on_conflict, &output_folder);
+        processor.move_selected_items(
+            progress_sender,
+            weak_app,
+            stop_flag,
+            preserve_structure,
+            copy_mode,
+            rename_on_conflict,
+            &output_folder,
+            journal,
+        );
     });
 }
 
 impl ModelProcessor {
+    #[expect(clippy::too_many_arguments)]
     fn move_selected_items(
         self,
         progress_sender: Sender<ProgressData>,
@@ -41,6 +62,7 @@ impl ModelProcessor {
         copy_mode: bool,
         rename_on_conflict: bool,
         output_folder: &str,
+        journal: Option<Arc<Journal>>,
     ) {
         if let Err(err) = fs::create_dir_all(output_folder) {
             let app = weak_app.upgrade().expect("Failed to upgrade app :(");
@@ -58,7 +80,17 @@ impl ModelProcessor {
 
             let claimed_paths = Mutex::new(HashSet::new());
             let mlt_fnc = move |data: &SimplerSingleMainListModel| {
-                move_single_item(data, path_idx, name_idx, &output_folder, preserve_structure, copy_mode, rename_on_conflict, &claimed_paths)
+                move_single_item(
+                    data,
+                    path_idx,
+                    name_idx,
+                    &output_folder,
+                    preserve_structure,
+                    copy_mode,
+                    rename_on_conflict,
+                    &claimed_paths,
+                    journal.as_deref(),
+                )
             };
 
             self.process_and_update_gui_state(
@@ -84,6 +116,7 @@ fn move_single_item(
     copy_mode: bool,
     rename_on_conflict: bool,
     claimed_paths: &Mutex<HashSet<PathBuf>>,
+    journal: Option<&Journal>,
 ) -> Result<(), String> {
     let path = &data.val_str[path_idx];
     let name = &data.val_str[name_idx];
@@ -105,25 +138,29 @@ fn move_single_item(
     if copy_mode {
         try_to_copy_item(&input_file, &output_file)
     } else {
-        // Try to rename file, may fail due various reasons
-        // It is the easiest way to move file, but only on same partition
-        if fs::rename(&input_file, &output_file).is_ok() {
-            return Ok(());
-        }
+        run_with_journal(journal, JournalAction::Move, &input_file, Some(&output_file), || move_item(&input_file, &output_file))
+    }
+}
+
+fn move_item(input_file: &Path, output_file: &Path) -> Result<(), String> {
+    // Try to rename file, may fail due various reasons
+    // It is the easiest way to move file, but only on same partition
+    if fs::rename(input_file, output_file).is_ok() {
+        return Ok(());
+    }
 
-        // It is possible that this failed, because file is on different partition, so
-        // we need to copy file and then remove old
-        try_to_copy_item(&input_file, &output_file)?;
+    // It is possible that this failed, because file is on different partition, so
+    // we need to copy file and then remove old
+    try_to_copy_item(input_file, output_file)?;
 
-        if let Err(e) = fs::remove_file(&input_file) {
-            return Err(flk!(
-                "rust_error_removing_file_after_copy",
-                file = input_file.to_string_lossy().to_string(),
-                reason = e.to_string()
-            ));
-        }
-        Ok(())
+    if let Err(e) = fs::remove_file(input_file) {
+        return Err(flk!(
+            "rust_error_removing_file_after_copy",
+            file = input_file.to_string_lossy().to_string(),
+            reason = e.to_string()
+        ));
     }
+    Ok(())
 }
 
 // Tries to copy file/folder, and returns error if it fails
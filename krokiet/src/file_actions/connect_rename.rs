@@ -4,11 +4,13 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 
 use crossbeam_channel::Sender;
+use czkawka_core::common::journal::{Journal, JournalAction};
 use czkawka_core::common::progress_data::ProgressData;
 use log::{error, info, warn};
 use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel, Weak};
 
 use crate::common::StrDataBadNames;
+use crate::file_actions::{open_journal, run_with_journal};
 use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
 use crate::simpler_model::{SimplerSingleMainListModel, ToSimplerVec};
 use crate::{ActiveTab, Callabler, GuiState, MainWindow};
@@ -23,13 +25,17 @@ pub(crate) fn connect_rename(app: &MainWindow, progress_sender: Sender<ProgressD
         let app = a.upgrade().expect("Failed to upgrade app :(");
         let active_tab = app.global::<GuiState>().get_active_tab();
 
+        let Ok(journal) = open_journal(&app) else {
+            return;
+        };
+
         let processor = ModelProcessor::new(active_tab);
         match active_tab {
             ActiveTab::BadExtensions => {
-                processor.rename_bad_extensions(progress_sender, weak_app, stop_flag);
+                processor.rename_bad_extensions(progress_sender, weak_app, stop_flag, journal);
             }
             ActiveTab::BadNames => {
-                processor.rename_bad_file_names(progress_sender, weak_app, stop_flag);
+                processor.rename_bad_file_names(progress_sender, weak_app, stop_flag, journal);
             }
             _ => panic!("{active_tab:?} is not supported for renaming bad extensions/bad file names"),
         }
@@ -85,12 +91,12 @@ fn connect_rename_single_file(app: &MainWindow) {
             report_failure(&app, crate::flk!("rust_rename_single_target_exists"));
             return;
         }
-        if let Err(e) = std::fs::rename(&old_full_path, &new_full_path) {
-            error!("Failed to rename {old_full_path:?} to {new_full_path:?}: {e}");
-            report_failure(
-                &app,
-                crate::flk!("rust_failed_to_rename_file", old_path = old_full_path, new_path = new_full_path, error = e.to_string()),
-            );
+        let Ok(journal) = open_journal(&app) else {
+            return;
+        };
+        if let Err(e) = rename_item(journal.as_deref(), &old_full_path, &new_full_path) {
+            error!("{e}");
+            report_failure(&app, e);
             return;
         }
         info!("Renamed {old_full_path:?} to {new_full_path:?}");
@@ -142,6 +148,13 @@ fn is_valid_rename_target_name(new_name: &str) -> bool {
     !new_name.is_empty() && !new_name.contains('/') && !new_name.contains('\\')
 }
 
+fn rename_item(journal: Option<&Journal>, old_full_path: &str, new_full_path: &str) -> Result<(), String> {
+    run_with_journal(journal, JournalAction::Rename, Path::new(old_full_path), Some(Path::new(new_full_path)), || {
+        std::fs::rename(old_full_path, new_full_path)
+            .map_err(|e| crate::flk!("rust_failed_to_rename_file", old_path = old_full_path, new_path = new_full_path, error = e.to_string()))
+    })
+}
+
 fn build_full_path(folder: &str, name: &str) -> String {
     if folder.is_empty() {
         name.to_string()
@@ -151,7 +164,7 @@ fn build_full_path(folder: &str, name: &str) -> String {
 }
 
 impl ModelProcessor {
-    fn rename_bad_extensions(self, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>) {
+    fn rename_bad_extensions(self, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>, journal: Option<Arc<Journal>>) {
         let model = self.active_tab.get_tool_model(&weak_app.upgrade().expect("Failed to upgrade app :("));
         let simpler_model = model.to_simpler_enumerated_vec();
         thread::spawn(move || {
@@ -159,7 +172,7 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
             let ext_idx = self.active_tab.get_str_proper_extension();
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx);
+            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx, journal.as_deref());
 
             self.process_and_update_gui_state(
                 &weak_app,
@@ -172,7 +185,7 @@ impl ModelProcessor {
             );
         });
     }
-    fn rename_bad_file_names(self, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>) {
+    fn rename_bad_file_names(self, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>, journal: Option<Arc<Journal>>) {
         let model = self.active_tab.get_tool_model(&weak_app.upgrade().expect("Failed to upgrade app :("));
         let simpler_model = model.to_simpler_enumerated_vec();
         thread::spawn(move || {
@@ -180,7 +193,7 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
             let new_name_idx = StrDataBadNames::NewName as usize;
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_file_name_item(data, path_idx, name_idx, new_name_idx);
+            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_file_name_item(data, path_idx, name_idx, new_name_idx, journal.as_deref());
 
             self.process_and_update_gui_state(
                 &weak_app,
@@ -196,7 +209,7 @@ impl ModelProcessor {
 }
 
 #[cfg(not(test))]
-fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, new_file_name_idx: usize) -> Result<(), String> {
+fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, new_file_name_idx: usize, journal: Option<&Journal>) -> Result<(), String> {
     use std::path::MAIN_SEPARATOR;
     let folder = &data.val_str[path_idx];
     let file_name = &data.val_str[name_idx];
@@ -205,20 +218,11 @@ fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usi
     let new_full_path = format!("{folder}{MAIN_SEPARATOR}{new_file_name}");
     let old_full_path = format!("{folder}{MAIN_SEPARATOR}{file_name}");
 
-    if let Err(e) = std::fs::rename(&old_full_path, &new_full_path) {
-        Err(crate::flk!(
-            "rust_failed_to_rename_file",
-            old_path = old_full_path,
-            new_path = new_full_path,
-            error = e.to_string()
-        ))
-    } else {
-        Ok(())
-    }
+    rename_item(journal, &old_full_path, &new_full_path)
 }
 
 #[cfg(not(test))]
-fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, ext_idx: usize) -> Result<(), String> {
+fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, ext_idx: usize, journal: Option<&Journal>) -> Result<(), String> {
     use std::path::MAIN_SEPARATOR;
     let folder = &data.val_str[path_idx];
     let file_name = &data.val_str[name_idx];
@@ -228,20 +232,11 @@ fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usi
     let new_full_path = format!("{folder}{MAIN_SEPARATOR}{file_stem}.{new_extension}");
     let old_full_path = format!("{folder}{MAIN_SEPARATOR}{file_name}");
 
-    if let Err(e) = std::fs::rename(&old_full_path, &new_full_path) {
-        Err(crate::flk!(
-            "rust_failed_to_rename_file",
-            old_path = old_full_path,
-            new_path = new_full_path,
-            error = e.to_string()
-        ))
-    } else {
-        Ok(())
-    }
+    rename_item(journal, &old_full_path, &new_full_path)
 }
 
 #[cfg(test)]
-fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _ext_idx: usize) -> Result<(), String> {
+fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _ext_idx: usize, _journal: Option<&Journal>) -> Result<(), String> {
     let full_path = &data.val_str[path_idx];
     if full_path.contains("test_error") {
         return Err(format!("Test error for item: {full_path}"));
@@ -250,7 +245,7 @@ fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usi
 }
 
 #[cfg(test)]
-fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _file_name: usize) -> Result<(), String> {
+fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _file_name: usize, _journal: Option<&Journal>) -> Result<(), String> {
     let full_path = &data.val_str[path_idx];
     if full_path.contains("test_error") {
         return Err(format!("Test error for item: {full_path}"));
@@ -285,7 +280,7 @@ mod tests {
             let name_idx = 0;
             let ext_idx = 0;
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx);
+            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx, None);
 
             let output = Self::process_items(
                 simplified_model,
@@ -1,12 +1,14 @@
-use std::path::MAIN_SEPARATOR;
+use std::path::{MAIN_SEPARATOR, Path};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 
 use crossbeam_channel::Sender;
+use czkawka_core::common::journal::{Journal, JournalAction};
 use czkawka_core::common::progress_data::ProgressData;
 use slint::{ComponentHandle, Weak};
 
+use crate::file_actions::{open_journal, run_with_journal};
 use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
 use crate::simpler_model::{SimplerSingleMainListModel, ToSimplerVec};
 use crate::{Callabler, GuiState, MainWindow};
@@ -21,13 +23,17 @@ pub(crate) fn connect_symlink(app: &MainWindow, progress_sender: Sender<Progress
         let app = a.upgrade().expect("Failed to upgrade app :(");
         let active_tab = app.global::<GuiState>().get_active_tab();
 
+        let Ok(journal) = open_journal(&app) else {
+            return;
+        };
+
         let processor = ModelProcessor::new(active_tab);
-        processor.symlink_selected_items(progress_sender, weak_app, stop_flag);
+        processor.symlink_selected_items(progress_sender, weak_app, stop_flag, journal);
     });
 }
 
 impl ModelProcessor {
-    fn symlink_selected_items(self, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>) {
+    fn symlink_selected_items(self, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>, journal: Option<Arc<Journal>>) {
         let model = self.active_tab.get_tool_model(&weak_app.upgrade().expect("Failed to upgrade app :("));
         let simpler_model = model.to_simpler_enumerated_vec();
         thread::spawn(move || {
@@ -35,9 +41,14 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
 
             let symlink_fnc = move |original: &SimplerSingleMainListModel, derived: &SimplerSingleMainListModel| {
-                symlink_single_item(
-                    &format!("{}{MAIN_SEPARATOR}{}", original.val_str[path_idx], original.val_str[name_idx]),
-                    &format!("{}{MAIN_SEPARATOR}{}", derived.val_str[path_idx], derived.val_str[name_idx]),
+                let original_path = format!("{}{MAIN_SEPARATOR}{}", original.val_str[path_idx], original.val_str[name_idx]);
+                let derived_path = format!("{}{MAIN_SEPARATOR}{}", derived.val_str[path_idx], derived.val_str[name_idx]);
+                run_with_journal(
+                    journal.as_deref(),
+                    JournalAction::Symlink,
+                    Path::new(&derived_path),
+                    Some(Path::new(&original_path)),
+                    || symlink_single_item(&original_path, &derived_path),
                 )
             };
 
@@ -1,3 +1,12 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use czkawka_core::common::journal::{Journal, JournalAction};
+use log::error;
+use slint::ComponentHandle;
+
+use crate::{GuiState, MainWindow, Settings, flk};
+
 pub mod connect_clean_exif;
 pub mod connect_delete;
 pub mod connect_hardlink;
@@ -5,3 +14,34 @@ pub mod connect_move;
 pub mod connect_optimize_video;
 pub mod connect_rename;
 pub mod connect_symlink;
+
+// Without a working journal nothing is changed, because user explicitly asked to be able to revert changes
+fn open_journal(app: &MainWindow) -> Result<Option<Arc<Journal>>, ()> {
+    let journal_path = app.global::<Settings>().get_journal_path().trim().to_string();
+    if journal_path.is_empty() {
+        return Ok(None);
+    }
+    Journal::open(Path::new(&journal_path)).map(|journal| Some(Arc::new(journal))).map_err(|e| {
+        error!("{e}");
+        app.global::<GuiState>().set_info_text(flk!("rust_cannot_open_journal", error = e).into());
+    })
+}
+
+// Operation is saved to journal before it starts, so it is visible there even when app is killed in the middle
+fn run_with_journal(
+    journal: Option<&Journal>,
+    action: JournalAction,
+    path: &Path,
+    destination: Option<&Path>,
+    operation: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    let Some(journal) = journal else {
+        return operation();
+    };
+    let journal_entry = journal.start_for_path(action, path, destination)?;
+    let result = operation();
+    if let Err(e) = journal.finish(journal_entry, result.is_ok()) {
+        error!("{e}");
+    }
+    result
+}
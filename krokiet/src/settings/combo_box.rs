@@ -124,6 +124,9 @@ impl StringComboBoxItems {
             ("blake3", "Blake3", HashType::Blake3),
             ("crc32", "CRC32", HashType::Crc32),
             ("xxh3", "XXH3", HashType::Xxh3),
+            ("sha256", "SHA-256", HashType::Sha256),
+            ("sha1", "SHA-1", HashType::Sha1),
+            ("md5", "MD5", HashType::Md5),
         ]);
 
         let biggest_files_method = Self::convert_to_combobox_items_i18n(&[
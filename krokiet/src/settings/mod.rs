@@ -300,6 +300,8 @@ pub(crate) fn set_base_settings_to_gui(app: &MainWindow, basic_settings: &BasicS
     settings.set_select_show_except_biggest_resolution(basic_settings.select_show_except_biggest_resolution);
     settings.set_select_show_except_shortest_path(basic_settings.select_show_except_shortest_path);
     settings.set_select_show_except_longest_path(basic_settings.select_show_except_longest_path);
+    settings.set_selection_rule(basic_settings.selection_rule.clone().into());
+    settings.set_journal_path(basic_settings.journal_path.clone().into());
 
     set_combobox_basic_settings_items(&settings, basic_settings);
 }
@@ -421,11 +423,21 @@ pub(crate) fn set_settings_to_gui(app: &MainWindow, custom_settings: &SettingsCu
     settings.set_excluded_items(custom_settings.excluded_items.clone().into());
     settings.set_allowed_extensions(custom_settings.allowed_extensions.clone().into());
     settings.set_excluded_extensions(custom_settings.excluded_extensions.clone().into());
+    settings.set_preferred_paths(custom_settings.preferred_paths.clone().into());
+    settings.set_modified_after(custom_settings.modified_after.clone().into());
+    settings.set_modified_before(custom_settings.modified_before.clone().into());
+    settings.set_created_after(custom_settings.created_after.clone().into());
+    settings.set_created_before(custom_settings.created_before.clone().into());
+    settings.set_accessed_after(custom_settings.accessed_after.clone().into());
+    settings.set_accessed_before(custom_settings.accessed_before.clone().into());
     settings.set_minimum_file_size(custom_settings.minimum_file_size.to_string().into());
     settings.set_maximum_file_size(custom_settings.maximum_file_size.to_string().into());
     settings.set_use_cache(custom_settings.use_cache);
+    settings.set_use_dir_snapshot(custom_settings.use_dir_snapshot);
+    settings.set_use_database_cache(custom_settings.use_database_cache);
     settings.set_save_as_json(custom_settings.save_also_as_json);
     settings.set_ignore_other_filesystems(custom_settings.ignore_other_file_systems);
+    settings.set_respect_gitignore(custom_settings.respect_gitignore);
     settings.set_thread_number(custom_settings.thread_number as f32);
 
     settings.set_recursive_search(custom_settings.recursive_search);
@@ -651,13 +663,23 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
     let excluded_items = settings.get_excluded_items().to_string();
     let allowed_extensions = settings.get_allowed_extensions().to_string();
     let excluded_extensions = settings.get_excluded_extensions().to_string();
+    let preferred_paths = settings.get_preferred_paths().to_string();
+    let modified_after = settings.get_modified_after().to_string();
+    let modified_before = settings.get_modified_before().to_string();
+    let created_after = settings.get_created_after().to_string();
+    let created_before = settings.get_created_before().to_string();
+    let accessed_after = settings.get_accessed_after().to_string();
+    let accessed_before = settings.get_accessed_before().to_string();
     let minimum_file_size = settings.get_minimum_file_size().parse::<i32>().unwrap_or(DEFAULT_MINIMUM_SIZE_KB);
     let maximum_file_size = settings.get_maximum_file_size().parse::<i32>().unwrap_or(DEFAULT_MAXIMUM_SIZE_KB);
 
     let recursive_search = settings.get_recursive_search();
     let use_cache = settings.get_use_cache();
+    let use_dir_snapshot = settings.get_use_dir_snapshot();
+    let use_database_cache = settings.get_use_database_cache();
     let save_also_as_json = settings.get_save_as_json();
     let ignore_other_file_systems = settings.get_ignore_other_filesystems();
+    let respect_gitignore = settings.get_respect_gitignore();
     let thread_number = settings.get_thread_number().round() as i32;
 
     let duplicate_image_preview = settings.get_duplicate_image_preview();
@@ -813,12 +835,22 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
         excluded_items,
         allowed_extensions,
         excluded_extensions,
+        preferred_paths,
+        modified_after,
+        modified_before,
+        created_after,
+        created_before,
+        accessed_after,
+        accessed_before,
         minimum_file_size,
         maximum_file_size,
         recursive_search,
         use_cache,
+        use_dir_snapshot,
+        use_database_cache,
         save_also_as_json,
         ignore_other_file_systems,
+        respect_gitignore,
         thread_number,
         duplicate_image_preview,
         duplicate_use_prehash,
@@ -1018,5 +1050,7 @@ pub(crate) fn collect_base_settings(app: &MainWindow) -> BasicSettings {
         select_show_except_biggest_resolution: settings.get_select_show_except_biggest_resolution(),
         select_show_except_shortest_path: settings.get_select_show_except_shortest_path(),
         select_show_except_longest_path: settings.get_select_show_except_longest_path(),
+        selection_rule: settings.get_selection_rule().to_string(),
+        journal_path: settings.get_journal_path().to_string(),
     }
 }
@@ -55,6 +55,20 @@ pub struct SettingsCustom {
     pub allowed_extensions: String,
     #[serde(default)]
     pub excluded_extensions: String,
+    #[serde(default)]
+    pub preferred_paths: String,
+    #[serde(default)]
+    pub modified_after: String,
+    #[serde(default)]
+    pub modified_before: String,
+    #[serde(default)]
+    pub created_after: String,
+    #[serde(default)]
+    pub created_before: String,
+    #[serde(default)]
+    pub accessed_after: String,
+    #[serde(default)]
+    pub accessed_before: String,
     #[serde(default = "minimum_file_size")]
     pub minimum_file_size: i32,
     #[serde(default = "maximum_file_size")]
@@ -64,10 +78,16 @@ pub struct SettingsCustom {
     #[serde(default = "ttrue")]
     pub use_cache: bool,
     #[serde(default)]
+    pub use_dir_snapshot: bool,
+    #[serde(default)]
+    pub use_database_cache: bool,
+    #[serde(default)]
     pub save_also_as_json: bool,
     #[serde(default)]
     pub ignore_other_file_systems: bool,
     #[serde(default)]
+    pub respect_gitignore: bool,
+    #[serde(default)]
     pub thread_number: i32,
     #[serde(default = "ttrue")]
     pub duplicate_image_preview: bool,
@@ -373,6 +393,10 @@ pub struct BasicSettings {
     pub select_show_except_shortest_path: bool,
     #[serde(default = "ttrue")]
     pub select_show_except_longest_path: bool,
+    #[serde(default)]
+    pub selection_rule: String,
+    #[serde(default)]
+    pub journal_path: String,
 }
 
 impl Default for BasicSettings {